@@ -0,0 +1,159 @@
+use crate::Pos;
+
+pub(crate) struct Module {
+    pub(crate) name: String,
+    /// The name of the file the module was read from, for errors.
+    pub(crate) file: String,
+    pub(crate) tag_default: TagDefault,
+    /// Whether the module specifies `EXTENSIBILITY IMPLIED`, making all of
+    /// its `SEQUENCE`, `SET`, and `CHOICE` types extensible.
+    pub(crate) extensibility_implied: bool,
+    pub(crate) imports: Vec<Import>,
+    pub(crate) assignments: Vec<Assignment>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TagDefault {
+    Explicit,
+    Implicit,
+    Automatic,
+}
+
+pub(crate) struct Import {
+    pub(crate) symbols: Vec<String>,
+    pub(crate) module: String,
+    pub(crate) pos: Pos,
+}
+
+pub(crate) struct Assignment {
+    pub(crate) name: String,
+    pub(crate) pos: Pos,
+    pub(crate) kind: AssignmentKind,
+}
+
+pub(crate) enum AssignmentKind {
+    Type(Type),
+    Value(Type, Value),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Type {
+    pub(crate) kind: TypeKind,
+    /// A value range constraint, for `INTEGER`s. Other constraints are
+    /// ignored, since they don't affect the encoding.
+    pub(crate) range: Option<Range>,
+    pub(crate) pos: Pos,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum TypeKind {
+    Boolean,
+    Integer(Vec<NamedNumber>),
+    Enumerated(Vec<NamedNumber>),
+    Real,
+    BitString(Vec<NamedNumber>),
+    OctetString,
+    Null,
+    ObjectIdentifier,
+    RelativeOid,
+    /// A character string or time type, e.g. `UTF8String` or `UTCTime`.
+    Useful(String),
+    /// `ANY` or `ANY DEFINED BY`.
+    Any,
+    Sequence(Components),
+    Set(Components),
+    SequenceOf(Box<Type>),
+    SetOf(Box<Type>),
+    Choice(Components),
+    Tagged {
+        class: TagClass,
+        number: u32,
+        mode: TagMode,
+        inner: Box<Type>,
+    },
+    Reference {
+        module: Option<String>,
+        name: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TagClass {
+    Universal,
+    Application,
+    ContextSpecific,
+    Private,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TagMode {
+    /// Neither `EXPLICIT` nor `IMPLICIT` was given, so the module's default
+    /// applies.
+    Default,
+    Explicit,
+    Implicit,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Components {
+    pub(crate) items: Vec<Component>,
+    /// Whether there is an extension marker (`...`).
+    pub(crate) extensible: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Component {
+    Named {
+        name: String,
+        ty: Type,
+        presence: Presence,
+    },
+    /// `COMPONENTS OF Type`, which includes the components of another
+    /// `SEQUENCE` or `SET`.
+    ComponentsOf(Type),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Presence {
+    Required,
+    Optional,
+    Default(Value),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct NamedNumber {
+    pub(crate) name: String,
+    pub(crate) value: Value,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Range {
+    /// `None` for `MIN`.
+    pub(crate) lower: Option<Value>,
+    /// `None` for `MAX`.
+    pub(crate) upper: Option<Value>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Value {
+    Number(i128),
+    Boolean(bool),
+    Null,
+    String(String),
+    BString(String),
+    HString(String),
+    /// A reference to another value, or a named number.
+    Reference(String),
+    /// An `OBJECT IDENTIFIER` value, or a list of named bits.
+    Braced(Vec<BracedItem>),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum BracedItem {
+    Number(u128),
+    /// A name on its own, which is either a value reference or a well-known
+    /// arc name like `iso`.
+    Name(String),
+    /// A name and its number (or a value reference), e.g. `iso(1)`.
+    NameAndNumber(String, Value),
+}
@@ -0,0 +1,1048 @@
+use crate::ast::{
+    Assignment, AssignmentKind, BracedItem, Component, Components, Module, NamedNumber, Presence,
+    TagClass, TagDefault, TagMode, Type, TypeKind, Value,
+};
+use crate::{Error, Pos};
+use std::fmt::Write;
+
+// How deeply references may be followed, so that circular definitions are
+// reported rather than overflowing the stack.
+const MAX_REFERENCE_DEPTH: u32 = 64;
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+/// Converts an ASN.1 type reference, e.g. `Extension-Name`, to a Rust type
+/// name, e.g. `ExtensionName`.
+fn type_name(name: &str) -> String {
+    let mut result = String::new();
+    let mut upper = true;
+    for c in name.chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Converts an ASN.1 identifier, e.g. `subjectPublicKeyInfo`, to snake case,
+/// e.g. `subject_public_key_info`.
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut result = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '-' {
+            result.push('_');
+        } else if c.is_ascii_uppercase() {
+            let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+            let next = chars.get(i + 1);
+            let boundary = match prev {
+                Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit() => true,
+                Some(p) if p.is_ascii_uppercase() => {
+                    matches!(next, Some(n) if n.is_ascii_lowercase())
+                }
+                _ => false,
+            };
+            if boundary && !result.ends_with('_') {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn field_name(name: &str) -> String {
+    let name = snake_case(name);
+    if RUST_KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else if name == "self" || name == "super" || name == "crate" {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+fn const_name(name: &str) -> String {
+    snake_case(name).to_uppercase()
+}
+
+// Renders a value as it would appear in ASN.1, for comments.
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string(),
+        Value::Boolean(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Null => "NULL".to_string(),
+        Value::String(s) => format!("{:?}", s),
+        Value::BString(s) => format!("'{}'B", s),
+        Value::HString(s) => format!("'{}'H", s),
+        Value::Reference(s) => s.clone(),
+        Value::Braced(items) => {
+            let items = items
+                .iter()
+                .map(|item| match item {
+                    BracedItem::Number(n) => n.to_string(),
+                    BracedItem::Name(name) => name.clone(),
+                    BracedItem::NameAndNumber(name, value) => {
+                        format!("{}({})", name, describe_value(value))
+                    }
+                })
+                .collect::<Vec<_>>();
+            format!("{{ {} }}", items.join(" "))
+        }
+    }
+}
+
+// The names of the arcs which may be used without a number at the start of an
+// `OBJECT IDENTIFIER` value (X.660 Annex A).
+fn well_known_arc(parent: Option<u128>, name: &str) -> Option<u128> {
+    Some(match (parent, name) {
+        (None, "itu-t") | (None, "ccitt") => 0,
+        (None, "iso") => 1,
+        (None, "joint-iso-itu-t") | (None, "joint-iso-ccitt") => 2,
+        (Some(0), "recommendation") => 0,
+        (Some(0), "question") => 1,
+        (Some(0), "administration") => 2,
+        (Some(0), "network-operator") => 3,
+        (Some(0), "identified-organization") => 4,
+        (Some(1), "standard") => 0,
+        (Some(1), "registration-authority") => 1,
+        (Some(1), "member-body") => 2,
+        (Some(1), "identified-organization") => 3,
+        _ => return None,
+    })
+}
+
+/// A tag applied to a field or variant, as `#[explicit(n)]` or
+/// `#[implicit(n)]`.
+#[derive(Clone, Copy)]
+struct Tagging {
+    number: u32,
+    explicit: bool,
+}
+
+// A component of a `SEQUENCE`, `SET`, or `CHOICE`, along with the module it
+// was defined in (which differs for `COMPONENTS OF` another module's type).
+struct NamedComponent<'a, 't> {
+    module: &'a Module,
+    name: &'t str,
+    ty: Type,
+    presence: &'t Presence,
+}
+
+struct Generator<'a> {
+    modules: &'a [Module],
+    module: &'a Module,
+    // The generated items, in order.
+    items: Vec<String>,
+}
+
+impl<'a> Generator<'a> {
+    fn error(&self, module: &Module, pos: Pos, message: &str) -> Error {
+        Error::new(message).at(&module.file, pos)
+    }
+
+    fn find_module(&self, name: &str) -> Option<&'a Module> {
+        self.modules.iter().find(|m| m.name == name)
+    }
+
+    // Finds the assignment which `name` refers to in `module`, following
+    // imports.
+    fn resolve(
+        &self,
+        module: &'a Module,
+        name: &str,
+        pos: Pos,
+        depth: u32,
+    ) -> Result<(&'a Module, &'a Assignment), Error> {
+        if depth > MAX_REFERENCE_DEPTH {
+            return Err(self.error(module, pos, &format!("`{}` refers to itself", name)));
+        }
+        if let Some(assignment) = module.assignments.iter().find(|a| a.name == name) {
+            return Ok((module, assignment));
+        }
+        for import in &module.imports {
+            if import.symbols.iter().any(|s| s == name) {
+                let from = self.find_module(&import.module).ok_or_else(|| {
+                    self.error(
+                        module,
+                        import.pos,
+                        &format!("module `{}` was not found", import.module),
+                    )
+                })?;
+                return self.resolve(from, name, import.pos, depth + 1);
+            }
+        }
+        Err(self.error(module, pos, &format!("`{}` is not defined", name)))
+    }
+
+    // Finds the type assignment which a type reference refers to.
+    fn resolve_type(
+        &self,
+        module: &'a Module,
+        ty: &Type,
+        depth: u32,
+    ) -> Result<(&'a Module, &'a Assignment, &'a Type), Error> {
+        let (module_name, name) = match &ty.kind {
+            TypeKind::Reference { module, name } => (module, name),
+            _ => unreachable!(),
+        };
+        let module = match module_name {
+            Some(module_name) => self.find_module(module_name).ok_or_else(|| {
+                self.error(
+                    module,
+                    ty.pos,
+                    &format!("module `{}` was not found", module_name),
+                )
+            })?,
+            None => module,
+        };
+        let (module, assignment) = self.resolve(module, name, ty.pos, depth)?;
+        match &assignment.kind {
+            AssignmentKind::Type(ty) => Ok((module, assignment, ty)),
+            AssignmentKind::Value(..) => Err(self.error(
+                module,
+                ty.pos,
+                &format!("`{}` is a value, not a type", name),
+            )),
+        }
+    }
+
+    // Follows references and tags to the built-in type which `ty` is.
+    fn builtin_type<'t>(
+        &self,
+        module: &'a Module,
+        ty: &'t Type,
+        depth: u32,
+    ) -> Result<(&'a Module, &'t Type), Error>
+    where
+        'a: 't,
+    {
+        if depth > MAX_REFERENCE_DEPTH {
+            return Err(self.error(module, ty.pos, "type refers to itself"));
+        }
+        match &ty.kind {
+            TypeKind::Tagged { inner, .. } => self.builtin_type(module, inner, depth + 1),
+            TypeKind::Reference { .. } => {
+                let (module, _, ty) = self.resolve_type(module, ty, depth)?;
+                self.builtin_type(module, ty, depth + 1)
+            }
+            _ => Ok((module, ty)),
+        }
+    }
+
+    // Returns the outermost tag of `ty`, if it has one, as it applies to a
+    // field of that type.
+    fn tag_of(&self, module: &'a Module, ty: &Type, depth: u32) -> Result<Option<Tagging>, Error> {
+        if depth > MAX_REFERENCE_DEPTH {
+            return Err(self.error(module, ty.pos, "type refers to itself"));
+        }
+        match &ty.kind {
+            TypeKind::Tagged {
+                class,
+                number,
+                mode,
+                inner,
+            } => {
+                if *class != TagClass::ContextSpecific {
+                    return Err(self.error(
+                        module,
+                        ty.pos,
+                        "only context-specific tags are supported",
+                    ));
+                }
+                let mut explicit = match mode {
+                    TagMode::Explicit => true,
+                    TagMode::Implicit => false,
+                    TagMode::Default => module.tag_default == TagDefault::Explicit,
+                };
+                // `CHOICE` and `ANY` types are always tagged explicitly,
+                // since their own tag identifies the alternative.
+                let (_, builtin) = self.builtin_type(module, inner, depth + 1)?;
+                let inner_tagged = matches!(inner.kind, TypeKind::Tagged { .. })
+                    || self.tag_of(module, inner, depth + 1)?.is_some();
+                if !inner_tagged && matches!(builtin.kind, TypeKind::Choice(_) | TypeKind::Any) {
+                    if *mode == TagMode::Implicit {
+                        return Err(self.error(
+                            module,
+                            ty.pos,
+                            "CHOICE and ANY types can't be tagged implicitly",
+                        ));
+                    }
+                    explicit = true;
+                }
+                let inner_tag = self.tag_of(module, inner, depth + 1)?;
+                match inner_tag {
+                    None => Ok(Some(Tagging {
+                        number: *number,
+                        explicit,
+                    })),
+                    // An implicit tag replaces the tag of a tagged type.
+                    Some(inner_tag) if !explicit => Ok(Some(Tagging {
+                        number: *number,
+                        explicit: inner_tag.explicit,
+                    })),
+                    Some(_) => Err(self.error(
+                        module,
+                        ty.pos,
+                        "explicitly tagging a tagged type is not supported",
+                    )),
+                }
+            }
+            TypeKind::Reference { .. } => {
+                let (module, _, ty) = self.resolve_type(module, ty, depth)?;
+                self.tag_of(module, ty, depth + 1)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn integer_value(
+        &self,
+        module: &'a Module,
+        value: &Value,
+        named: &[NamedNumber],
+        pos: Pos,
+        depth: u32,
+    ) -> Result<i128, Error> {
+        match value {
+            Value::Number(n) => Ok(*n),
+            Value::Reference(name) => {
+                if let Some(n) = named.iter().find(|n| n.name == *name) {
+                    return self.integer_value(module, &n.value, &[], pos, depth + 1);
+                }
+                let (module, assignment) = self.resolve(module, name, pos, depth)?;
+                match &assignment.kind {
+                    AssignmentKind::Value(_, value) => {
+                        self.integer_value(module, value, &[], assignment.pos, depth + 1)
+                    }
+                    AssignmentKind::Type(_) => {
+                        Err(self.error(module, pos, &format!("`{}` is a type, not a value", name)))
+                    }
+                }
+            }
+            _ => Err(self.error(module, pos, "expected an integer value")),
+        }
+    }
+
+    // Chooses the Rust type of an `INTEGER`, from its value range constraint.
+    fn integer_type(
+        &self,
+        module: &'a Module,
+        ty: &Type,
+        named: &[NamedNumber],
+    ) -> Result<&'static str, Error> {
+        const TYPES: &[(&str, i128, i128)] = &[
+            ("u8", 0, u8::MAX as i128),
+            ("u32", 0, u32::MAX as i128),
+            ("u64", 0, u64::MAX as i128),
+            ("i8", i8::MIN as i128, i8::MAX as i128),
+            ("i32", i32::MIN as i128, i32::MAX as i128),
+            ("i64", i64::MIN as i128, i64::MAX as i128),
+        ];
+
+        if let Some(range) = &ty.range {
+            if let (Some(lower), Some(upper)) = (&range.lower, &range.upper) {
+                let lower = self.integer_value(module, lower, named, ty.pos, 0)?;
+                let upper = self.integer_value(module, upper, named, ty.pos, 0)?;
+                for (name, min, max) in TYPES {
+                    if *min <= lower && upper <= *max {
+                        return Ok(name);
+                    }
+                }
+                return Ok("asn1::OwnedBigInt");
+            }
+        }
+        // Named numbers suggest the values are small, and make defaults
+        // possible; otherwise the value may be of any size.
+        if named.is_empty() {
+            Ok("asn1::OwnedBigInt")
+        } else {
+            Ok("i64")
+        }
+    }
+
+    // Emits constants for the named numbers (or bits) of a type.
+    fn named_number_consts(
+        &mut self,
+        module: &'a Module,
+        prefix: &str,
+        rust_type: &str,
+        named: &[NamedNumber],
+        pos: Pos,
+    ) -> Result<(), Error> {
+        if named.is_empty() {
+            return Ok(());
+        }
+        let mut item = String::new();
+        for n in named {
+            let value = self.integer_value(module, &n.value, named, pos, 0)?;
+            writeln!(
+                item,
+                "pub const {}_{}: {} = {};",
+                const_name(prefix),
+                const_name(&n.name),
+                rust_type,
+                value
+            )
+            .unwrap();
+        }
+        self.items.push(item);
+        Ok(())
+    }
+
+    // Returns the Rust type for `ty`, which must not be tagged, generating any
+    // types it needs. `name` is used to name those types.
+    fn rust_type(&mut self, module: &'a Module, ty: &Type, name: &str) -> Result<String, Error> {
+        Ok(match &ty.kind {
+            TypeKind::Boolean => "bool".to_string(),
+            TypeKind::Integer(named) => {
+                let rust_type = self.integer_type(module, ty, named)?;
+                let const_type = if rust_type.starts_with("asn1::") {
+                    "i64"
+                } else {
+                    rust_type
+                };
+                self.named_number_consts(module, name, const_type, named, ty.pos)?;
+                rust_type.to_string()
+            }
+            TypeKind::Enumerated(named) => {
+                self.named_number_consts(module, name, "u32", named, ty.pos)?;
+                "asn1::Enumerated".to_string()
+            }
+            TypeKind::Real => "asn1::Real".to_string(),
+            TypeKind::BitString(named) => {
+                self.named_number_consts(module, name, "usize", named, ty.pos)?;
+                "asn1::OwnedBitString".to_string()
+            }
+            TypeKind::OctetString => "asn1::OwnedOctetString".to_string(),
+            TypeKind::Null => "asn1::Null".to_string(),
+            TypeKind::ObjectIdentifier => "asn1::ObjectIdentifier".to_string(),
+            TypeKind::RelativeOid => "asn1::RelativeOid".to_string(),
+            TypeKind::Useful(useful) => match useful.as_str() {
+                "UTF8String" => "String",
+                "PrintableString" => "asn1::OwnedPrintableString",
+                "IA5String" => "asn1::OwnedIA5String",
+                "VisibleString" | "ISO646String" => "asn1::OwnedVisibleString",
+                "BMPString" => "asn1::OwnedBMPString",
+                "UniversalString" => "asn1::OwnedUniversalString",
+                "UTCTime" => "asn1::UtcTime",
+                "GeneralizedTime" => "asn1::GeneralizedTime",
+                _ => {
+                    return Err(self.error(
+                        module,
+                        ty.pos,
+                        &format!("`{}` is not supported", useful),
+                    ))
+                }
+            }
+            .to_string(),
+            TypeKind::Any => "asn1::OwnedTlv".to_string(),
+            TypeKind::Sequence(_) | TypeKind::Set(_) | TypeKind::Choice(_) => {
+                self.constructed_type(module, ty, name)?;
+                name.to_string()
+            }
+            TypeKind::SequenceOf(element) => {
+                format!("Vec<{}>", self.element_type(module, element, name)?)
+            }
+            TypeKind::SetOf(element) => {
+                format!(
+                    "asn1::OwnedSetOf<{}>",
+                    self.element_type(module, element, name)?
+                )
+            }
+            TypeKind::Tagged { .. } => unreachable!(),
+            TypeKind::Reference {
+                module: module_name,
+                name,
+            } => {
+                let (defined_in, assignment, _) = self.resolve_type(module, ty, 0)?;
+                match module_name {
+                    Some(_) => format!(
+                        "super::{}::{}",
+                        snake_case(&defined_in.name),
+                        type_name(&assignment.name)
+                    ),
+                    None => type_name(name),
+                }
+            }
+        })
+    }
+
+    fn element_type(&mut self, module: &'a Module, ty: &Type, name: &str) -> Result<String, Error> {
+        if self.tag_of(module, ty, 0)?.is_some() {
+            return Err(self.error(
+                module,
+                ty.pos,
+                "tagged elements of a SEQUENCE OF or SET OF are not supported",
+            ));
+        }
+        self.rust_type(module, strip_tags(ty), &format!("{}Item", name))
+    }
+
+    // Lists the components of a `SEQUENCE`, `SET`, or `CHOICE`, including
+    // those of any `COMPONENTS OF` types, applying automatic tagging.
+    fn components<'t>(
+        &self,
+        module: &'a Module,
+        components: &'t Components,
+        depth: u32,
+    ) -> Result<Vec<NamedComponent<'a, 't>>, Error>
+    where
+        'a: 't,
+    {
+        let mut result = vec![];
+        for component in &components.items {
+            match component {
+                Component::Named { name, ty, presence } => result.push(NamedComponent {
+                    module,
+                    name,
+                    ty: ty.clone(),
+                    presence,
+                }),
+                Component::ComponentsOf(ty) => {
+                    let (from, builtin) = self.builtin_type(module, ty, depth + 1)?;
+                    match &builtin.kind {
+                        TypeKind::Sequence(components) | TypeKind::Set(components) => {
+                            result.extend(self.components(from, components, depth + 1)?);
+                        }
+                        _ => {
+                            return Err(self.error(
+                                module,
+                                ty.pos,
+                                "COMPONENTS OF must refer to a SEQUENCE or SET",
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+
+        let tagged = components.items.iter().any(|c| match c {
+            Component::Named { ty, .. } => matches!(ty.kind, TypeKind::Tagged { .. }),
+            Component::ComponentsOf(_) => false,
+        });
+        if module.tag_default == TagDefault::Automatic && !tagged {
+            for (i, component) in result.iter_mut().enumerate() {
+                let pos = component.ty.pos;
+                component.ty = Type {
+                    kind: TypeKind::Tagged {
+                        class: TagClass::ContextSpecific,
+                        number: i as u32,
+                        mode: TagMode::Default,
+                        inner: Box::new(component.ty.clone()),
+                    },
+                    range: None,
+                    pos,
+                };
+            }
+        }
+        Ok(result)
+    }
+
+    // Returns the `#[default(...)]` literal for a `DEFAULT` value, or `None`
+    // if it can't be expressed as one.
+    fn default_literal(
+        &self,
+        module: &'a Module,
+        ty: &Type,
+        value: &Value,
+    ) -> Result<Option<String>, Error> {
+        let (module, builtin) = self.builtin_type(module, ty, 0)?;
+        Ok(match (&builtin.kind, value) {
+            (TypeKind::Boolean, Value::Boolean(b)) => Some(b.to_string()),
+            (TypeKind::Integer(named), _) => {
+                let rust_type = self.integer_type(module, builtin, named)?;
+                if rust_type.starts_with("asn1::") {
+                    None
+                } else {
+                    let n = self.integer_value(module, value, named, ty.pos, 0)?;
+                    Some(format!("{}{}", n, rust_type))
+                }
+            }
+            (TypeKind::Useful(useful), Value::String(s)) if useful == "UTF8String" => {
+                Some(format!("{:?}", s))
+            }
+            _ => None,
+        })
+    }
+
+    fn field_attributes(&self, tag: Option<Tagging>, required: bool) -> String {
+        match tag {
+            Some(tag) => format!(
+                "#[{}({}{})]\n",
+                if tag.explicit { "explicit" } else { "implicit" },
+                tag.number,
+                if required { ", required" } else { "" }
+            ),
+            None => String::new(),
+        }
+    }
+
+    // Generates the struct or enum for a `SEQUENCE`, `SET`, or `CHOICE`.
+    fn constructed_type(&mut self, module: &'a Module, ty: &Type, name: &str) -> Result<(), Error> {
+        // Nested types are emitted after this one.
+        let index = self.items.len();
+        let mut item = String::new();
+        let (components, is_set, is_choice) = match &ty.kind {
+            TypeKind::Sequence(components) => (components, false, false),
+            TypeKind::Set(components) => (components, true, false),
+            TypeKind::Choice(components) => (components, false, true),
+            _ => unreachable!(),
+        };
+        let extensible = components.extensible || module.extensibility_implied;
+
+        item.push_str("#[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]\n");
+        let mut container = vec![];
+        if is_set {
+            container.push("set");
+        }
+        if extensible {
+            container.push("extensible");
+        }
+        if !container.is_empty() {
+            writeln!(item, "#[asn1({})]", container.join(", ")).unwrap();
+        }
+
+        let components = self.components(module, components, 0)?;
+        if is_choice {
+            if components.is_empty() {
+                return Err(self.error(module, ty.pos, "a CHOICE must have alternatives"));
+            }
+            writeln!(item, "pub enum {} {{", name).unwrap();
+            let mut variants = vec![];
+            for component in &components {
+                let tag = self.tag_of(component.module, &component.ty, 0)?;
+                let variant = type_name(component.name);
+                let rust_type = self.rust_type(
+                    component.module,
+                    strip_tags(&component.ty),
+                    &format!("{}{}", name, variant),
+                )?;
+                item.push_str(&indent(&self.field_attributes(tag, false)));
+                writeln!(item, "    {}({}),", variant, rust_type).unwrap();
+                variants.push(variant);
+            }
+            if extensible {
+                let mut variant = "Unknown".to_string();
+                while variants.contains(&variant) {
+                    variant.push('_');
+                }
+                writeln!(item, "    #[extensions]\n    {}(asn1::OwnedTlv),", variant).unwrap();
+            }
+        } else {
+            writeln!(item, "pub struct {} {{", name).unwrap();
+            for component in &components {
+                let tag = self.tag_of(component.module, &component.ty, 0)?;
+                let rust_type = self.rust_type(
+                    component.module,
+                    strip_tags(&component.ty),
+                    &format!("{}{}", name, type_name(component.name)),
+                )?;
+                let field = field_name(component.name);
+                let (attributes, rust_type) = match component.presence {
+                    Presence::Required => (self.field_attributes(tag, true), rust_type),
+                    Presence::Optional => (
+                        self.field_attributes(tag, false),
+                        format!("Option<{}>", rust_type),
+                    ),
+                    Presence::Default(value) => {
+                        match self.default_literal(component.module, &component.ty, value)? {
+                            Some(literal) => (
+                                format!(
+                                    "{}#[default({})]\n",
+                                    self.field_attributes(tag, false),
+                                    literal
+                                ),
+                                rust_type,
+                            ),
+                            None => (
+                                format!(
+                                    "/// `None` when the value is the default, `{}`.\n{}",
+                                    describe_value(value),
+                                    self.field_attributes(tag, false)
+                                ),
+                                format!("Option<{}>", rust_type),
+                            ),
+                        }
+                    }
+                };
+                item.push_str(&indent(&attributes));
+                writeln!(item, "    pub {}: {},", field, rust_type).unwrap();
+            }
+        }
+        item.push_str("}\n");
+        self.items.insert(index, item);
+        Ok(())
+    }
+
+    fn type_assignment(&mut self, assignment: &Assignment, ty: &Type) -> Result<(), Error> {
+        let module = self.module;
+        let name = type_name(&assignment.name);
+        // Validate any tag. It isn't part of the generated type, and is
+        // instead applied to fields of this type.
+        self.tag_of(module, ty, 0)?;
+        let inner = strip_tags(ty);
+        match inner.kind {
+            TypeKind::Sequence(_) | TypeKind::Set(_) | TypeKind::Choice(_) => {
+                self.constructed_type(module, inner, &name)
+            }
+            _ => {
+                let index = self.items.len();
+                let rust_type = self.rust_type(module, inner, &name)?;
+                self.items
+                    .insert(index, format!("pub type {} = {};\n", name, rust_type));
+                Ok(())
+            }
+        }
+    }
+
+    fn oid_arcs(
+        &self,
+        module: &'a Module,
+        value: &Value,
+        pos: Pos,
+        depth: u32,
+    ) -> Result<Vec<u128>, Error> {
+        if depth > MAX_REFERENCE_DEPTH {
+            return Err(self.error(module, pos, "value refers to itself"));
+        }
+        let items = match value {
+            Value::Braced(items) => items,
+            Value::Reference(name) => {
+                let (module, assignment) = self.resolve(module, name, pos, depth)?;
+                return match &assignment.kind {
+                    AssignmentKind::Value(_, value) => {
+                        self.oid_arcs(module, value, assignment.pos, depth + 1)
+                    }
+                    AssignmentKind::Type(_) => {
+                        Err(self.error(module, pos, &format!("`{}` is a type, not a value", name)))
+                    }
+                };
+            }
+            _ => return Err(self.error(module, pos, "expected an OBJECT IDENTIFIER value")),
+        };
+        let mut arcs = vec![];
+        for (i, item) in items.iter().enumerate() {
+            match item {
+                BracedItem::Number(n) => arcs.push(*n),
+                BracedItem::NameAndNumber(_, value) => {
+                    let n = self.integer_value(module, value, &[], pos, depth + 1)?;
+                    if n < 0 {
+                        return Err(self.error(module, pos, "arcs can't be negative"));
+                    }
+                    arcs.push(n as u128);
+                }
+                BracedItem::Name(name) => {
+                    let parent = match arcs.as_slice() {
+                        [] => Some(None),
+                        [root] => Some(Some(*root)),
+                        _ => None,
+                    };
+                    if let Some(n) = parent.and_then(|parent| well_known_arc(parent, name)) {
+                        arcs.push(n);
+                    } else if i == 0 {
+                        // A reference to another `OBJECT IDENTIFIER`, which
+                        // this one extends.
+                        arcs.extend(self.oid_arcs(
+                            module,
+                            &Value::Reference(name.clone()),
+                            pos,
+                            depth + 1,
+                        )?);
+                    } else {
+                        let n = self.integer_value(
+                            module,
+                            &Value::Reference(name.clone()),
+                            &[],
+                            pos,
+                            depth + 1,
+                        )?;
+                        if n < 0 {
+                            return Err(self.error(module, pos, "arcs can't be negative"));
+                        }
+                        arcs.push(n as u128);
+                    }
+                }
+            }
+        }
+        if arcs.len() < 2 || arcs[0] > 2 || (arcs[0] < 2 && arcs[1] >= 40) {
+            return Err(self.error(module, pos, "invalid OBJECT IDENTIFIER"));
+        }
+        Ok(arcs)
+    }
+
+    fn value_assignment(
+        &mut self,
+        assignment: &Assignment,
+        ty: &Type,
+        value: &Value,
+    ) -> Result<(), Error> {
+        let module = self.module;
+        let name = const_name(&assignment.name);
+        let pos = assignment.pos;
+        let (builtin_module, builtin) = self.builtin_type(module, ty, 0)?;
+        let item = match &builtin.kind {
+            TypeKind::ObjectIdentifier => {
+                let arcs = self.oid_arcs(module, value, pos, 0)?;
+                let dotted = arcs
+                    .iter()
+                    .map(|arc| arc.to_string())
+                    .collect::<Vec<_>>()
+                    .join(".");
+                let declaration = format!("pub const {}: asn1::ObjectIdentifier =", name);
+                let initializer = format!("asn1::ObjectIdentifier::from_dotted_str({:?});", dotted);
+                // Wrapped as rustfmt would, given the module's indentation.
+                if 4 + declaration.len() + 1 + initializer.len() <= 100 {
+                    format!("{} {}\n", declaration, initializer)
+                } else {
+                    format!("{}\n    {}\n", declaration, initializer)
+                }
+            }
+            TypeKind::Integer(named) => {
+                let n = self.integer_value(module, value, named, pos, 0)?;
+                let rust_type = self.integer_type(builtin_module, builtin, named)?;
+                let rust_type = match (&ty.kind, rust_type.starts_with("asn1::")) {
+                    (_, true) => "i64".to_string(),
+                    (TypeKind::Reference { .. }, false) => self.rust_type(module, ty, "")?,
+                    (_, false) => rust_type.to_string(),
+                };
+                format!("pub const {}: {} = {};\n", name, rust_type, n)
+            }
+            TypeKind::Boolean => match value {
+                Value::Boolean(b) => format!("pub const {}: bool = {};\n", name, b),
+                _ => return Err(self.error(module, pos, "expected a BOOLEAN value")),
+            },
+            TypeKind::Useful(useful)
+                if [
+                    "UTF8String",
+                    "PrintableString",
+                    "IA5String",
+                    "VisibleString",
+                ]
+                .contains(&useful.as_str()) =>
+            {
+                match value {
+                    Value::String(s) => format!("pub const {}: &str = {:?};\n", name, s),
+                    _ => return Err(self.error(module, pos, "expected a string value")),
+                }
+            }
+            _ => {
+                return Err(self.error(
+                    module,
+                    pos,
+                    "only OBJECT IDENTIFIER, INTEGER, BOOLEAN, and string values are supported",
+                ))
+            }
+        };
+        self.items.push(item);
+        Ok(())
+    }
+
+    fn imports(&self) -> Result<String, Error> {
+        let module = self.module;
+        let mut result = String::new();
+        let mut uses: Vec<(String, Vec<String>)> = vec![];
+        for import in &module.imports {
+            let from = self.find_module(&import.module).ok_or_else(|| {
+                self.error(
+                    module,
+                    import.pos,
+                    &format!("module `{}` was not found", import.module),
+                )
+            })?;
+            for symbol in &import.symbols {
+                let (defined_in, assignment) = self.resolve(from, symbol, import.pos, 0)?;
+                // Values are inlined where they're used.
+                if let AssignmentKind::Type(_) = assignment.kind {
+                    let path = snake_case(&defined_in.name);
+                    let name = type_name(&assignment.name);
+                    match uses.iter_mut().find(|(p, _)| *p == path) {
+                        Some((_, names)) => names.push(name),
+                        None => uses.push((path, vec![name])),
+                    }
+                }
+            }
+        }
+        for (path, mut names) in uses {
+            names.sort();
+            result.push_str("#[allow(unused_imports)]\n");
+            if names.len() == 1 {
+                writeln!(result, "use super::{}::{};", path, names[0]).unwrap();
+            } else {
+                writeln!(result, "use super::{}::{{{}}};", path, names.join(", ")).unwrap();
+            }
+        }
+        Ok(result)
+    }
+
+    fn generate(&mut self) -> Result<String, Error> {
+        for assignment in &self.module.assignments {
+            match &assignment.kind {
+                AssignmentKind::Type(ty) => self.type_assignment(assignment, ty)?,
+                AssignmentKind::Value(ty, value) => self.value_assignment(assignment, ty, value)?,
+            }
+        }
+
+        let mut items = vec![];
+        let imports = self.imports()?;
+        if !imports.is_empty() {
+            items.push(imports);
+        }
+        items.append(&mut self.items);
+        let mut result = format!("pub mod {} {{\n", snake_case(&self.module.name));
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                result.push('\n');
+            }
+            result.push_str(&indent(item));
+        }
+        result.push_str("}\n");
+        Ok(result)
+    }
+}
+
+// Removes any tags from `ty`.
+fn strip_tags(ty: &Type) -> &Type {
+    match &ty.kind {
+        TypeKind::Tagged { inner, .. } => strip_tags(inner),
+        _ => ty,
+    }
+}
+
+fn indent(text: &str) -> String {
+    let mut result = String::new();
+    for line in text.lines() {
+        if !line.is_empty() {
+            result.push_str("    ");
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    result
+}
+
+/// Generates Rust code for `modules`, with a Rust module for each.
+pub(crate) fn generate(modules: &[Module]) -> Result<String, Error> {
+    let mut result = "// Generated by asn1_compiler. Do not edit.\n".to_string();
+    for (i, module) in modules.iter().enumerate() {
+        if modules[..i].iter().any(|m| m.name == module.name) {
+            return Err(Error::new(&format!(
+                "module `{}` is defined more than once",
+                module.name
+            )));
+        }
+        let mut generator = Generator {
+            modules,
+            module,
+            items: vec![],
+        };
+        result.push('\n');
+        result.push_str(&generator.generate()?);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_name, snake_case, type_name};
+    use crate::parser::parse_modules;
+
+    fn compile(source: &str) -> Result<String, String> {
+        let modules = parse_modules("test.asn1", source).unwrap();
+        super::generate(&modules).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(type_name("TBSCertificate"), "TBSCertificate");
+        assert_eq!(type_name("Extension-Name"), "ExtensionName");
+        assert_eq!(type_name("rfc822Name"), "Rfc822Name");
+        assert_eq!(snake_case("PKIX1Explicit88"), "pkix1_explicit88");
+        assert_eq!(
+            snake_case("subjectPublicKeyInfo"),
+            "subject_public_key_info"
+        );
+        assert_eq!(snake_case("issuerUniqueID"), "issuer_unique_id");
+        assert_eq!(snake_case("id-ce-keyUsage"), "id_ce_key_usage");
+        assert_eq!(field_name("type"), "r#type");
+        assert_eq!(field_name("self"), "self_");
+    }
+
+    #[test]
+    fn test_tags() {
+        let generated = compile(
+            "A DEFINITIONS IMPLICIT TAGS ::= BEGIN
+             Choice ::= CHOICE { a BOOLEAN, b NULL }
+             Tagged ::= [3] EXPLICIT OCTET STRING
+             S ::= SEQUENCE {
+                 c [0] Choice,
+                 t [1] Tagged OPTIONAL,
+                 u Tagged
+             }
+             END",
+        )
+        .unwrap();
+        // CHOICE types are always tagged explicitly, and an implicit tag
+        // replaces an explicit one.
+        assert!(generated.contains("#[explicit(0, required)]\n        pub c: Choice,"));
+        assert!(generated.contains("#[explicit(1)]\n        pub t: Option<Tagged>,"));
+        assert!(generated.contains("#[explicit(3, required)]\n        pub u: Tagged,"));
+    }
+
+    #[test]
+    fn test_errors() {
+        for (source, expected) in &[
+            (
+                "A DEFINITIONS ::= BEGIN T ::= [APPLICATION 1] INTEGER END",
+                "test.asn1:1:31: only context-specific tags are supported",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN T ::= SEQUENCE { a U } END",
+                "test.asn1:1:44: `U` is not defined",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN IMPORTS U FROM B; T ::= U END",
+                "test.asn1:1:33: module `B` was not found",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN T ::= [0] IMPLICIT CHOICE { a NULL } END",
+                "test.asn1:1:31: CHOICE and ANY types can't be tagged implicitly",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN T ::= [0] EXPLICIT [1] NULL END",
+                "test.asn1:1:31: explicitly tagging a tagged type is not supported",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN T ::= U U ::= T END",
+                "test.asn1:1:39: type refers to itself",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN T ::= TeletexString END",
+                "test.asn1:1:31: `TeletexString` is not supported",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN a OBJECT IDENTIFIER ::= { 1 40 } END",
+                "test.asn1:1:25: invalid OBJECT IDENTIFIER",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN END A DEFINITIONS ::= BEGIN END",
+                "module `A` is defined more than once",
+            ),
+        ] {
+            assert_eq!(compile(source).unwrap_err(), *expected);
+        }
+    }
+}
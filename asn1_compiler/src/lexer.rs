@@ -0,0 +1,323 @@
+use crate::{Error, Pos};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    /// A type or module reference, or a keyword. These begin with an
+    /// uppercase letter.
+    Upper(String),
+    /// An identifier or value reference. These begin with a lowercase letter.
+    Lower(String),
+    Number(u128),
+    /// A quoted string, with any doubled quotes unescaped.
+    CString(String),
+    /// The digits of a `'0101'B` binary string.
+    BString(String),
+    /// The digits of a `'0AF1'H` hexadecimal string.
+    HString(String),
+    /// `::=`
+    Assign,
+    /// `..`
+    Range,
+    /// `...`
+    Ellipsis,
+    /// `[[`
+    LeftVersionBrackets,
+    /// `]]`
+    RightVersionBrackets,
+    /// Any other single character, e.g. `{` or `,`.
+    Punct(char),
+    Eof,
+}
+
+impl Token {
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Token::Upper(s) | Token::Lower(s) => format!("`{}`", s),
+            Token::Number(n) => format!("`{}`", n),
+            Token::CString(_) => "a string".to_string(),
+            Token::BString(_) | Token::HString(_) => "a bit string".to_string(),
+            Token::Assign => "`::=`".to_string(),
+            Token::Range => "`..`".to_string(),
+            Token::Ellipsis => "`...`".to_string(),
+            Token::LeftVersionBrackets => "`[[`".to_string(),
+            Token::RightVersionBrackets => "`]]`".to_string(),
+            Token::Punct(c) => format!("`{}`", c),
+            Token::Eof => "the end of the module".to_string(),
+        }
+    }
+}
+
+struct Lexer<'a> {
+    chars: Vec<char>,
+    index: usize,
+    pos: Pos,
+    file: &'a str,
+}
+
+impl Lexer<'_> {
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.index + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.index += 1;
+        if c == '\n' {
+            self.pos.line += 1;
+            self.pos.column = 1;
+        } else {
+            self.pos.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, pos: Pos, message: &str) -> Error {
+        Error::new(message).at(self.file, pos)
+    }
+
+    // Skips whitespace and comments, which are either `--` to the next `--`
+    // or the end of the line, or (possibly nested) `/* ... */`.
+    fn skip_trivia(&mut self) -> Result<(), Error> {
+        loop {
+            match (self.peek(0), self.peek(1)) {
+                (Some(c), _) if c.is_whitespace() => {
+                    self.bump();
+                }
+                (Some('-'), Some('-')) => {
+                    self.bump();
+                    self.bump();
+                    loop {
+                        match (self.peek(0), self.peek(1)) {
+                            (None, _) | (Some('\n'), _) | (Some('\r'), _) => break,
+                            (Some('-'), Some('-')) => {
+                                self.bump();
+                                self.bump();
+                                break;
+                            }
+                            _ => {
+                                self.bump();
+                            }
+                        }
+                    }
+                }
+                (Some('/'), Some('*')) => {
+                    let start = self.pos;
+                    let mut depth = 0;
+                    loop {
+                        match (self.peek(0), self.peek(1)) {
+                            (None, _) => return Err(self.error(start, "unterminated comment")),
+                            (Some('/'), Some('*')) => {
+                                self.bump();
+                                self.bump();
+                                depth += 1;
+                            }
+                            (Some('*'), Some('/')) => {
+                                self.bump();
+                                self.bump();
+                                depth -= 1;
+                                if depth == 0 {
+                                    break;
+                                }
+                            }
+                            _ => {
+                                self.bump();
+                            }
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn next(&mut self) -> Result<(Token, Pos), Error> {
+        self.skip_trivia()?;
+        let pos = self.pos;
+        let c = match self.bump() {
+            Some(c) => c,
+            None => return Ok((Token::Eof, pos)),
+        };
+        let token = match c {
+            'a'..='z' | 'A'..='Z' => {
+                let mut s = c.to_string();
+                loop {
+                    match (self.peek(0), self.peek(1)) {
+                        (Some(c), _) if c.is_ascii_alphanumeric() => s.push(c),
+                        // A hyphen may not be doubled (which would start a
+                        // comment) or end the identifier.
+                        (Some('-'), Some(next)) if next.is_ascii_alphanumeric() => s.push('-'),
+                        _ => break,
+                    }
+                    self.bump();
+                }
+                if c.is_ascii_uppercase() {
+                    Token::Upper(s)
+                } else {
+                    Token::Lower(s)
+                }
+            }
+            '0'..='9' => {
+                let mut n = u128::from(c.to_digit(10).unwrap());
+                while let Some(d) = self.peek(0).and_then(|c| c.to_digit(10)) {
+                    n = n
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(u128::from(d)))
+                        .ok_or_else(|| self.error(pos, "number is too large"))?;
+                    self.bump();
+                }
+                Token::Number(n)
+            }
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match self.bump() {
+                        None => return Err(self.error(pos, "unterminated string")),
+                        Some('"') if self.peek(0) == Some('"') => {
+                            self.bump();
+                            s.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => s.push(c),
+                    }
+                }
+                Token::CString(s)
+            }
+            '\'' => {
+                let mut s = String::new();
+                loop {
+                    match self.bump() {
+                        None => return Err(self.error(pos, "unterminated bit string")),
+                        Some('\'') => break,
+                        Some(c) if c.is_whitespace() => {}
+                        Some(c) => s.push(c),
+                    }
+                }
+                match self.bump() {
+                    Some('B') if s.chars().all(|c| c == '0' || c == '1') => Token::BString(s),
+                    Some('H') if s.chars().all(|c| c.is_ascii_hexdigit()) => Token::HString(s),
+                    _ => return Err(self.error(pos, "invalid bit string")),
+                }
+            }
+            ':' if self.peek(0) == Some(':') && self.peek(1) == Some('=') => {
+                self.bump();
+                self.bump();
+                Token::Assign
+            }
+            '.' if self.peek(0) == Some('.') => {
+                self.bump();
+                if self.peek(0) == Some('.') {
+                    self.bump();
+                    Token::Ellipsis
+                } else {
+                    Token::Range
+                }
+            }
+            '[' if self.peek(0) == Some('[') => {
+                self.bump();
+                Token::LeftVersionBrackets
+            }
+            ']' if self.peek(0) == Some(']') => {
+                self.bump();
+                Token::RightVersionBrackets
+            }
+            '{' | '}' | '(' | ')' | '[' | ']' | ',' | ';' | '.' | '|' | '<' | '>' | '@' | '!'
+            | '^' | ':' | '&' | '-' | '=' => Token::Punct(c),
+            _ => return Err(self.error(pos, &format!("unexpected character `{}`", c))),
+        };
+        Ok((token, pos))
+    }
+}
+
+/// Splits `source` into tokens, ending with `Token::Eof`.
+pub(crate) fn tokenize(file: &str, source: &str) -> Result<Vec<(Token, Pos)>, Error> {
+    let mut lexer = Lexer {
+        chars: source.chars().collect(),
+        index: 0,
+        pos: Pos { line: 1, column: 1 },
+        file,
+    };
+    let mut tokens = vec![];
+    loop {
+        let (token, pos) = lexer.next()?;
+        let done = token == Token::Eof;
+        tokens.push((token, pos));
+        if done {
+            return Ok(tokens);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, Token};
+
+    fn tokens(source: &str) -> Vec<Token> {
+        tokenize("test.asn1", source)
+            .unwrap()
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokens("id-ce-keyUsage OBJECT IDENTIFIER ::= { id-ce 15 }"),
+            vec![
+                Token::Lower("id-ce-keyUsage".to_string()),
+                Token::Upper("OBJECT".to_string()),
+                Token::Upper("IDENTIFIER".to_string()),
+                Token::Assign,
+                Token::Punct('{'),
+                Token::Lower("id-ce".to_string()),
+                Token::Number(15),
+                Token::Punct('}'),
+                Token::Eof,
+            ]
+        );
+        assert_eq!(
+            tokens("a -- comment -- b -- comment\n/* c /* d */ */ (1..MAX, ...) [[ ]]"),
+            vec![
+                Token::Lower("a".to_string()),
+                Token::Lower("b".to_string()),
+                Token::Punct('('),
+                Token::Number(1),
+                Token::Range,
+                Token::Upper("MAX".to_string()),
+                Token::Punct(','),
+                Token::Ellipsis,
+                Token::Punct(')'),
+                Token::LeftVersionBrackets,
+                Token::RightVersionBrackets,
+                Token::Eof,
+            ]
+        );
+        assert_eq!(
+            tokens("\"a \"\"b\"\"\" '0110'B '0aF'H -1"),
+            vec![
+                Token::CString("a \"b\"".to_string()),
+                Token::BString("0110".to_string()),
+                Token::HString("0aF".to_string()),
+                Token::Punct('-'),
+                Token::Number(1),
+                Token::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_errors() {
+        assert_eq!(
+            tokenize("test.asn1", "a\n  \"b").unwrap_err().to_string(),
+            "test.asn1:2:3: unterminated string"
+        );
+        assert_eq!(
+            tokenize("test.asn1", "'012'B").unwrap_err().to_string(),
+            "test.asn1:1:1: invalid bit string"
+        );
+        assert_eq!(
+            tokenize("test.asn1", "a # b").unwrap_err().to_string(),
+            "test.asn1:1:3: unexpected character `#`"
+        );
+    }
+}
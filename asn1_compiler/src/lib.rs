@@ -0,0 +1,128 @@
+#![forbid(unsafe_code)]
+
+//! Generates Rust types for the `asn1` crate from ASN.1 modules.
+//!
+//! Each ASN.1 module becomes a Rust module, with a struct for each `SEQUENCE`
+//! or `SET`, an enum for each `CHOICE`, and a type alias for other types.
+//! These derive `asn1::Asn1Read` and `asn1::Asn1Write`, so the generated code
+//! requires `asn1`'s `derive` feature. `OBJECT IDENTIFIER` and `INTEGER`
+//! values become constants.
+//!
+//! The compiler is intended to be run from a build script:
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     println!("cargo:rerun-if-changed=pkix.asn1");
+//!     let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("pkix.rs");
+//!     asn1_compiler::Compiler::new()
+//!         .add_file("pkix.asn1")
+//!         .and_then(|c| c.compile_to_file(out))
+//!         .unwrap();
+//! }
+//! ```
+//!
+//! with the generated code then included in the crate:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/pkix.rs"));
+//! ```
+//!
+//! Owned types are generated (e.g. `asn1::OwnedOctetString` rather than
+//! `&[u8]`), so values can outlive the data they were parsed from.
+//! Information object classes, parameterized types, and constraints other
+//! than `INTEGER` value ranges are not supported. Value ranges select the
+//! smallest fitting Rust integer type; unconstrained `INTEGER`s are
+//! `asn1::OwnedBigInt`.
+
+mod ast;
+mod codegen;
+mod lexer;
+mod parser;
+
+use std::fmt;
+use std::path::Path;
+
+/// A position in an ASN.1 source file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Pos {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+/// An error encountered while reading or compiling ASN.1 modules.
+#[derive(Debug)]
+pub struct Error {
+    message: String,
+    location: Option<(String, Pos)>,
+}
+
+impl Error {
+    pub(crate) fn new(message: &str) -> Error {
+        Error {
+            message: message.to_string(),
+            location: None,
+        }
+    }
+
+    pub(crate) fn at(self, file: &str, pos: Pos) -> Error {
+        Error {
+            location: Some((file.to_string(), pos)),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some((file, pos)) => {
+                write!(f, "{}:{}:{}: {}", file, pos.line, pos.column, self.message)
+            }
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Compiles a set of ASN.1 modules to Rust code. Modules may import from any
+/// other module added to the same `Compiler`.
+#[derive(Default)]
+pub struct Compiler {
+    modules: Vec<ast::Module>,
+}
+
+impl Compiler {
+    pub fn new() -> Compiler {
+        Compiler::default()
+    }
+
+    /// Adds the modules defined in `source`. `name` identifies the source in
+    /// errors.
+    pub fn add_source(&mut self, name: &str, source: &str) -> Result<&mut Compiler, Error> {
+        self.modules.extend(parser::parse_modules(name, source)?);
+        Ok(self)
+    }
+
+    /// Adds the modules defined in the file at `path`.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> Result<&mut Compiler, Error> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| Error::new(&format!("{}: {}", path.display(), e)))?;
+        self.add_source(&path.display().to_string(), &source)
+    }
+
+    /// Generates Rust code for all of the modules which have been added.
+    pub fn compile(&self) -> Result<String, Error> {
+        codegen::generate(&self.modules)
+    }
+
+    /// Generates Rust code for all of the modules which have been added, and
+    /// writes it to `path`.
+    pub fn compile_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let code = self.compile()?;
+        std::fs::write(path, code).map_err(|e| Error::new(&format!("{}: {}", path.display(), e)))
+    }
+}
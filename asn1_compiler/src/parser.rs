@@ -0,0 +1,770 @@
+use crate::ast::{
+    Assignment, AssignmentKind, BracedItem, Component, Components, Import, Module, NamedNumber,
+    Presence, Range, TagClass, TagDefault, TagMode, Type, TypeKind, Value,
+};
+use crate::lexer::{tokenize, Token};
+use crate::{Error, Pos};
+
+// Types which are valid ASN.1, but which can't be represented with the
+// `asn1` crate.
+const UNSUPPORTED_TYPES: &[&str] = &[
+    "ABSTRACT-SYNTAX",
+    "CHARACTER",
+    "CLASS",
+    "DATE",
+    "DATE-TIME",
+    "DURATION",
+    "EMBEDDED",
+    "EXTERNAL",
+    "INSTANCE",
+    "OID-IRI",
+    "RELATIVE-OID-IRI",
+    "TIME",
+    "TIME-OF-DAY",
+    "TYPE-IDENTIFIER",
+];
+
+// Character string and time types.
+const USEFUL_TYPES: &[&str] = &[
+    "BMPString",
+    "GeneralString",
+    "GeneralizedTime",
+    "GraphicString",
+    "IA5String",
+    "ISO646String",
+    "NumericString",
+    "ObjectDescriptor",
+    "PrintableString",
+    "T61String",
+    "TeletexString",
+    "UTCTime",
+    "UTF8String",
+    "UniversalString",
+    "VideotexString",
+    "VisibleString",
+];
+
+struct Parser<'a> {
+    tokens: Vec<(Token, Pos)>,
+    index: usize,
+    file: &'a str,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        let index = (self.index + offset).min(self.tokens.len() - 1);
+        &self.tokens[index].0
+    }
+
+    fn pos(&self) -> Pos {
+        self.tokens[self.index].1
+    }
+
+    fn bump(&mut self) -> Token {
+        let token = self.tokens[self.index].0.clone();
+        if token != Token::Eof {
+            self.index += 1;
+        }
+        token
+    }
+
+    // Undoes a `bump` which returned `token`.
+    fn unbump(&mut self, token: &Token) {
+        if *token != Token::Eof {
+            self.index -= 1;
+        }
+    }
+
+    fn error_at(&self, pos: Pos, message: &str) -> Error {
+        Error::new(message).at(self.file, pos)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        self.error_at(self.pos(), message)
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        self.error(&format!(
+            "expected {}, found {}",
+            expected,
+            self.peek().describe()
+        ))
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Upper(s) if s == keyword)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        if !self.eat_keyword(keyword) {
+            return Err(self.unexpected(&format!("`{}`", keyword)));
+        }
+        Ok(())
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == token;
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        self.eat(&Token::Punct(c))
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), Error> {
+        if !self.eat_punct(c) {
+            return Err(self.unexpected(&format!("`{}`", c)));
+        }
+        Ok(())
+    }
+
+    fn expect_upper(&mut self, expected: &str) -> Result<String, Error> {
+        match self.peek() {
+            Token::Upper(s) => {
+                let s = s.clone();
+                self.bump();
+                Ok(s)
+            }
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn expect_lower(&mut self, expected: &str) -> Result<String, Error> {
+        match self.peek() {
+            Token::Lower(s) => {
+                let s = s.clone();
+                self.bump();
+                Ok(s)
+            }
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    // Skips the tokens up to and including the `close` which matches an
+    // already consumed `open`, returning them (without the `close`).
+    fn skip_balanced(&mut self, open: char, close: char) -> Result<Vec<Token>, Error> {
+        let pos = self.pos();
+        let mut depth = 1;
+        let mut tokens = vec![];
+        loop {
+            let token = self.bump();
+            match token {
+                Token::Eof => {
+                    return Err(self.error_at(pos, &format!("unterminated `{}`", open)));
+                }
+                Token::Punct(c) if c == open => depth += 1,
+                Token::Punct(c) if c == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(tokens);
+                    }
+                }
+                _ => {}
+            }
+            tokens.push(token);
+        }
+    }
+
+    fn module(&mut self) -> Result<Module, Error> {
+        let name = self.expect_upper("a module name")?;
+        if self.eat_punct('{') {
+            self.skip_balanced('{', '}')?;
+        }
+        if let Token::CString(_) = self.peek() {
+            self.bump();
+        }
+        self.expect_keyword("DEFINITIONS")?;
+        if matches!(self.peek_at(1), Token::Upper(s) if s == "INSTRUCTIONS") {
+            self.bump();
+            self.bump();
+        }
+
+        let tag_default = if self.eat_keyword("EXPLICIT") {
+            Some(TagDefault::Explicit)
+        } else if self.eat_keyword("IMPLICIT") {
+            Some(TagDefault::Implicit)
+        } else if self.eat_keyword("AUTOMATIC") {
+            Some(TagDefault::Automatic)
+        } else {
+            None
+        };
+        if tag_default.is_some() {
+            self.expect_keyword("TAGS")?;
+        }
+        let tag_default = tag_default.unwrap_or(TagDefault::Explicit);
+        let extensibility_implied = self.eat_keyword("EXTENSIBILITY");
+        if extensibility_implied {
+            self.expect_keyword("IMPLIED")?;
+        }
+        if !self.eat(&Token::Assign) {
+            return Err(self.unexpected("`::=`"));
+        }
+        self.expect_keyword("BEGIN")?;
+
+        if self.eat_keyword("EXPORTS") {
+            while !self.eat_punct(';') {
+                if self.bump() == Token::Eof {
+                    return Err(self.unexpected("`;`"));
+                }
+            }
+        }
+        let imports = if self.eat_keyword("IMPORTS") {
+            self.imports()?
+        } else {
+            vec![]
+        };
+
+        let mut assignments = vec![];
+        while !self.eat_keyword("END") {
+            assignments.push(self.assignment()?);
+        }
+        Ok(Module {
+            name,
+            file: self.file.to_string(),
+            tag_default,
+            extensibility_implied,
+            imports,
+            assignments,
+        })
+    }
+
+    fn imports(&mut self) -> Result<Vec<Import>, Error> {
+        let mut imports = vec![];
+        while !self.eat_punct(';') {
+            let pos = self.pos();
+            let mut symbols = vec![];
+            loop {
+                let symbol = match self.bump() {
+                    Token::Upper(s) | Token::Lower(s) => s,
+                    _ => return Err(self.error_at(pos, "expected a symbol to import")),
+                };
+                // A parameterized reference, e.g. `Name{}`.
+                if self.eat_punct('{') {
+                    self.expect_punct('}')?;
+                }
+                symbols.push(symbol);
+                if !self.eat_punct(',') {
+                    break;
+                }
+            }
+            self.expect_keyword("FROM")?;
+            let module = self.expect_upper("a module name")?;
+            if self.eat_punct('{') {
+                self.skip_balanced('{', '}')?;
+            } else if let Token::Lower(_) = self.peek() {
+                // The module may be identified by a value reference, which
+                // is told apart from the start of the next list of symbols by
+                // what follows it.
+                let next = self.peek_at(1);
+                if *next != Token::Punct(',') && *next != Token::Upper("FROM".to_string()) {
+                    self.bump();
+                }
+            }
+            if self.eat_keyword("WITH") {
+                self.bump();
+            }
+            imports.push(Import {
+                symbols,
+                module,
+                pos,
+            });
+        }
+        Ok(imports)
+    }
+
+    fn assignment(&mut self) -> Result<Assignment, Error> {
+        let pos = self.pos();
+        match self.bump() {
+            Token::Upper(name) => {
+                if *self.peek() == Token::Punct('{') {
+                    return Err(self.error_at(pos, "parameterized types are not supported"));
+                }
+                if let Token::Upper(_) = self.peek() {
+                    return Err(self.error_at(pos, "information object sets are not supported"));
+                }
+                if !self.eat(&Token::Assign) {
+                    return Err(self.unexpected("`::=`"));
+                }
+                let ty = self.ty()?;
+                Ok(Assignment {
+                    name,
+                    pos,
+                    kind: AssignmentKind::Type(ty),
+                })
+            }
+            Token::Lower(name) => {
+                if *self.peek() == Token::Punct('{') {
+                    return Err(self.error_at(pos, "parameterized values are not supported"));
+                }
+                let ty = self.ty()?;
+                if !self.eat(&Token::Assign) {
+                    return Err(self.unexpected("`::=`"));
+                }
+                let value = self.value()?;
+                Ok(Assignment {
+                    name,
+                    pos,
+                    kind: AssignmentKind::Value(ty, value),
+                })
+            }
+            token => {
+                self.unbump(&token);
+                Err(self.unexpected("an assignment or `END`"))
+            }
+        }
+    }
+
+    fn ty(&mut self) -> Result<Type, Error> {
+        let pos = self.pos();
+        let kind = match self.bump() {
+            Token::Punct('[') => self.tagged()?,
+            Token::Upper(keyword) => match keyword.as_str() {
+                "BOOLEAN" => TypeKind::Boolean,
+                "INTEGER" => TypeKind::Integer(self.named_numbers(false)?),
+                "ENUMERATED" => {
+                    if *self.peek() != Token::Punct('{') {
+                        return Err(self.unexpected("`{`"));
+                    }
+                    TypeKind::Enumerated(self.named_numbers(true)?)
+                }
+                "REAL" => TypeKind::Real,
+                "BIT" => {
+                    self.expect_keyword("STRING")?;
+                    TypeKind::BitString(self.named_numbers(false)?)
+                }
+                "OCTET" => {
+                    self.expect_keyword("STRING")?;
+                    TypeKind::OctetString
+                }
+                "NULL" => TypeKind::Null,
+                "OBJECT" => {
+                    self.expect_keyword("IDENTIFIER")?;
+                    TypeKind::ObjectIdentifier
+                }
+                "RELATIVE-OID" => TypeKind::RelativeOid,
+                "ANY" => {
+                    if self.eat_keyword("DEFINED") {
+                        self.expect_keyword("BY")?;
+                        self.expect_lower("a component name")?;
+                    }
+                    TypeKind::Any
+                }
+                "SEQUENCE" | "SET" => {
+                    let is_sequence = keyword == "SEQUENCE";
+                    if *self.peek() == Token::Punct('{') {
+                        let components = self.components()?;
+                        if is_sequence {
+                            TypeKind::Sequence(components)
+                        } else {
+                            TypeKind::Set(components)
+                        }
+                    } else {
+                        // A size constraint, which is ignored.
+                        if self.eat_keyword("SIZE") {
+                            self.expect_punct('(')?;
+                            self.skip_balanced('(', ')')?;
+                        } else if self.eat_punct('(') {
+                            self.skip_balanced('(', ')')?;
+                        }
+                        self.expect_keyword("OF")?;
+                        // The element may be named, e.g. `SEQUENCE OF name Type`.
+                        if let Token::Lower(_) = self.peek() {
+                            self.bump();
+                        }
+                        let element = Box::new(self.ty()?);
+                        if is_sequence {
+                            TypeKind::SequenceOf(element)
+                        } else {
+                            TypeKind::SetOf(element)
+                        }
+                    }
+                }
+                "CHOICE" => TypeKind::Choice(self.components()?),
+                _ if USEFUL_TYPES.contains(&keyword.as_str()) => TypeKind::Useful(keyword),
+                _ if UNSUPPORTED_TYPES.contains(&keyword.as_str()) => {
+                    return Err(self.error_at(pos, &format!("`{}` is not supported", keyword)));
+                }
+                _ => {
+                    let (module, name) = if self.eat_punct('.') {
+                        (Some(keyword), self.expect_upper("a type name")?)
+                    } else {
+                        (None, keyword)
+                    };
+                    if *self.peek() == Token::Punct('{') {
+                        return Err(self.error("parameterized types are not supported"));
+                    }
+                    if *self.peek() == Token::Punct('.') {
+                        return Err(self.error("information object classes are not supported"));
+                    }
+                    TypeKind::Reference { module, name }
+                }
+            },
+            token => {
+                self.unbump(&token);
+                return Err(self.unexpected("a type"));
+            }
+        };
+
+        let mut range = None;
+        while self.eat_punct('(') {
+            let constraint = self.skip_balanced('(', ')')?;
+            if range.is_none() {
+                range = value_range(&constraint);
+            }
+        }
+        Ok(Type { kind, range, pos })
+    }
+
+    // Parses a tagged type, after the `[`.
+    fn tagged(&mut self) -> Result<TypeKind, Error> {
+        let class = if self.eat_keyword("UNIVERSAL") {
+            TagClass::Universal
+        } else if self.eat_keyword("APPLICATION") {
+            TagClass::Application
+        } else if self.eat_keyword("PRIVATE") {
+            TagClass::Private
+        } else {
+            TagClass::ContextSpecific
+        };
+        let number = match self.peek() {
+            Token::Number(n) if *n <= u128::from(u32::MAX) => *n as u32,
+            Token::Number(_) => return Err(self.error("tag number is too large")),
+            _ => return Err(self.unexpected("a tag number")),
+        };
+        self.bump();
+        self.expect_punct(']')?;
+        let mode = if self.eat_keyword("IMPLICIT") {
+            TagMode::Implicit
+        } else if self.eat_keyword("EXPLICIT") {
+            TagMode::Explicit
+        } else {
+            TagMode::Default
+        };
+        Ok(TypeKind::Tagged {
+            class,
+            number,
+            mode,
+            inner: Box::new(self.ty()?),
+        })
+    }
+
+    // Parses the optional named numbers of an `INTEGER` or `BIT STRING`, or
+    // the items of an `ENUMERATED`, whose numbers may be omitted.
+    fn named_numbers(&mut self, enumerated: bool) -> Result<Vec<NamedNumber>, Error> {
+        let mut items = vec![];
+        if !self.eat_punct('{') {
+            return Ok(vec![]);
+        }
+        loop {
+            // An `ENUMERATED` may have an extension marker.
+            if !(enumerated && self.eat(&Token::Ellipsis)) {
+                let name = self.expect_lower("a name")?;
+                if self.eat_punct('(') {
+                    let value = self.value()?;
+                    self.expect_punct(')')?;
+                    items.push((name, Some(value)));
+                } else if enumerated {
+                    items.push((name, None));
+                } else {
+                    return Err(self.unexpected("`(`"));
+                }
+            }
+            if !self.eat_punct(',') {
+                break;
+            }
+        }
+        self.expect_punct('}')?;
+
+        // Items without a number are given the lowest ones not otherwise
+        // used, in order.
+        let used = items
+            .iter()
+            .filter_map(|(_, value)| match value {
+                Some(Value::Number(n)) => Some(*n),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut next = 0;
+        Ok(items
+            .into_iter()
+            .map(|(name, value)| {
+                let value = value.unwrap_or_else(|| {
+                    while used.contains(&next) {
+                        next += 1;
+                    }
+                    next += 1;
+                    Value::Number(next - 1)
+                });
+                NamedNumber { name, value }
+            })
+            .collect())
+    }
+
+    fn components(&mut self) -> Result<Components, Error> {
+        self.expect_punct('{')?;
+        let mut components = Components {
+            items: vec![],
+            extensible: false,
+        };
+        if self.eat_punct('}') {
+            return Ok(components);
+        }
+        self.component_list(&mut components, &Token::Punct('}'))?;
+        Ok(components)
+    }
+
+    // Parses comma separated components up to and including `end`.
+    fn component_list(&mut self, components: &mut Components, end: &Token) -> Result<(), Error> {
+        loop {
+            if self.eat(&Token::Ellipsis) {
+                components.extensible = true;
+                if self.eat_punct('!') {
+                    return Err(self.error("exception specifications are not supported"));
+                }
+            } else if self.eat(&Token::LeftVersionBrackets) {
+                // A group of extension additions, optionally with a version
+                // number.
+                if let Token::Number(_) = self.peek() {
+                    self.bump();
+                    self.expect_punct(':')?;
+                }
+                self.component_list(components, &Token::RightVersionBrackets)?;
+            } else if self.eat_keyword("COMPONENTS") {
+                self.expect_keyword("OF")?;
+                components.items.push(Component::ComponentsOf(self.ty()?));
+            } else {
+                let name = self.expect_lower("a component name")?;
+                let ty = self.ty()?;
+                let presence = if self.eat_keyword("OPTIONAL") {
+                    Presence::Optional
+                } else if self.eat_keyword("DEFAULT") {
+                    Presence::Default(self.value()?)
+                } else {
+                    Presence::Required
+                };
+                components
+                    .items
+                    .push(Component::Named { name, ty, presence });
+            }
+            if !self.eat_punct(',') {
+                break;
+            }
+        }
+        if !self.eat(end) {
+            return Err(self.unexpected(&format!("`,` or {}", end.describe())));
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        let pos = self.pos();
+        let value = match self.bump() {
+            Token::Number(n) => {
+                Value::Number(number(n).ok_or_else(|| self.error_at(pos, "number is too large"))?)
+            }
+            Token::Punct('-') => match self.bump() {
+                Token::Number(n) => Value::Number(
+                    -number(n).ok_or_else(|| self.error_at(pos, "number is too large"))?,
+                ),
+                _ => return Err(self.error_at(pos, "expected a number")),
+            },
+            Token::Upper(s) if s == "TRUE" => Value::Boolean(true),
+            Token::Upper(s) if s == "FALSE" => Value::Boolean(false),
+            Token::Upper(s) if s == "NULL" => Value::Null,
+            Token::CString(s) => Value::String(s),
+            Token::BString(s) => Value::BString(s),
+            Token::HString(s) => Value::HString(s),
+            Token::Lower(s) => Value::Reference(s),
+            Token::Punct('{') => Value::Braced(self.braced_items()?),
+            token => {
+                self.unbump(&token);
+                return Err(self.unexpected("a value"));
+            }
+        };
+        Ok(value)
+    }
+
+    // Parses the items of an `OBJECT IDENTIFIER` value, or a list of named
+    // bits, after the `{`.
+    fn braced_items(&mut self) -> Result<Vec<BracedItem>, Error> {
+        let mut items = vec![];
+        loop {
+            match self.bump() {
+                Token::Punct('}') => return Ok(items),
+                Token::Punct(',') if !items.is_empty() => {}
+                Token::Number(n) => items.push(BracedItem::Number(n)),
+                Token::Lower(name) => {
+                    if self.eat_punct('(') {
+                        let value = self.value()?;
+                        self.expect_punct(')')?;
+                        items.push(BracedItem::NameAndNumber(name, value));
+                    } else {
+                        items.push(BracedItem::Name(name));
+                    }
+                }
+                token => {
+                    self.unbump(&token);
+                    return Err(self.unexpected("a number, a name, or `}`"));
+                }
+            }
+        }
+    }
+}
+
+fn number(n: u128) -> Option<i128> {
+    if n > i128::MAX as u128 {
+        return None;
+    }
+    Some(n as i128)
+}
+
+// Interprets a constraint as a single value or a range of values, e.g.
+// `(0..MAX)`. Returns `None` for any other constraint, including extensible
+// ones, since those don't limit the values which may be encoded.
+fn value_range(tokens: &[Token]) -> Option<Range> {
+    fn bound(tokens: &[Token], index: &mut usize) -> Option<Option<Value>> {
+        let value = match tokens.get(*index)? {
+            Token::Upper(s) if s == "MIN" || s == "MAX" => None,
+            Token::Number(n) => Some(Value::Number(number(*n)?)),
+            Token::Punct('-') => match tokens.get(*index + 1)? {
+                Token::Number(n) => {
+                    *index += 1;
+                    Some(Value::Number(-number(*n)?))
+                }
+                _ => return None,
+            },
+            Token::Lower(s) => Some(Value::Reference(s.clone())),
+            _ => return None,
+        };
+        *index += 1;
+        Some(value)
+    }
+
+    let mut index = 0;
+    let lower = bound(tokens, &mut index)?;
+    let upper = if tokens.get(index) == Some(&Token::Range) {
+        index += 1;
+        bound(tokens, &mut index)?
+    } else {
+        lower.clone()
+    };
+    if index != tokens.len() {
+        return None;
+    }
+    Some(Range { lower, upper })
+}
+
+/// Parses the modules in `source`, which was read from `file`.
+pub(crate) fn parse_modules(file: &str, source: &str) -> Result<Vec<Module>, Error> {
+    let mut parser = Parser {
+        tokens: tokenize(file, source)?,
+        index: 0,
+        file,
+    };
+    let mut modules = vec![];
+    while *parser.peek() != Token::Eof {
+        modules.push(parser.module()?);
+    }
+    if modules.is_empty() {
+        return Err(Error::new("no modules were found").at(file, parser.pos()));
+    }
+    Ok(modules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_modules;
+    use crate::ast::{AssignmentKind, TagDefault, TypeKind, Value};
+
+    #[test]
+    fn test_parse_module() {
+        let modules = parse_modules(
+            "test.asn1",
+            "Test { 1 2 3 } DEFINITIONS IMPLICIT TAGS ::= BEGIN
+             IMPORTS A, b FROM Other { 1 2 } C FROM Third;
+             Small ::= INTEGER (0..255)
+             Color ::= ENUMERATED { red, green(0), blue, ... }
+             END",
+        )
+        .unwrap();
+        assert_eq!(modules.len(), 1);
+        let module = &modules[0];
+        assert_eq!(module.name, "Test");
+        assert_eq!(module.tag_default, TagDefault::Implicit);
+        assert_eq!(module.imports.len(), 2);
+        assert_eq!(module.imports[0].symbols, ["A", "b"]);
+        assert_eq!(module.imports[0].module, "Other");
+        assert_eq!(module.imports[1].symbols, ["C"]);
+
+        match &module.assignments[0].kind {
+            AssignmentKind::Type(ty) => {
+                let range = ty.range.as_ref().unwrap();
+                assert!(matches!(range.lower, Some(Value::Number(0))));
+                assert!(matches!(range.upper, Some(Value::Number(255))));
+            }
+            _ => panic!(),
+        }
+        match &module.assignments[1].kind {
+            AssignmentKind::Type(ty) => match &ty.kind {
+                TypeKind::Enumerated(items) => {
+                    let numbers = items
+                        .iter()
+                        .map(|item| match item.value {
+                            Value::Number(n) => (item.name.as_str(), n),
+                            _ => panic!(),
+                        })
+                        .collect::<Vec<_>>();
+                    assert_eq!(numbers, [("red", 1), ("green", 0), ("blue", 2)]);
+                }
+                _ => panic!(),
+            },
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (source, expected) in &[
+            ("", "test.asn1:1:1: no modules were found"),
+            (
+                "A DEFINITIONS ::= BEGIN T ::= INTEGER",
+                "test.asn1:1:38: expected an assignment or `END`, found the end of the module",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN T{X} ::= SEQUENCE OF X END",
+                "test.asn1:1:25: parameterized types are not supported",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN T ::= TYPE-IDENTIFIER.&Type END",
+                "test.asn1:1:31: `TYPE-IDENTIFIER` is not supported",
+            ),
+            (
+                "A DEFINITIONS ::= BEGIN T ::= SEQUENCE { a INTEGER b INTEGER } END",
+                "test.asn1:1:52: expected `,` or `}`, found `b`",
+            ),
+        ] {
+            assert_eq!(
+                parse_modules("test.asn1", source)
+                    .err()
+                    .unwrap()
+                    .to_string(),
+                *expected
+            );
+        }
+    }
+}
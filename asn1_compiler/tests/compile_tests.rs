@@ -0,0 +1,35 @@
+use std::fs;
+use std::path::Path;
+
+// Compiles each `tests/modules/*.asn1` file, and compares the result with the
+// corresponding `.rs` file. Set `ASN1_COMPILER_BLESS` to update those instead.
+#[test]
+fn test_modules() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/modules");
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension() != Some("asn1".as_ref()) {
+            continue;
+        }
+        let generated = asn1_compiler::Compiler::new()
+            .add_file(&path)
+            .and_then(|c| c.compile())
+            .unwrap();
+        let expected_path = path.with_extension("rs");
+        if std::env::var_os("ASN1_COMPILER_BLESS").is_some() {
+            fs::write(&expected_path, &generated).unwrap();
+        } else {
+            let expected = fs::read_to_string(&expected_path).unwrap();
+            assert_eq!(generated, expected, "{}", path.display());
+        }
+    }
+}
+
+#[test]
+fn test_add_file_error() {
+    let err = asn1_compiler::Compiler::new()
+        .add_file("does-not-exist.asn1")
+        .err()
+        .unwrap();
+    assert!(err.to_string().starts_with("does-not-exist.asn1: "));
+}
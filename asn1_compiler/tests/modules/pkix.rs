@@ -0,0 +1,243 @@
+// Generated by asn1_compiler. Do not edit.
+
+pub mod pkix1_explicit88 {
+    #[allow(unused_imports)]
+    use super::pkix1_implicit88::{GeneralNames, KeyIdentifier};
+
+    pub const ID_PKIX: asn1::ObjectIdentifier =
+        asn1::ObjectIdentifier::from_dotted_str("1.3.6.1.5.5.7");
+
+    pub const ID_AT: asn1::ObjectIdentifier = asn1::ObjectIdentifier::from_dotted_str("2.5.4");
+
+    pub const ID_AT_COMMON_NAME: asn1::ObjectIdentifier =
+        asn1::ObjectIdentifier::from_dotted_str("2.5.4.3");
+
+    pub const UB_COMMON_NAME: i64 = 64;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct Certificate {
+        pub tbs_certificate: TBSCertificate,
+        pub signature_algorithm: AlgorithmIdentifier,
+        pub signature: asn1::OwnedBitString,
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct TBSCertificate {
+        #[explicit(0)]
+        #[default(0i64)]
+        pub version: Version,
+        pub serial_number: CertificateSerialNumber,
+        pub signature: AlgorithmIdentifier,
+        pub issuer: Name,
+        pub validity: Validity,
+        pub subject: Name,
+        pub subject_public_key_info: SubjectPublicKeyInfo,
+        #[implicit(1)]
+        pub issuer_unique_id: Option<UniqueIdentifier>,
+        #[implicit(2)]
+        pub subject_unique_id: Option<UniqueIdentifier>,
+        #[explicit(3)]
+        pub extensions: Option<Extensions>,
+    }
+
+    pub type Version = i64;
+
+    pub const VERSION_V1: i64 = 0;
+    pub const VERSION_V2: i64 = 1;
+    pub const VERSION_V3: i64 = 2;
+
+    pub type CertificateSerialNumber = asn1::OwnedBigInt;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct Validity {
+        pub not_before: Time,
+        pub not_after: Time,
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub enum Time {
+        UtcTime(asn1::UtcTime),
+        GeneralTime(asn1::GeneralizedTime),
+    }
+
+    pub type UniqueIdentifier = asn1::OwnedBitString;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct SubjectPublicKeyInfo {
+        pub algorithm: AlgorithmIdentifier,
+        pub subject_public_key: asn1::OwnedBitString,
+    }
+
+    pub type Extensions = Vec<Extension>;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct Extension {
+        pub extn_id: asn1::ObjectIdentifier,
+        #[default(false)]
+        pub critical: bool,
+        pub extn_value: asn1::OwnedOctetString,
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct AlgorithmIdentifier {
+        pub algorithm: asn1::ObjectIdentifier,
+        pub parameters: Option<asn1::OwnedTlv>,
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub enum Name {
+        RdnSequence(RDNSequence),
+    }
+
+    pub type RDNSequence = Vec<RelativeDistinguishedName>;
+
+    pub type RelativeDistinguishedName = asn1::OwnedSetOf<AttributeTypeAndValue>;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct AttributeTypeAndValue {
+        pub r#type: AttributeType,
+        pub value: AttributeValue,
+    }
+
+    pub type AttributeType = asn1::ObjectIdentifier;
+
+    pub type AttributeValue = asn1::OwnedTlv;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    #[asn1(extensible)]
+    pub enum DirectoryString {
+        PrintableString(asn1::OwnedPrintableString),
+        UniversalString(asn1::OwnedUniversalString),
+        Utf8String(String),
+        BmpString(asn1::OwnedBMPString),
+        #[extensions]
+        Unknown(asn1::OwnedTlv),
+    }
+}
+
+pub mod pkix1_implicit88 {
+    #[allow(unused_imports)]
+    use super::pkix1_explicit88::Name;
+
+    pub const ID_CE: asn1::ObjectIdentifier = asn1::ObjectIdentifier::from_dotted_str("2.5.29");
+
+    pub const ID_CE_BASIC_CONSTRAINTS: asn1::ObjectIdentifier =
+        asn1::ObjectIdentifier::from_dotted_str("2.5.29.19");
+
+    pub const ID_KP: asn1::ObjectIdentifier =
+        asn1::ObjectIdentifier::from_dotted_str("1.3.6.1.5.5.7.3");
+
+    pub type KeyIdentifier = asn1::OwnedOctetString;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct BasicConstraints {
+        #[default(false)]
+        pub c_a: bool,
+        pub path_len_constraint: Option<asn1::OwnedBigInt>,
+    }
+
+    pub type KeyUsage = asn1::OwnedBitString;
+
+    pub const KEY_USAGE_DIGITAL_SIGNATURE: usize = 0;
+    pub const KEY_USAGE_NON_REPUDIATION: usize = 1;
+    pub const KEY_USAGE_KEY_ENCIPHERMENT: usize = 2;
+
+    pub type GeneralNames = Vec<GeneralName>;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub enum GeneralName {
+        #[implicit(1)]
+        Rfc822Name(asn1::OwnedIA5String),
+        #[implicit(2)]
+        DNSName(asn1::OwnedIA5String),
+        #[explicit(4)]
+        DirectoryName(Name),
+        #[implicit(7)]
+        IPAddress(asn1::OwnedOctetString),
+        #[implicit(8)]
+        RegisteredID(asn1::ObjectIdentifier),
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct GeneralSubtree {
+        pub base: GeneralName,
+        #[implicit(0)]
+        #[default(0u8)]
+        pub minimum: BaseDistance,
+        #[implicit(1)]
+        pub maximum: Option<BaseDistance>,
+    }
+
+    pub type BaseDistance = u8;
+
+    pub type CRLReason = asn1::Enumerated;
+
+    pub const CRL_REASON_UNSPECIFIED: u32 = 0;
+    pub const CRL_REASON_KEY_COMPROMISE: u32 = 1;
+    pub const CRL_REASON_REMOVE_FROM_CRL: u32 = 8;
+}
+
+pub mod example {
+    #[allow(unused_imports)]
+    use super::pkix1_implicit88::KeyIdentifier;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct Header {
+        #[implicit(0, required)]
+        pub id: KeyIdentifier,
+        #[implicit(1)]
+        pub flags: Option<asn1::OwnedBitString>,
+    }
+
+    pub const HEADER_FLAGS_URGENT: usize = 0;
+    pub const HEADER_FLAGS_SIGNED: usize = 1;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    #[asn1(extensible)]
+    pub struct Options {
+        #[implicit(0)]
+        #[default(true)]
+        pub verbose: bool,
+        #[implicit(1)]
+        #[default(0i8)]
+        pub priority: i8,
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct Message {
+        #[implicit(0, required)]
+        pub id: KeyIdentifier,
+        #[implicit(1)]
+        pub flags: Option<asn1::OwnedBitString>,
+        #[explicit(2, required)]
+        pub body: MessageBody,
+        #[implicit(3)]
+        pub entries: Option<asn1::OwnedSetOf<MessageEntriesItem>>,
+        #[implicit(4)]
+        pub options: Option<Options>,
+        /// `None` when the value is the default, `1`.
+        #[implicit(5)]
+        pub count: Option<asn1::OwnedBigInt>,
+    }
+
+    pub const MESSAGE_FLAGS_URGENT: usize = 0;
+    pub const MESSAGE_FLAGS_SIGNED: usize = 1;
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub enum MessageBody {
+        #[implicit(0)]
+        Text(String),
+        #[implicit(1)]
+        Data(asn1::OwnedOctetString),
+        #[implicit(2)]
+        Empty(asn1::Null),
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    pub struct MessageEntriesItem {
+        #[implicit(0, required)]
+        pub key: asn1::OwnedPrintableString,
+        #[implicit(1, required)]
+        pub value: asn1::OwnedBigInt,
+    }
+}
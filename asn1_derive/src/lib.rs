@@ -5,19 +5,34 @@ use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
 
-#[proc_macro_derive(Asn1Read, attributes(explicit, implicit, default))]
+#[proc_macro_derive(
+    Asn1Read,
+    attributes(asn1, explicit, implicit, default, defined_by, extensions)
+)]
 pub fn derive_asn1_read(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
 
     let name = input.ident;
     let (impl_lifetimes, ty_lifetimes, lifetime_name) = add_lifetime_if_none(input.generics);
 
+    let properties = extract_container_properties(&input.attrs);
     let expanded = match input.data {
+        syn::Data::Struct(data) if properties.set => {
+            let read_block = generate_set_read_block(&name, &data, properties.extensible);
+            quote::quote! {
+                impl<#impl_lifetimes> asn1::SimpleAsn1Readable<#lifetime_name> for #name<#ty_lifetimes> {
+                    const TAG: asn1::Tag = asn1::Tag::constructed(0x11);
+                    fn parse_data(data: &#lifetime_name [u8]) -> asn1::ParseResult<Self> {
+                        asn1::parse(data, |p| #read_block)
+                    }
+                }
+            }
+        }
         syn::Data::Struct(data) => {
-            let read_block = generate_struct_read_block(&name, &data);
+            let read_block = generate_struct_read_block(&name, &data, properties.extensible);
             quote::quote! {
                 impl<#impl_lifetimes> asn1::SimpleAsn1Readable<#lifetime_name> for #name<#ty_lifetimes> {
-                    const TAG: u8 = <asn1::Sequence as asn1::SimpleAsn1Readable>::TAG;
+                    const TAG: asn1::Tag = <asn1::Sequence as asn1::SimpleAsn1Readable>::TAG;
                     fn parse_data(data: &#lifetime_name [u8]) -> asn1::ParseResult<Self> {
                         asn1::parse(data, |p| #read_block)
                     }
@@ -25,18 +40,17 @@ pub fn derive_asn1_read(input: proc_macro::TokenStream) -> proc_macro::TokenStre
             }
         }
         syn::Data::Enum(data) => {
-            let (read_block, can_parse_block) = generate_enum_read_block(&name, &data);
+            let (read_block, can_parse_block) =
+                generate_enum_read_block(&name, &data, properties.extensible);
             quote::quote! {
                 impl<#impl_lifetimes> asn1::Asn1Readable<#lifetime_name> for #name<#ty_lifetimes> {
                     fn parse(parser: &mut asn1::Parser<#lifetime_name>) -> asn1::ParseResult<Self> {
                         let tlv = parser.read_element::<asn1::Tlv>()?;
                         #read_block
-                        Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag{actual: tlv.tag()}))
                     }
 
-                    fn can_parse(tag: u8) -> bool {
+                    fn can_parse(tag: asn1::Tag) -> bool {
                         #can_parse_block
-                        false
                     }
                 }
             }
@@ -47,32 +61,59 @@ pub fn derive_asn1_read(input: proc_macro::TokenStream) -> proc_macro::TokenStre
     proc_macro::TokenStream::from(expanded)
 }
 
-#[proc_macro_derive(Asn1Write, attributes(explicit, implicit, default))]
+#[proc_macro_derive(
+    Asn1Write,
+    attributes(asn1, explicit, implicit, default, defined_by, extensions)
+)]
 pub fn derive_asn1_write(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
 
     let name = input.ident;
     let (impl_lifetimes, ty_lifetimes, lifetime_name) = add_lifetime_if_none(input.generics);
 
+    let properties = extract_container_properties(&input.attrs);
     let expanded = match input.data {
+        syn::Data::Struct(data) if properties.set => {
+            let (write_block, len_block) = generate_set_write_block(&data);
+            quote::quote! {
+                impl<#impl_lifetimes> asn1::SimpleAsn1Writable<#lifetime_name> for #name<#ty_lifetimes> {
+                    const TAG: asn1::Tag = asn1::Tag::constructed(0x11);
+                    fn write_data(&self, dest: &mut asn1::WriteBuf) -> asn1::WriteResult {
+                        #write_block
+                    }
+
+                    fn data_len(&self) -> Option<usize> {
+                        #len_block
+                    }
+                }
+            }
+        }
         syn::Data::Struct(data) => {
-            let write_block = generate_struct_write_block(&data);
+            let (write_block, len_block) = generate_struct_write_block(&data);
             quote::quote! {
                 impl<#impl_lifetimes> asn1::SimpleAsn1Writable<#lifetime_name> for #name<#ty_lifetimes> {
-                    const TAG: u8 = <asn1::SequenceWriter as asn1::SimpleAsn1Writable>::TAG;
-                    fn write_data(&self, dest: &mut Vec<u8>) {
+                    const TAG: asn1::Tag = <asn1::SequenceWriter as asn1::SimpleAsn1Writable>::TAG;
+                    fn write_data(&self, dest: &mut asn1::WriteBuf) -> asn1::WriteResult {
                         #write_block
                     }
+
+                    fn data_len(&self) -> Option<usize> {
+                        #len_block
+                    }
                 }
             }
         }
         syn::Data::Enum(data) => {
-            let write_block = generate_enum_write_block(&name, &data);
+            let (write_block, len_block) = generate_enum_write_block(&name, &data);
             quote::quote! {
                 impl<#impl_lifetimes> asn1::Asn1Writable<#lifetime_name> for #name<#ty_lifetimes> {
-                    fn write(&self, w: &mut asn1::Writer) {
+                    fn write(&self, w: &mut asn1::Writer) -> asn1::WriteResult {
                         #write_block
                     }
+
+                    fn encoded_len(&self) -> Option<usize> {
+                        #len_block
+                    }
                 }
             }
         }
@@ -82,6 +123,156 @@ pub fn derive_asn1_write(input: proc_macro::TokenStream) -> proc_macro::TokenStr
     proc_macro::TokenStream::from(expanded)
 }
 
+#[proc_macro_derive(Asn1DefinedByRead, attributes(defined_by))]
+pub fn derive_asn1_defined_by_read(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    let name = input.ident;
+    let (impl_lifetimes, ty_lifetimes, lifetime_name) = add_lifetime_if_none(input.generics);
+
+    let data = match input.data {
+        syn::Data::Enum(data) => data,
+        _ => panic!("Asn1DefinedByRead is only supported for enums"),
+    };
+
+    let mut read_blocks = vec![];
+    let mut default_read_block = None;
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        let error_location = format!("{}::{}", name, ident);
+        let add_error_location = quote::quote! {
+            .map_err(|e| e.add_location(asn1::ParseLocation::Field(#error_location)))
+        };
+        match extract_defined_by_variant(variant) {
+            DefinedByVariant::Oid(oid_expr, false) => read_blocks.push(quote::quote! {{
+                const OID: asn1::ObjectIdentifier = #oid_expr;
+                if *oid == OID {
+                    return Ok(#name::#ident);
+                }
+            }}),
+            DefinedByVariant::Oid(oid_expr, true) => read_blocks.push(quote::quote! {{
+                const OID: asn1::ObjectIdentifier = #oid_expr;
+                if *oid == OID {
+                    return Ok(#name::#ident(parser.read_element()#add_error_location?));
+                }
+            }}),
+            DefinedByVariant::Unknown => {
+                assert!(
+                    default_read_block.is_none(),
+                    "Can't specify #[defined_by(unknown)] on more than one variant"
+                );
+                default_read_block = Some(quote::quote! {
+                    Ok(#name::#ident(oid.clone(), parser.read_element()#add_error_location?))
+                });
+            }
+        }
+    }
+    let default_read_block = default_read_block.unwrap_or_else(|| {
+        quote::quote! {
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::UnknownDefinedBy))
+        }
+    });
+
+    let expanded = quote::quote! {
+        impl<#impl_lifetimes> asn1::Asn1DefinedByReadable<#lifetime_name> for #name<#ty_lifetimes> {
+            fn parse(
+                oid: &asn1::ObjectIdentifier,
+                parser: &mut asn1::Parser<#lifetime_name>,
+            ) -> asn1::ParseResult<Self> {
+                #(#read_blocks)*
+                #default_read_block
+            }
+        }
+    };
+
+    proc_macro::TokenStream::from(expanded)
+}
+
+#[proc_macro_derive(Asn1DefinedByWrite, attributes(defined_by))]
+pub fn derive_asn1_defined_by_write(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    let name = input.ident;
+    let (impl_lifetimes, ty_lifetimes, lifetime_name) = add_lifetime_if_none(input.generics);
+
+    let data = match input.data {
+        syn::Data::Enum(data) => data,
+        _ => panic!("Asn1DefinedByWrite is only supported for enums"),
+    };
+
+    let mut oid_arms = vec![];
+    let mut write_arms = vec![];
+    let mut len_arms = vec![];
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        match extract_defined_by_variant(variant) {
+            DefinedByVariant::Oid(oid_expr, false) => {
+                oid_arms.push(quote::quote! {
+                    #name::#ident => {
+                        static OID: asn1::ObjectIdentifier = #oid_expr;
+                        &OID
+                    }
+                });
+                write_arms.push(quote::quote! {
+                    #name::#ident => Ok(()),
+                });
+                len_arms.push(quote::quote! {
+                    #name::#ident => Some(0),
+                });
+            }
+            DefinedByVariant::Oid(oid_expr, true) => {
+                oid_arms.push(quote::quote! {
+                    #name::#ident(_) => {
+                        static OID: asn1::ObjectIdentifier = #oid_expr;
+                        &OID
+                    }
+                });
+                write_arms.push(quote::quote! {
+                    #name::#ident(value) => w.write_element(value),
+                });
+                len_arms.push(quote::quote! {
+                    #name::#ident(value) => asn1::Asn1Writable::encoded_len(value),
+                });
+            }
+            DefinedByVariant::Unknown => {
+                oid_arms.push(quote::quote! {
+                    #name::#ident(oid, _) => oid,
+                });
+                write_arms.push(quote::quote! {
+                    #name::#ident(_, value) => w.write_element(value),
+                });
+                len_arms.push(quote::quote! {
+                    #name::#ident(_, value) => asn1::Asn1Writable::encoded_len(value),
+                });
+            }
+        }
+    }
+
+    let expanded = quote::quote! {
+        impl<#impl_lifetimes> asn1::Asn1DefinedByWritable<#lifetime_name> for #name<#ty_lifetimes> {
+            fn oid(&self) -> &asn1::ObjectIdentifier {
+                match self {
+                    #(#oid_arms)*
+                }
+            }
+
+            fn write(&self, w: &mut asn1::Writer) -> asn1::WriteResult {
+                match self {
+                    #(#write_arms)*
+                }
+            }
+
+            fn encoded_len(&self) -> Option<usize> {
+                match self {
+                    #(#len_arms)*
+                }
+            }
+        }
+    };
+
+    proc_macro::TokenStream::from(expanded)
+}
+
 fn add_lifetime_if_none(
     mut generics: syn::Generics,
 ) -> (
@@ -133,6 +324,43 @@ impl syn::parse::Parse for OpTypeArgs {
     }
 }
 
+struct ContainerProperties {
+    // `#[asn1(set)]`: the struct is a `SET`, rather than a `SEQUENCE`.
+    set: bool,
+    // `#[asn1(extensible)]`: the type has an extension marker, so unknown
+    // elements (or alternatives) are permitted.
+    extensible: bool,
+}
+
+fn extract_container_properties(attrs: &[syn::Attribute]) -> ContainerProperties {
+    let mut properties = ContainerProperties {
+        set: false,
+        extensible: false,
+    };
+    for attr in attrs {
+        if attr.path.is_ident("asn1") {
+            let args = attr
+                .parse_args_with(Punctuated::<syn::Ident, Comma>::parse_terminated)
+                .unwrap();
+            for arg in args {
+                if arg == "set" {
+                    properties.set = true;
+                } else if arg == "extensible" {
+                    properties.extensible = true;
+                } else {
+                    panic!("Unknown #[asn1] argument");
+                }
+            }
+        }
+    }
+    properties
+}
+
+// Returns whether the field (or variant) is annotated with `#[extensions]`.
+fn is_extensions(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident("extensions"))
+}
+
 fn extract_field_properties(attrs: &[syn::Attribute]) -> (OpType, Option<syn::Lit>) {
     let mut op_type = OpType::Regular;
     let mut default = None;
@@ -158,12 +386,109 @@ fn extract_field_properties(attrs: &[syn::Attribute]) -> (OpType, Option<syn::Li
     (op_type, default)
 }
 
+// Returns the name of the field referred to by a `#[defined_by(field)]`
+// attribute, if there is one.
+fn extract_defined_by(attrs: &[syn::Attribute]) -> Option<syn::Ident> {
+    let mut defined_by = None;
+    for attr in attrs {
+        if attr.path.is_ident("defined_by") {
+            assert!(
+                defined_by.is_none(),
+                "Can't specify #[defined_by] more than once"
+            );
+            defined_by = Some(attr.parse_args::<syn::Ident>().unwrap());
+        }
+    }
+    defined_by
+}
+
+// The fields of a struct with a `#[defined_by(field)]` attribute, paired
+// with the field they refer to.
+fn defined_by_fields(fields: &syn::FieldsNamed) -> Vec<(&syn::Ident, syn::Ident)> {
+    fields
+        .named
+        .iter()
+        .filter_map(|f| {
+            let (op_type, default) = extract_field_properties(&f.attrs);
+            let oid_field = extract_defined_by(&f.attrs)?;
+            assert!(
+                matches!(op_type, OpType::Regular) && default.is_none(),
+                "#[defined_by] can't be combined with #[explicit], #[implicit], or #[default]"
+            );
+            Some((f.ident.as_ref().unwrap(), oid_field))
+        })
+        .collect()
+}
+
+fn defined_by_var(oid_field: &syn::Ident) -> syn::Ident {
+    quote::format_ident!("__asn1_defined_by_{}", oid_field)
+}
+
+enum DefinedByVariant {
+    // The `OBJECT IDENTIFIER` which selects the variant, and whether the
+    // variant has a value.
+    Oid(proc_macro2::TokenStream, bool),
+    // The variant for unknown `OBJECT IDENTIFIER`s, which holds it along with
+    // the value.
+    Unknown,
+}
+
+fn extract_defined_by_variant(variant: &syn::Variant) -> DefinedByVariant {
+    let mut result = None;
+    for attr in &variant.attrs {
+        if attr.path.is_ident("defined_by") {
+            assert!(
+                result.is_none(),
+                "Can't specify #[defined_by] more than once"
+            );
+            let args = attr.parse_args::<proc_macro2::TokenStream>().unwrap();
+            let is_unknown = syn::parse2::<syn::Ident>(args.clone())
+                .map(|ident| ident == "unknown")
+                .unwrap_or(false);
+            result = Some(if is_unknown {
+                DefinedByVariant::Unknown
+            } else {
+                DefinedByVariant::Oid(args, false)
+            });
+        }
+    }
+    let result = result.expect("Each variant must have a #[defined_by(...)] attribute");
+
+    match (result, &variant.fields) {
+        (DefinedByVariant::Oid(oid_expr, _), syn::Fields::Unit) => {
+            DefinedByVariant::Oid(oid_expr, false)
+        }
+        (DefinedByVariant::Oid(oid_expr, _), syn::Fields::Unnamed(fields))
+            if fields.unnamed.len() == 1 =>
+        {
+            DefinedByVariant::Oid(oid_expr, true)
+        }
+        (DefinedByVariant::Unknown, syn::Fields::Unnamed(fields)) if fields.unnamed.len() == 2 => {
+            DefinedByVariant::Unknown
+        }
+        (DefinedByVariant::Oid(..), _) => {
+            panic!("#[defined_by] variants must have no fields or a single un-named field")
+        }
+        (DefinedByVariant::Unknown, _) => panic!(
+            "#[defined_by(unknown)] variants must have two un-named fields: the OBJECT IDENTIFIER and the value"
+        ),
+    }
+}
+
 fn generate_read_element(
     struct_name: &syn::Ident,
     f: &syn::Field,
     f_name: &str,
 ) -> proc_macro2::TokenStream {
     let (read_type, default) = extract_field_properties(&f.attrs);
+    assert!(
+        extract_defined_by(&f.attrs).is_none(),
+        "#[defined_by] is only supported on named fields of SEQUENCEs"
+    );
+    assert!(
+        !is_extensions(&f.attrs),
+        "#[extensions] is only supported on the last field of a SEQUENCE"
+    );
 
     let error_location = format!("{}::{}", struct_name, f_name);
     let add_error_location = quote::quote! {
@@ -206,53 +531,262 @@ fn generate_read_element(
     read_op
 }
 
+// Generates the read of an `#[extensions]` field, which holds whatever
+// elements remain.
+fn generate_extensions_read(
+    struct_name: &syn::Ident,
+    f: &syn::Field,
+    f_name: &str,
+    is_last: bool,
+) -> proc_macro2::TokenStream {
+    let (op_type, default) = extract_field_properties(&f.attrs);
+    assert!(
+        is_last,
+        "#[extensions] is only supported on the last field of a SEQUENCE"
+    );
+    assert!(
+        matches!(op_type, OpType::Regular)
+            && default.is_none()
+            && extract_defined_by(&f.attrs).is_none(),
+        "#[extensions] can't be combined with other attributes"
+    );
+
+    let ty = &f.ty;
+    let error_location = format!("{}::{}", struct_name, f_name);
+    quote::quote! {
+        <#ty as asn1::Asn1ExtensionsReadable>::parse(p)
+            .map_err(|e| e.add_location(asn1::ParseLocation::Field(#error_location)))?
+    }
+}
+
 fn generate_struct_read_block(
     struct_name: &syn::Ident,
     data: &syn::DataStruct,
+    extensible: bool,
 ) -> proc_macro2::TokenStream {
-    match data.fields {
+    let field_count = data.fields.len();
+    let has_extensions = data.fields.iter().any(|f| is_extensions(&f.attrs));
+
+    // The `OBJECT IDENTIFIER`s of `#[defined_by]` fields are stored in locals
+    // as they're read, for use by the fields they select.
+    let mut declarations = vec![];
+    let construct = match data.fields {
         syn::Fields::Named(ref fields) => {
-            let recurse = fields.named.iter().map(|f| {
-                let name = &f.ident;
-                let read_op =
-                    generate_read_element(struct_name, f, &format!("{}", name.as_ref().unwrap()));
-                quote::quote_spanned! {f.span() =>
+            let defined_by = defined_by_fields(fields);
+            let mut recurse = vec![];
+            for (i, f) in fields.named.iter().enumerate() {
+                let name = f.ident.as_ref().unwrap();
+                let error_location = format!("{}::{}", struct_name, name);
+                let add_error_location = quote::quote! {
+                    .map_err(|e| e.add_location(asn1::ParseLocation::Field(#error_location)))
+                };
+                let read_op = if is_extensions(&f.attrs) {
+                    generate_extensions_read(
+                        struct_name,
+                        f,
+                        &format!("{}", name),
+                        i == field_count - 1,
+                    )
+                } else if let Some(oid_field) = extract_defined_by(&f.attrs) {
+                    assert!(
+                        fields
+                            .named
+                            .iter()
+                            .take_while(|other| other.ident.as_ref() != Some(name))
+                            .any(|other| other.ident.as_ref() == Some(&oid_field)),
+                        "#[defined_by] must refer to an earlier field"
+                    );
+                    let ty = &f.ty;
+                    let var = defined_by_var(&oid_field);
+                    quote::quote! {
+                        <#ty as asn1::Asn1DefinedByReadable>::parse(&#var, p)#add_error_location?
+                    }
+                } else if defined_by.iter().any(|(_, oid_field)| oid_field == name) {
+                    let var = defined_by_var(name);
+                    declarations.push(quote::quote! { let #var; });
+                    quote::quote! {{
+                        #var = p.read_element::<asn1::ObjectIdentifier>()#add_error_location?;
+                        asn1::DefinedByMarker
+                    }}
+                } else {
+                    generate_read_element(struct_name, f, &format!("{}", name))
+                };
+                recurse.push(quote::quote_spanned! {f.span() =>
                     #name: #read_op,
-                }
-            });
+                });
+            }
 
             quote::quote! {
-                Ok(Self {
+                Self {
                     #(#recurse)*
-                })
+                }
             }
         }
         syn::Fields::Unnamed(ref fields) => {
             let recurse = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                let read_op = generate_read_element(struct_name, f, &format!("{}", i));
+                let read_op = if is_extensions(&f.attrs) {
+                    generate_extensions_read(
+                        struct_name,
+                        f,
+                        &format!("{}", i),
+                        i == field_count - 1,
+                    )
+                } else {
+                    generate_read_element(struct_name, f, &format!("{}", i))
+                };
                 quote::quote_spanned! {f.span() =>
                     #read_op,
                 }
             });
 
             quote::quote! {
-                Ok(Self(
+                Self(
                     #(#recurse)*
-                ))
+                )
             }
         }
         syn::Fields::Unit => {
-            quote::quote! { Ok(Self) }
+            quote::quote! { Self }
         }
+    };
+
+    if extensible && !has_extensions {
+        // Unknown extensions are skipped.
+        quote::quote! {{
+            #(#declarations)*
+            let value = #construct;
+            while !p.is_empty() {
+                p.read_element::<asn1::Tlv>()?;
+            }
+            Ok(value)
+        }}
+    } else {
+        quote::quote! {{
+            #(#declarations)*
+            Ok(#construct)
+        }}
     }
 }
 
+// The fields of a struct, along with how each is named in error locations
+// and accessed when writing.
+fn struct_fields(data: &syn::DataStruct) -> Vec<(&syn::Field, String, proc_macro2::TokenStream)> {
+    match data.fields {
+        syn::Fields::Named(ref fields) => fields
+            .named
+            .iter()
+            .map(|f| {
+                let name = f.ident.as_ref().unwrap();
+                (f, format!("{}", name), quote::quote! { #name })
+            })
+            .collect(),
+        syn::Fields::Unnamed(ref fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let index = syn::Index::from(i);
+                (f, format!("{}", i), quote::quote! { #index })
+            })
+            .collect(),
+        syn::Fields::Unit => vec![],
+    }
+}
+
+// Generates an expression which tests whether `tag` belongs to the field.
+fn generate_field_tag_match(f: &syn::Field) -> proc_macro2::TokenStream {
+    let (op_type, _) = extract_field_properties(&f.attrs);
+    match op_type {
+        OpType::Regular => {
+            let ty = &f.ty;
+            quote::quote! { <#ty as asn1::Asn1Readable>::can_parse(tag) }
+        }
+        OpType::Explicit(arg) => {
+            let value = arg.value;
+            quote::quote! { tag == asn1::explicit_tag(#value) }
+        }
+        OpType::Implicit(arg) => {
+            let value = arg.value;
+            quote::quote! {
+                tag.class() == asn1::TagClass::ContextSpecific && tag.value() == #value
+            }
+        }
+    }
+}
+
+// Elements of a `SET` may appear in any order, so each element is matched to
+// a field by its tag before being parsed. Fields which are absent are parsed
+// from empty data, which succeeds only for `OPTIONAL` and `DEFAULT` fields.
+fn generate_set_read_block(
+    struct_name: &syn::Ident,
+    data: &syn::DataStruct,
+    extensible: bool,
+) -> proc_macro2::TokenStream {
+    let fields = struct_fields(data);
+
+    let mut slots = vec![];
+    let mut match_arms = vec![];
+    let mut field_reads = vec![];
+    for (i, (f, f_name, accessor)) in fields.iter().enumerate() {
+        let slot = quote::format_ident!("slot_{}", i);
+        let error_location = format!("{}::{}", struct_name, f_name);
+        let tag_match = generate_field_tag_match(f);
+        let read_op = generate_read_element(struct_name, f, f_name);
+
+        slots.push(quote::quote! {
+            let mut #slot: Option<asn1::Tlv> = None;
+        });
+        match_arms.push(quote::quote! {
+            if #tag_match {
+                if #slot.is_some() {
+                    return Err(asn1::ParseError::new(asn1::ParseErrorKind::DuplicateSetElement)
+                        .add_location(asn1::ParseLocation::Field(#error_location)));
+                }
+                #slot = Some(tlv);
+                continue;
+            }
+        });
+        field_reads.push(quote::quote_spanned! {f.span() =>
+            #accessor: match #slot {
+                Some(tlv) => asn1::parse::<_, asn1::ParseError, _>(tlv.full_data(), |p| Ok(#read_op))?,
+                None => asn1::parse::<_, asn1::ParseError, _>(&[], |p| Ok(#read_op))
+                    .map_err(|_| asn1::ParseError::new(asn1::ParseErrorKind::MissingSetElement)
+                        .add_location(asn1::ParseLocation::Field(#error_location)))?,
+            },
+        });
+    }
+
+    // Elements with unknown tags are skipped if the `SET` is extensible.
+    let unknown_element = if extensible {
+        quote::quote! {}
+    } else {
+        quote::quote! {
+            return Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag { actual: tag }));
+        }
+    };
+
+    quote::quote! {{
+        #(#slots)*
+        while !p.is_empty() {
+            let tlv = p.read_element::<asn1::Tlv>()?;
+            let tag = tlv.tag();
+            #(#match_arms)*
+            #unknown_element
+        }
+        Ok(Self {
+            #(#field_reads)*
+        })
+    }}
+}
+
 fn generate_enum_read_block(
     name: &syn::Ident,
     data: &syn::DataEnum,
+    extensible: bool,
 ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     let mut read_blocks = vec![];
     let mut can_parse_blocks = vec![];
+    let mut extensions_read_block = None;
 
     for variant in &data.variants {
         let field = match &variant.fields {
@@ -272,6 +806,21 @@ fn generate_enum_read_block(
         let add_error_location = quote::quote! {
             .map_err(|e| e.add_location(asn1::ParseLocation::Field(#error_location)))
         };
+        if is_extensions(&variant.attrs) {
+            assert!(
+                matches!(op_type, OpType::Regular),
+                "#[extensions] can't be combined with other attributes"
+            );
+            assert!(
+                extensions_read_block.is_none(),
+                "Can't specify #[extensions] on more than one variant"
+            );
+            // Tried only once none of the other variants match.
+            extensions_read_block = Some(quote::quote! {
+                Ok(#name::#ident(asn1::parse_single(tlv.full_data())#add_error_location?))
+            });
+            continue;
+        }
         match op_type {
             OpType::Regular => {
                 read_blocks.push(quote::quote! {
@@ -320,20 +869,47 @@ fn generate_enum_read_block(
         };
     }
 
-    let read_block = quote::quote! {
-        #(#read_blocks)*
+    let has_extensions = extensions_read_block.is_some();
+    assert!(
+        !extensible || has_extensions,
+        "Extensible CHOICEs must have an #[extensions] variant to hold unknown alternatives"
+    );
+    let read_block = match extensions_read_block {
+        Some(extensions_read_block) => quote::quote! {
+            #(#read_blocks)*
+            #extensions_read_block
+        },
+        None => quote::quote! {
+            #(#read_blocks)*
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag{actual: tlv.tag()}))
+        },
     };
-    let can_parse_block = quote::quote! {
-        #(#can_parse_blocks)*
+    let can_parse_block = if has_extensions {
+        quote::quote! { true }
+    } else {
+        quote::quote! {
+            #(#can_parse_blocks)*
+            false
+        }
     };
     (read_block, can_parse_block)
 }
 
+// Returns a statement which writes the field, and an expression for the
+// length of what it writes, if known.
 fn generate_write_element(
     f: &syn::Field,
     mut field_read: proc_macro2::TokenStream,
-) -> proc_macro2::TokenStream {
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     let (write_type, default) = extract_field_properties(&f.attrs);
+    assert!(
+        extract_defined_by(&f.attrs).is_none(),
+        "#[defined_by] is only supported on named fields of SEQUENCEs"
+    );
+    assert!(
+        !is_extensions(&f.attrs),
+        "#[extensions] is only supported on the last field of a SEQUENCE"
+    );
 
     if let Some(default) = default {
         field_read = quote::quote! {&{
@@ -345,105 +921,258 @@ fn generate_write_element(
         OpType::Explicit(arg) => {
             let value = arg.value;
             if arg.required {
-                quote::quote_spanned! {f.span() =>
-                    w.write_explicit_element(#field_read, #value);
-                }
+                (
+                    quote::quote_spanned! {f.span() =>
+                        w.write_explicit_element(#field_read, #value)?;
+                    },
+                    quote::quote! {
+                        asn1::explicit_encoded_len(#field_read, #value)
+                    },
+                )
             } else {
-                quote::quote_spanned! {f.span() =>
-                    w.write_optional_explicit_element(#field_read, #value);
-                }
+                (
+                    quote::quote_spanned! {f.span() =>
+                        w.write_optional_explicit_element(#field_read, #value)?;
+                    },
+                    quote::quote! {
+                        match #field_read {
+                            Some(v) => asn1::explicit_encoded_len(v, #value),
+                            None => Some(0),
+                        }
+                    },
+                )
             }
         }
         OpType::Implicit(arg) => {
             let value = arg.value;
             if arg.required {
-                quote::quote_spanned! {f.span() =>
-                    w.write_implicit_element(#field_read, #value);
-                }
+                (
+                    quote::quote_spanned! {f.span() =>
+                        w.write_implicit_element(#field_read, #value)?;
+                    },
+                    quote::quote! {
+                        asn1::implicit_encoded_len(#field_read, #value)
+                    },
+                )
             } else {
-                quote::quote_spanned! {f.span() =>
-                    w.write_optional_implicit_element(#field_read, #value);
-                }
+                (
+                    quote::quote_spanned! {f.span() =>
+                        w.write_optional_implicit_element(#field_read, #value)?;
+                    },
+                    quote::quote! {
+                        match #field_read {
+                            Some(v) => asn1::implicit_encoded_len(v, #value),
+                            None => Some(0),
+                        }
+                    },
+                )
             }
         }
-        OpType::Regular => quote::quote! {
-            w.write_element(#field_read);
-        },
+        OpType::Regular => (
+            quote::quote! {
+                w.write_element(#field_read)?;
+            },
+            quote::quote! {
+                asn1::Asn1Writable::encoded_len(#field_read)
+            },
+        ),
+    }
+}
+
+// Generates the body of a `data_len` which sums the lengths of the fields.
+fn generate_data_len_block(lengths: Vec<proc_macro2::TokenStream>) -> proc_macro2::TokenStream {
+    quote::quote! {
+        let len: usize = 0;
+        #(let len = len.checked_add(#lengths?)?;)*
+        Some(len)
     }
 }
 
-fn generate_struct_write_block(data: &syn::DataStruct) -> proc_macro2::TokenStream {
+// Returns the bodies of `write_data` and `data_len` for a `SEQUENCE`.
+fn generate_struct_write_block(
+    data: &syn::DataStruct,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     match data.fields {
         syn::Fields::Named(ref fields) => {
-            let recurse = fields.named.iter().map(|f| {
-                let name = &f.ident;
-                generate_write_element(f, quote::quote! { &self.#name })
-            });
+            let defined_by = defined_by_fields(fields);
+            let (writes, lengths): (Vec<_>, Vec<_>) = fields
+                .named
+                .iter()
+                .map(|f| {
+                    let name = f.ident.as_ref().unwrap();
+                    if is_extensions(&f.attrs) {
+                        (
+                            quote::quote! {
+                                asn1::Asn1ExtensionsWritable::write(&self.#name, &mut w)?;
+                            },
+                            quote::quote! {
+                                asn1::Asn1ExtensionsWritable::encoded_len(&self.#name)
+                            },
+                        )
+                    } else if extract_defined_by(&f.attrs).is_some() {
+                        (
+                            quote::quote! {
+                                asn1::Asn1DefinedByWritable::write(&self.#name, &mut w)?;
+                            },
+                            quote::quote! {
+                                asn1::Asn1DefinedByWritable::encoded_len(&self.#name)
+                            },
+                        )
+                    } else if let Some((field, _)) =
+                        defined_by.iter().find(|(_, oid_field)| oid_field == name)
+                    {
+                        (
+                            quote::quote! {
+                                w.write_element(asn1::Asn1DefinedByWritable::oid(&self.#field))?;
+                            },
+                            quote::quote! {
+                                asn1::Asn1Writable::encoded_len(
+                                    asn1::Asn1DefinedByWritable::oid(&self.#field)
+                                )
+                            },
+                        )
+                    } else {
+                        generate_write_element(f, quote::quote! { &self.#name })
+                    }
+                })
+                .unzip();
 
-            quote::quote! {
-                let mut w = asn1::Writer::new(dest);
-                #(#recurse)*
-            }
+            let len_block = generate_data_len_block(lengths);
+            (
+                quote::quote! {
+                    let mut w = asn1::Writer::new(dest);
+                    #(#writes)*
+                    Ok(())
+                },
+                len_block,
+            )
         }
         syn::Fields::Unnamed(ref fields) => {
-            let recurse = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                let index = syn::Index::from(i);
-                generate_write_element(f, quote::quote! { &self.#index })
-            });
+            let (writes, lengths): (Vec<_>, Vec<_>) = fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    let index = syn::Index::from(i);
+                    if is_extensions(&f.attrs) {
+                        (
+                            quote::quote! {
+                                asn1::Asn1ExtensionsWritable::write(&self.#index, &mut w)?;
+                            },
+                            quote::quote! {
+                                asn1::Asn1ExtensionsWritable::encoded_len(&self.#index)
+                            },
+                        )
+                    } else {
+                        generate_write_element(f, quote::quote! { &self.#index })
+                    }
+                })
+                .unzip();
 
-            quote::quote! {
-                let mut w = asn1::Writer::new(dest);
-                #(#recurse)*
-            }
-        }
-        syn::Fields::Unit => {
-            quote::quote! {}
+            let len_block = generate_data_len_block(lengths);
+            (
+                quote::quote! {
+                    let mut w = asn1::Writer::new(dest);
+                    #(#writes)*
+                    Ok(())
+                },
+                len_block,
+            )
         }
+        syn::Fields::Unit => (quote::quote! { Ok(()) }, quote::quote! { Some(0) }),
     }
 }
 
-fn generate_enum_write_block(name: &syn::Ident, data: &syn::DataEnum) -> proc_macro2::TokenStream {
-    let write_arms = data.variants.iter().map(|v| {
-        match &v.fields {
-            syn::Fields::Unnamed(fields) => {
-                assert_eq!(fields.unnamed.len(), 1);
-            }
-            _ => panic!("enum elements must have a single field"),
-        };
-        let (op_type, default) = extract_field_properties(&v.attrs);
-        assert!(default.is_none());
-        let ident = &v.ident;
+// The fields of a `SET` are written in order, and then sorted in place into
+// the canonical order of their tags.
+fn generate_set_write_block(
+    data: &syn::DataStruct,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let (writes, lengths): (Vec<_>, Vec<_>) = struct_fields(data)
+        .into_iter()
+        .map(|(f, _, accessor)| generate_write_element(f, quote::quote! { &self.#accessor }))
+        .unzip();
 
-        match op_type {
-            OpType::Regular => {
-                quote::quote! {
-                    #name::#ident(value) => w.write_element(value),
+    let len_block = generate_data_len_block(lengths);
+    (
+        quote::quote! {
+            let start = dest.len();
+            let mut w = asn1::Writer::new(dest);
+            #(#writes)*
+            asn1::sort_set_elements(dest, start)
+        },
+        len_block,
+    )
+}
+
+// Returns the bodies of `write` and `encoded_len` for a `CHOICE`.
+fn generate_enum_write_block(
+    name: &syn::Ident,
+    data: &syn::DataEnum,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let (write_arms, len_arms): (Vec<_>, Vec<_>) = data
+        .variants
+        .iter()
+        .map(|v| {
+            match &v.fields {
+                syn::Fields::Unnamed(fields) => {
+                    assert_eq!(fields.unnamed.len(), 1);
                 }
-            }
-            OpType::Explicit(arg) => {
-                let tag = arg.value;
-                quote::quote! {
-                    #name::#ident(value) => w.write_optional_explicit_element(&Some(value), #tag),
+                _ => panic!("enum elements must have a single field"),
+            };
+            let (op_type, default) = extract_field_properties(&v.attrs);
+            assert!(default.is_none());
+            let ident = &v.ident;
+
+            match op_type {
+                OpType::Regular => (
+                    quote::quote! {
+                        #name::#ident(value) => w.write_element(value),
+                    },
+                    quote::quote! {
+                        #name::#ident(value) => asn1::Asn1Writable::encoded_len(value),
+                    },
+                ),
+                OpType::Explicit(arg) => {
+                    let tag = arg.value;
+                    (
+                        quote::quote! {
+                            #name::#ident(value) => w.write_explicit_element(value, #tag),
+                        },
+                        quote::quote! {
+                            #name::#ident(value) => asn1::explicit_encoded_len(value, #tag),
+                        },
+                    )
                 }
-            }
-            OpType::Implicit(arg) => {
-                let tag = arg.value;
-                quote::quote! {
-                    #name::#ident(value) => w.write_optional_implicit_element(&Some(value), #tag),
+                OpType::Implicit(arg) => {
+                    let tag = arg.value;
+                    (
+                        quote::quote! {
+                            #name::#ident(value) => w.write_optional_implicit_element(&Some(value), #tag),
+                        },
+                        quote::quote! {
+                            #name::#ident(value) => asn1::implicit_encoded_len(value, #tag),
+                        },
+                    )
                 }
             }
-        }
-    });
-    quote::quote! {
-        match self {
-            #(#write_arms)*
-        }
-    }
+        })
+        .unzip();
+    (
+        quote::quote! {
+            match self {
+                #(#write_arms)*
+            }
+        },
+        quote::quote! {
+            match self {
+                #(#len_arms)*
+            }
+        },
+    )
 }
 
-// TODO: Duplicate of this function in src/object_identifier.rs, can we
-// de-dupe?
-fn _write_base128_int(data: &mut Vec<u8>, n: u32) {
+fn _write_base128_int(data: &mut Vec<u8>, n: u128) {
     if n == 0 {
         data.push(0);
         return;
@@ -474,20 +1203,26 @@ pub fn oid(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut arcs = p_arcs.iter();
 
     let mut der_encoded = vec![];
-    let first = arcs.next().unwrap().base10_parse::<u32>().unwrap();
-    let second = arcs.next().unwrap().base10_parse::<u32>().unwrap();
-    _write_base128_int(&mut der_encoded, 40 * first + second);
+    let first = arcs.next().unwrap().base10_parse::<u128>().unwrap();
+    let second = arcs.next().unwrap().base10_parse::<u128>().unwrap();
+    _write_base128_int(&mut der_encoded, (40 * first).checked_add(second).unwrap());
     for arc in arcs {
         _write_base128_int(&mut der_encoded, arc.base10_parse().unwrap());
     }
 
     let der_len = der_encoded.len();
-    // TODO: is there a way to use the `MAX_OID_LENGTH` constant here?
-    assert!(der_len <= 63);
-    der_encoded.resize(63, 0);
-    let der_lit = syn::LitByteStr::new(&der_encoded, proc_macro2::Span::call_site());
-    let expanded = quote::quote! {
-        asn1::ObjectIdentifier::from_der_unchecked(*#der_lit, #der_len as u8)
+    // OIDs up to 63 bytes long are stored inline.
+    let expanded = if der_len <= 63 {
+        der_encoded.resize(63, 0);
+        let der_lit = syn::LitByteStr::new(&der_encoded, proc_macro2::Span::call_site());
+        quote::quote! {
+            asn1::ObjectIdentifier::from_der_unchecked(*#der_lit, #der_len as u8)
+        }
+    } else {
+        let der_lit = syn::LitByteStr::new(&der_encoded, proc_macro2::Span::call_site());
+        quote::quote! {
+            asn1::ObjectIdentifier::from_static_der_unchecked(#der_lit)
+        }
     };
 
     proc_macro::TokenStream::from(expanded)
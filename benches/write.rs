@@ -0,0 +1,137 @@
+//! Compares writing values whose lengths are known before they're written,
+//! which is the case for the crate's types and derived types, with writing
+//! the same values with `SequenceWriter`s, whose lengths aren't known, and so
+//! whose contents must be moved to make room for lengths of 128 bytes or
+//! more. Run with `cargo bench`.
+
+use std::time::{Duration, Instant};
+
+#[derive(asn1::Asn1Write)]
+struct RevokedCertificate {
+    serial_number: u64,
+    revocation_date: asn1::UtcTime,
+}
+
+#[derive(asn1::Asn1Write)]
+struct TbsCertList {
+    version: u8,
+    this_update: asn1::UtcTime,
+    revoked_certificates: Vec<RevokedCertificate>,
+}
+
+#[derive(asn1::Asn1Write)]
+struct CertificateList {
+    tbs_cert_list: TbsCertList,
+    signature: asn1::OwnedBitString,
+}
+
+fn revocation_date() -> asn1::UtcTime {
+    asn1::UtcTime::new(asn1::DateTime::new(2021, 6, 1, 12, 0, 0).unwrap()).unwrap()
+}
+
+// A CRL with `n` entries.
+fn crl(n: u64) -> CertificateList {
+    CertificateList {
+        tbs_cert_list: TbsCertList {
+            version: 1,
+            this_update: revocation_date(),
+            revoked_certificates: (0..n)
+                .map(|serial_number| RevokedCertificate {
+                    serial_number,
+                    revocation_date: revocation_date(),
+                })
+                .collect(),
+        },
+        signature: asn1::OwnedBitString::new(vec![0xab; 256], 0).unwrap(),
+    }
+}
+
+// Writes `crl` with `SequenceWriter`s instead of its `Asn1Writable`
+// implementation.
+fn write_crl_unknown_lengths(crl: &CertificateList) -> Vec<u8> {
+    let tbs = &crl.tbs_cert_list;
+    asn1::write(|w| {
+        w.write_element(&asn1::SequenceWriter::new(&|w| {
+            w.write_element(&asn1::SequenceWriter::new(&|w| {
+                w.write_element(&tbs.version)?;
+                w.write_element(&tbs.this_update)?;
+                w.write_element(&asn1::SequenceWriter::new(&|w| {
+                    for entry in &tbs.revoked_certificates {
+                        w.write_element(&asn1::SequenceWriter::new(&|w| {
+                            w.write_element(&entry.serial_number)?;
+                            w.write_element(&entry.revocation_date)
+                        }))?;
+                    }
+                    Ok(())
+                }))
+            }))?;
+            w.write_element(&crl.signature)
+        }))
+    })
+    .unwrap()
+}
+
+// `depth` nested `SEQUENCE`s around a large `OCTET STRING`.
+fn nested(depth: usize, payload: &[u8]) -> asn1::Value<'_> {
+    let mut value = asn1::Value::OctetString(payload);
+    for _ in 0..depth {
+        value = asn1::Value::Sequence(vec![value]);
+    }
+    value
+}
+
+fn write_nested_unknown_lengths(
+    w: &mut asn1::Writer,
+    depth: usize,
+    payload: &[u8],
+) -> asn1::WriteResult {
+    if depth == 0 {
+        return w.write_element(&payload);
+    }
+    w.write_element(&asn1::SequenceWriter::new(&|w| {
+        write_nested_unknown_lengths(w, depth - 1, payload)
+    }))
+}
+
+// Runs `f` repeatedly for about a second, and prints the average time each
+// run took.
+fn bench<F: Fn() -> Vec<u8>>(name: &str, expected: &[u8], f: F) {
+    assert_eq!(f(), expected);
+
+    let start = Instant::now();
+    let mut iterations = 0;
+    while start.elapsed() < Duration::from_secs(1) {
+        assert_eq!(f().len(), expected.len());
+        iterations += 1;
+    }
+    println!("{:<40} {:>12?}", name, start.elapsed() / iterations);
+}
+
+fn main() {
+    for &n in &[1_000, 100_000] {
+        let crl = crl(n);
+        let expected = write_crl_unknown_lengths(&crl);
+        bench(&format!("crl/{}/unknown lengths", n), &expected, || {
+            write_crl_unknown_lengths(&crl)
+        });
+        bench(&format!("crl/{}/known lengths", n), &expected, || {
+            asn1::write_single(&crl).unwrap()
+        });
+    }
+
+    let payload = vec![0x42; 1 << 20];
+    for &depth in &[1, 16] {
+        let value = nested(depth, &payload);
+        let expected = asn1::write(|w| write_nested_unknown_lengths(w, depth, &payload)).unwrap();
+        bench(
+            &format!("nested/{}/unknown lengths", depth),
+            &expected,
+            || asn1::write(|w| write_nested_unknown_lengths(w, depth, &payload)).unwrap(),
+        );
+        bench(
+            &format!("nested/{}/known lengths", depth),
+            &expected,
+            || asn1::write_single(&value).unwrap(),
+        );
+    }
+}
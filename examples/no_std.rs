@@ -1,5 +1,15 @@
 #![no_std]
 
+// Nothing here allocates: values are written into caller-provided buffers,
+// which is how everything is written when `asn1` is built without its `alloc`
+// feature.
+
+#[derive(asn1::Asn1Read, asn1::Asn1Write)]
+struct Signature {
+    r: i64,
+    s: i64,
+}
+
 fn main() {
     let data = b"\x30\x06\x02\x01\x01\x02\x01\x03";
 
@@ -16,17 +26,34 @@ fn main() {
         Err(_) => unsafe { libc::printf("Error\n\x00".as_ptr() as *const libc::c_char) },
     };
 
-    let computed = asn1::write(|w| {
+    // Values are written into a caller-provided buffer, rather than a `Vec`.
+    let mut buf = [0u8; 16];
+    let computed = asn1::write_to_slice(&mut buf, |w| {
         w.write_element(&asn1::SequenceWriter::new(&|w: &mut asn1::Writer| {
-            w.write_element(&1i64);
-            w.write_element(&3i64);
-        }));
-    });
+            w.write_element(&1i64)?;
+            w.write_element(&3i64)?;
+            Ok(())
+        }))
+    })
+    .unwrap();
     unsafe {
         libc::printf(
             "Original length: %ld\nComputed length: %ld\n\x00".as_ptr() as *const libc::c_char,
             data.len() as i64,
-            computed.len() as i64,
+            computed as i64,
+        );
+    }
+
+    // The same goes for derived types, and writing fails if the buffer is too
+    // small.
+    let sig = Signature { r: 1, s: 3 };
+    let derived = asn1::write_single_to_slice(&sig, &mut buf).unwrap();
+    let too_small = asn1::write_single_to_slice(&sig, &mut buf[..4]);
+    unsafe {
+        libc::printf(
+            "Derived length: %ld\nToo small: %d\n\x00".as_ptr() as *const libc::c_char,
+            derived as i64,
+            (too_small == Err(asn1::WriteError::BufferTooSmall)) as libc::c_int,
         );
     }
 }
@@ -0,0 +1,712 @@
+use crate::parser::{parse, ParseError, ParseErrorKind, ParseResult, Parser};
+use crate::tag::{Tag, TagClass};
+use crate::types::{write_set_of_elements, Asn1Readable, GeneralizedTime, SimpleAsn1Writable};
+use crate::writer::{WriteBuf, WriteError, WriteResult, Writer};
+use alloc::borrow::Cow;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Nesting of constructed values beyond this depth is rejected, so that
+/// re-encoding untrusted input can't exhaust the stack. This API is public so
+/// that the same limit may be used by the `asn1` binary, but should not be
+/// considered a part of the supported API surface.
+pub const MAX_DEPTH: u32 = 128;
+
+const END_OF_CONTENTS: Tag = Tag::primitive(0x00);
+const BOOLEAN: Tag = Tag::primitive(0x01);
+const BIT_STRING: u32 = 0x03;
+const SET: Tag = Tag::constructed(0x11);
+const GENERALIZED_TIME: u32 = 0x18;
+
+/// BER encoded data which has been re-encoded as DER, so that it can be read
+/// with the usual (strict) parsing machinery.
+///
+/// The following BER-isms are accepted and normalized:
+///
+/// - indefinite length encodings of constructed values
+/// - lengths which are not minimally encoded
+/// - constructed encodings of `OCTET STRING`, `BIT STRING`, and the
+///   character string and time types, which are merged into a single
+///   primitive value
+/// - `BOOLEAN` values other than `0xff` for `TRUE`
+/// - non-zero unused bits at the end of a `BIT STRING`
+/// - elements of a `SET` or `SET OF` which aren't in canonical order
+/// - `GeneralizedTime` values with an offset from UTC, a `,` decimal
+///   separator, or trailing zeros in their fractional seconds
+///
+/// This is done without knowledge of the schema, so strings and booleans are
+/// only normalized when they carry their `UNIVERSAL` tag. Values that are
+/// `IMPLICIT`ly tagged are left as they are, and will fail to parse if they
+/// aren't already valid DER.
+///
+/// Everything else, e.g. the encoding of `INTEGER`s, is checked when parsing,
+/// exactly as it would be for DER.
+pub struct BerInput<'a> {
+    der: Cow<'a, [u8]>,
+}
+
+impl<'a> BerInput<'a> {
+    /// Decodes `data` as a series of BER elements. `data` is always
+    /// re-encoded, which is how it's checked for being DER; if it already
+    /// was, the re-encoding is discarded and `data` is borrowed instead.
+    pub fn new(data: &'a [u8]) -> ParseResult<BerInput<'a>> {
+        let mut der = WriteBuf::new();
+        let mut p = Parser::new(data);
+        while !p.is_empty() {
+            reencode_element(&mut p, &mut Writer::new(&mut der), 0)?;
+        }
+        let der = der.into_vec();
+
+        let der = if der == data {
+            Cow::Borrowed(data)
+        } else {
+            Cow::Owned(der)
+        };
+        Ok(BerInput { der })
+    }
+
+    /// Returns whether the input was already DER, i.e. no re-encoding was
+    /// necessary. Useful for flagging producers of non-canonical encodings.
+    pub fn is_der(&self) -> bool {
+        match self.der {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false,
+        }
+    }
+
+    /// Returns the data re-encoded as DER.
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// Parses the re-encoded data, see [`parse`].
+    pub fn parse<'b, T, E: From<ParseError>, F: Fn(&mut Parser<'b>) -> Result<T, E>>(
+        &'b self,
+        f: F,
+    ) -> Result<T, E> {
+        parse(&self.der, f)
+    }
+
+    /// Parses a single top-level element from the re-encoded data, see
+    /// [`parse_single`](crate::parse_single).
+    pub fn parse_single<'b, T: Asn1Readable<'b>>(&'b self) -> ParseResult<T> {
+        self.parse(|p| p.read_element::<T>())
+    }
+}
+
+/// Re-encodes the BER elements in `data` as DER. See [`BerInput`] for
+/// details on what is normalized.
+///
+/// This is useful when a signature is computed over the DER encoding of a
+/// structure, but it was transmitted as BER.
+pub fn ber_to_der(data: &[u8]) -> ParseResult<Vec<u8>> {
+    Ok(BerInput::new(data)?.der.into_owned())
+}
+
+/// Like [`parse`], but accepts BER encoded `data`. Because `data` may need
+/// to be re-encoded, the result cannot borrow from it; use [`BerInput`]
+/// directly to parse types which borrow.
+pub fn parse_ber<T, E, F>(data: &[u8], f: F) -> Result<T, E>
+where
+    E: From<ParseError>,
+    F: for<'b> Fn(&mut Parser<'b>) -> Result<T, E>,
+{
+    BerInput::new(data)?.parse(f)
+}
+
+/// Like [`parse_single`](crate::parse_single), but accepts BER encoded
+/// `data`. See [`parse_ber`].
+pub fn parse_single_ber<T: for<'b> Asn1Readable<'b>>(data: &[u8]) -> ParseResult<T> {
+    parse_ber(data, |p| p.read_element::<T>())
+}
+
+// Reads a BER length. `None` indicates the indefinite form.
+fn read_length(p: &mut Parser<'_>) -> ParseResult<Option<usize>> {
+    let b = p.read_u8()?;
+    if b & 0x80 == 0 {
+        return Ok(Some(b as usize));
+    }
+    let num_bytes = b & 0x7f;
+    if num_bytes == 0 {
+        return Ok(None);
+    }
+    // 0xff is reserved for future extensions.
+    if num_bytes == 0x7f {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue));
+    }
+
+    let mut length = 0;
+    for _ in 0..num_bytes {
+        let b = p.read_u8()?;
+        if length > (usize::MAX >> 8) {
+            return Err(ParseError::new(ParseErrorKind::IntegerOverflow));
+        }
+        length <<= 8;
+        length |= b as usize;
+    }
+    Ok(Some(length))
+}
+
+// Reads the contents of a constructed value, either up to the end of the
+// provided length, or up to the end-of-contents marker. Returns a parser over
+// the contents for definite lengths; for indefinite lengths the contents are
+// read from `p` itself by `next_child`.
+fn contents<'a>(p: &mut Parser<'a>, length: Option<usize>) -> ParseResult<Option<Parser<'a>>> {
+    match length {
+        Some(length) => Ok(Some(Parser::new(p.read_bytes(length)?))),
+        None => Ok(None),
+    }
+}
+
+// Returns whether there is another child element to be read, consuming the
+// end-of-contents marker if it is reached.
+fn next_child(p: &mut Parser<'_>, indefinite: bool) -> ParseResult<bool> {
+    if !indefinite {
+        return Ok(!p.is_empty());
+    }
+    if p.is_empty() {
+        return Err(ParseError::new(ParseErrorKind::ShortData));
+    }
+    if p.peek_tag() == Some(END_OF_CONTENTS) {
+        p.read_tag()?;
+        if p.read_u8()? != 0 {
+            return Err(ParseError::new(ParseErrorKind::InvalidValue));
+        }
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+fn is_string_type(tag: Tag) -> bool {
+    tag.class() == TagClass::Universal
+        && match tag.value() {
+            // BIT STRING, OCTET STRING, ObjectDescriptor, UTF8String
+            0x03 | 0x04 | 0x07 | 0x0c => true,
+            // NumericString through GeneralizedTime, GraphicString through
+            // UniversalString, and BMPString
+            0x12..=0x1c | 0x1e => true,
+            _ => false,
+        }
+}
+
+fn reencode_element(p: &mut Parser<'_>, w: &mut Writer<'_, '_>, depth: u32) -> ParseResult<()> {
+    if depth > MAX_DEPTH {
+        return Err(ParseError::new(ParseErrorKind::ExceedsMaxDepth));
+    }
+
+    let tag = p.read_tag()?;
+    if tag == END_OF_CONTENTS {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue));
+    }
+    let length = read_length(p)?;
+
+    if !tag.is_constructed() {
+        // The indefinite form is only permitted for constructed values.
+        let length = length.ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))?;
+        let data = p.read_bytes(length)?;
+        let result = if tag == BOOLEAN && data.len() == 1 && data[0] != 0 {
+            w.write_tlv(tag, |dest| dest.push_byte(0xff))
+        } else if tag == Tag::primitive(BIT_STRING) {
+            w.write_tlv(tag, |dest| write_bit_string(dest, data))
+        } else if tag == Tag::primitive(GENERALIZED_TIME) {
+            let time = GeneralizedTime::parse_ber_data(data)?;
+            w.write_tlv(tag, |dest| time.write_data(dest))
+        } else {
+            w.write_tlv(tag, |dest| dest.push_slice(data))
+        };
+        return result.map_err(write_error);
+    }
+
+    let mut inner = contents(p, length)?;
+    let child_parser = inner.as_mut().unwrap_or(p);
+    let indefinite = length.is_none();
+
+    if is_string_type(tag) {
+        let mut data = Vec::new();
+        let mut padding_bits = None;
+        collect_segments(
+            child_parser,
+            indefinite,
+            tag.value(),
+            &mut data,
+            &mut padding_bits,
+            depth + 1,
+        )?;
+        let primitive_tag = Tag::primitive(tag.value());
+        if tag.value() == BIT_STRING {
+            let padding_bits = padding_bits.unwrap_or(0);
+            if data.is_empty() && padding_bits != 0 {
+                return Err(ParseError::new(ParseErrorKind::InvalidValue));
+            }
+            data.insert(0, padding_bits);
+            w.write_tlv(primitive_tag, |dest| write_bit_string(dest, &data))
+        } else if tag.value() == GENERALIZED_TIME {
+            let time = GeneralizedTime::parse_ber_data(&data)?;
+            w.write_tlv(primitive_tag, |dest| time.write_data(dest))
+        } else {
+            w.write_tlv(primitive_tag, |dest| dest.push_slice(&data))
+        }
+        .map_err(write_error)?;
+        return Ok(());
+    }
+
+    let mut result = Ok(());
+    w.write_tlv(tag, |dest| {
+        result = if tag == SET {
+            reencode_set(child_parser, indefinite, dest, depth)
+        } else {
+            reencode_children(child_parser, indefinite, dest, depth)
+        };
+        Ok(())
+    })
+    .map_err(write_error)?;
+    result
+}
+
+fn reencode_children(
+    p: &mut Parser<'_>,
+    indefinite: bool,
+    dest: &mut WriteBuf,
+    depth: u32,
+) -> ParseResult<()> {
+    let mut w = Writer::new(dest);
+    while next_child(p, indefinite)? {
+        reencode_element(p, &mut w, depth + 1)?;
+    }
+    Ok(())
+}
+
+// Without the schema, `SET` and `SET OF` can't be told apart. Elements whose
+// tags are distinct are sorted by their tags, as DER requires for a `SET`;
+// anything else is sorted as a `SET OF`.
+fn reencode_set(
+    p: &mut Parser<'_>,
+    indefinite: bool,
+    dest: &mut WriteBuf,
+    depth: u32,
+) -> ParseResult<()> {
+    // Re-encoding always writes into a `Vec`, so there's always scratch
+    // space.
+    let mut data = dest.scratch().unwrap();
+    let mut w = Writer::new(&mut data);
+    let mut spans = vec![];
+    while next_child(p, indefinite)? {
+        let start = w.data.len();
+        reencode_element(p, &mut w, depth + 1)?;
+        spans.push(start..w.data.len());
+    }
+
+    let data = data.as_slice();
+    let tag = |span: &Range<usize>| {
+        let (tag, _) = Tag::from_bytes(&data[span.clone()]).unwrap();
+        (tag.class(), tag.value())
+    };
+    // The order of tags isn't that of their encodings, e.g. for high tag
+    // numbers, so they're compared as tags.
+    spans.sort_by_key(tag);
+    if spans.windows(2).all(|w| tag(&w[0]) != tag(&w[1])) {
+        for span in spans {
+            dest.push_slice(&data[span]).map_err(write_error)?;
+        }
+        return Ok(());
+    }
+    write_set_of_elements(dest, data, spans).map_err(write_error)
+}
+
+// Appends the contents of each segment of a constructed string to `dest`.
+// For `BIT STRING`s, the unused bits count of the final segment is stored in
+// `padding_bits`; only the final segment may have unused bits.
+fn collect_segments(
+    p: &mut Parser<'_>,
+    indefinite: bool,
+    tag_value: u32,
+    dest: &mut Vec<u8>,
+    padding_bits: &mut Option<u8>,
+    depth: u32,
+) -> ParseResult<()> {
+    if depth > MAX_DEPTH {
+        return Err(ParseError::new(ParseErrorKind::ExceedsMaxDepth));
+    }
+
+    while next_child(p, indefinite)? {
+        let tag = p.read_tag()?;
+        if tag.class() != TagClass::Universal || tag.value() != tag_value {
+            return Err(ParseError::new(ParseErrorKind::UnexpectedTag {
+                actual: tag,
+            }));
+        }
+        let length = read_length(p)?;
+
+        if tag.is_constructed() {
+            let mut inner = contents(p, length)?;
+            let child_parser = inner.as_mut().unwrap_or(p);
+            collect_segments(
+                child_parser,
+                length.is_none(),
+                tag_value,
+                dest,
+                padding_bits,
+                depth + 1,
+            )?;
+            continue;
+        }
+
+        let length = length.ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))?;
+        let data = p.read_bytes(length)?;
+        if tag_value == BIT_STRING {
+            if let Some(1..=7) = *padding_bits {
+                return Err(ParseError::new(ParseErrorKind::InvalidValue));
+            }
+            let (&segment_padding_bits, data) = data
+                .split_first()
+                .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))?;
+            if segment_padding_bits > 7 || (data.is_empty() && segment_padding_bits != 0) {
+                return Err(ParseError::new(ParseErrorKind::InvalidValue));
+            }
+            *padding_bits = Some(segment_padding_bits);
+            dest.extend_from_slice(data);
+        } else {
+            dest.extend_from_slice(data);
+        }
+    }
+    Ok(())
+}
+
+// Writes the contents of a `BIT STRING` with its unused bits cleared. Invalid
+// contents are written unchanged, so that they are rejected when parsed.
+fn write_bit_string(dest: &mut WriteBuf, data: &[u8]) -> WriteResult {
+    let start = dest.len();
+    dest.push_slice(data)?;
+    let dest = dest.as_mut_slice();
+    if let [padding_bits @ 1..=7, .., last] = dest[start..] {
+        let idx = dest.len() - 1;
+        dest[idx] = last & !((1 << padding_bits) - 1);
+    }
+    Ok(())
+}
+
+// Re-encoding only writes values which were successfully parsed, and without
+// a size limit, so this is not expected to happen.
+fn write_error(_: WriteError) -> ParseError {
+    ParseError::new(ParseErrorKind::InvalidValue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ber_to_der, parse_ber, parse_single_ber, BerInput};
+    use crate::{BitString, ParseError, ParseErrorKind, ParseResult, Tag};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn assert_reencodes(data: &[(&[u8], ParseResult<&[u8]>)]) {
+        for (ber, expected) in data {
+            let result = BerInput::new(ber);
+            match (&result, expected) {
+                (Ok(input), Ok(der)) => {
+                    assert_eq!(&input.der[..], *der);
+                    assert_eq!(input.is_der(), ber == der);
+                }
+                (Err(e), Err(expected)) => assert_eq!(e, expected),
+                _ => panic!("{:?}: expected {:?}", ber, expected),
+            }
+        }
+    }
+
+    #[test]
+    fn test_reencode_lengths() {
+        assert_reencodes(&[
+            (b"", Ok(b"")),
+            (b"\x02\x01\x05", Ok(b"\x02\x01\x05")),
+            (b"\x02\x81\x01\x05", Ok(b"\x02\x01\x05")),
+            (b"\x02\x84\x00\x00\x00\x01\x05", Ok(b"\x02\x01\x05")),
+            (b"\x30\x80\x02\x01\x05\x00\x00", Ok(b"\x30\x03\x02\x01\x05")),
+            (
+                b"\x30\x80\x30\x80\x05\x00\x00\x00\x02\x81\x01\x05\x00\x00",
+                Ok(b"\x30\x07\x30\x02\x05\x00\x02\x01\x05"),
+            ),
+            (b"\xa0\x80\x05\x00\x00\x00", Ok(b"\xa0\x02\x05\x00")),
+            (b"\x30\x80\x00\x00", Ok(b"\x30\x00")),
+            (
+                b"\x30\x80\x05\x00\x00\x00\x05\x00",
+                Ok(b"\x30\x02\x05\x00\x05\x00"),
+            ),
+            (
+                b"\x02\x80\x05\x00\x00",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x30\x80\x05\x00",
+                Err(ParseError::new(ParseErrorKind::ShortData)),
+            ),
+            (
+                b"\x30\x80\x05\x00\x00\x01\x00",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x00\x00",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x02\xff\x05",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x02\x82\x01",
+                Err(ParseError::new(ParseErrorKind::ShortData)),
+            ),
+            (
+                b"\x30\x03\x02\x01",
+                Err(ParseError::new(ParseErrorKind::ShortData)),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_reencode_constructed_strings() {
+        assert_reencodes(&[
+            (b"\x24\x80\x04\x02ab\x04\x01c\x00\x00", Ok(b"\x04\x03abc")),
+            (b"\x24\x07\x04\x02ab\x04\x01c", Ok(b"\x04\x03abc")),
+            (b"\x24\x00", Ok(b"\x04\x00")),
+            (
+                b"\x24\x80\x24\x80\x04\x01a\x00\x00\x04\x01b\x00\x00",
+                Ok(b"\x04\x02ab"),
+            ),
+            (b"\x2c\x80\x0c\x02ab\x00\x00", Ok(b"\x0c\x02ab")),
+            (
+                b"\x38\x80\x18\x0a2019121516\x18\x05\x30\x30\x30\x30Z\x00\x00",
+                Ok(b"\x18\x0f20191215160000Z"),
+            ),
+            (
+                b"\x30\x80\x24\x80\x04\x01a\x00\x00\x00\x00",
+                Ok(b"\x30\x03\x04\x01a"),
+            ),
+            // Implicitly tagged strings are not merged.
+            (b"\xa0\x03\x04\x01a", Ok(b"\xa0\x03\x04\x01a")),
+            (
+                b"\x24\x80\x0c\x01a\x00\x00",
+                Err(ParseError::new(ParseErrorKind::UnexpectedTag {
+                    actual: Tag::primitive(0x0c),
+                })),
+            ),
+            (
+                b"\x24\x80\x04\x80\x00\x00\x00\x00",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x24\x80\x04\x01a",
+                Err(ParseError::new(ParseErrorKind::ShortData)),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_reencode_bit_strings() {
+        assert_reencodes(&[
+            (b"\x03\x02\x01\xfe", Ok(b"\x03\x02\x01\xfe")),
+            (b"\x03\x02\x01\xff", Ok(b"\x03\x02\x01\xfe")),
+            (b"\x03\x02\x04\xff", Ok(b"\x03\x02\x04\xf0")),
+            (
+                b"\x23\x80\x03\x02\x00\xff\x03\x02\x04\xff\x00\x00",
+                Ok(b"\x03\x03\x04\xff\xf0"),
+            ),
+            (b"\x23\x80\x03\x01\x00\x00\x00", Ok(b"\x03\x01\x00")),
+            (b"\x23\x00", Ok(b"\x03\x01\x00")),
+            // Invalid primitive values are left for the parser to reject.
+            (b"\x03\x01\x01", Ok(b"\x03\x01\x01")),
+            (
+                b"\x23\x80\x03\x02\x04\xf0\x03\x02\x00\xff\x00\x00",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x23\x80\x03\x00\x00\x00",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x23\x80\x03\x01\x01\x00\x00",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x23\x80\x03\x02\x08\x00\x00\x00",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_reencode_booleans() {
+        assert_reencodes(&[
+            (b"\x01\x01\xff", Ok(b"\x01\x01\xff")),
+            (b"\x01\x01\x00", Ok(b"\x01\x01\x00")),
+            (b"\x01\x01\x01", Ok(b"\x01\x01\xff")),
+            (b"\x01\x81\x01\x80", Ok(b"\x01\x01\xff")),
+            (b"\x01\x02\x01\x01", Ok(b"\x01\x02\x01\x01")),
+        ]);
+    }
+
+    #[test]
+    fn test_reencode_sets() {
+        assert_reencodes(&[
+            (b"\x31\x00", Ok(b"\x31\x00")),
+            (b"\x31\x80\x00\x00", Ok(b"\x31\x00")),
+            (
+                b"\x31\x06\x02\x01\x01\x02\x01\x03",
+                Ok(b"\x31\x06\x02\x01\x01\x02\x01\x03"),
+            ),
+            (
+                b"\x31\x06\x02\x01\x03\x02\x01\x01",
+                Ok(b"\x31\x06\x02\x01\x01\x02\x01\x03"),
+            ),
+            (
+                b"\x31\x80\x02\x01\x03\x02\x01\x01\x02\x01\x03\x00\x00",
+                Ok(b"\x31\x09\x02\x01\x01\x02\x01\x03\x02\x01\x03"),
+            ),
+            // Elements are sorted by their DER encodings, not the input.
+            (
+                b"\x31\x80\x04\x81\x01b\x24\x80\x04\x01a\x00\x00\x00\x00",
+                Ok(b"\x31\x06\x04\x01a\x04\x01b"),
+            ),
+            // Elements with distinct tags are a `SET`, and are sorted by
+            // their tags, even when that isn't byte order.
+            (
+                b"\x31\x08\xa0\x03\x02\x01\x01\x81\x01\xff",
+                Ok(b"\x31\x08\xa0\x03\x02\x01\x01\x81\x01\xff"),
+            ),
+            (
+                b"\x31\x80\xa0\x80\x02\x01\x01\x00\x00\x81\x01\xff\x00\x00",
+                Ok(b"\x31\x08\xa0\x03\x02\x01\x01\x81\x01\xff"),
+            ),
+            (
+                b"\x31\x08\x81\x01\xff\xa0\x03\x02\x01\x01",
+                Ok(b"\x31\x08\xa0\x03\x02\x01\x01\x81\x01\xff"),
+            ),
+            (
+                b"\x31\x09\x9f\x1f\x01\x00\xa5\x00\x02\x01\x01",
+                Ok(b"\x31\x09\x02\x01\x01\xa5\x00\x9f\x1f\x01\x00"),
+            ),
+            // Sequences are left alone.
+            (
+                b"\x30\x06\x02\x01\x03\x02\x01\x01",
+                Ok(b"\x30\x06\x02\x01\x03\x02\x01\x01"),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_reencode_generalized_times() {
+        assert_reencodes(&[
+            (b"\x18\x0f20100102030405Z", Ok(b"\x18\x0f20100102030405Z")),
+            (
+                b"\x18\x1320100102030405+0607",
+                Ok(b"\x18\x0f20100101205705Z"),
+            ),
+            (
+                b"\x18\x1320100102030405-0000",
+                Ok(b"\x18\x0f20100102030405Z"),
+            ),
+            (
+                b"\x18\x1820100102030405,5000-0100",
+                Ok(b"\x18\x1120100102040405.5Z"),
+            ),
+            (b"\x18\x1120100102030405.0Z", Ok(b"\x18\x0f20100102030405Z")),
+            (
+                b"\x18\x1320100102030405+2400",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x18\x0e20100102030405",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_ber_to_der() {
+        assert_eq!(ber_to_der(b"\x02\x01\x05"), Ok(vec![0x02, 0x01, 0x05]));
+        assert_eq!(
+            ber_to_der(b"\x31\x80\x01\x01\x01\x24\x80\x04\x01a\x00\x00\x00\x00"),
+            Ok(b"\x31\x06\x01\x01\xff\x04\x01a".to_vec())
+        );
+        assert_eq!(
+            ber_to_der(b"\x31\x08\xa0\x03\x02\x01\x01\x81\x01\xff"),
+            Ok(b"\x31\x08\xa0\x03\x02\x01\x01\x81\x01\xff".to_vec())
+        );
+        assert_eq!(
+            ber_to_der(b"\x30\x80"),
+            Err(ParseError::new(ParseErrorKind::ShortData))
+        );
+    }
+
+    #[test]
+    fn test_reencode_max_depth() {
+        let mut data = vec![];
+        for _ in 0..200 {
+            data.extend_from_slice(b"\x30\x80");
+        }
+        for _ in 0..200 {
+            data.extend_from_slice(b"\x00\x00");
+        }
+        assert_eq!(
+            BerInput::new(&data).err(),
+            Some(ParseError::new(ParseErrorKind::ExceedsMaxDepth))
+        );
+
+        let mut data = vec![];
+        for _ in 0..200 {
+            data.extend_from_slice(b"\x24\x80");
+        }
+        assert_eq!(
+            BerInput::new(&data).err(),
+            Some(ParseError::new(ParseErrorKind::ExceedsMaxDepth))
+        );
+    }
+
+    #[test]
+    fn test_parse_ber() {
+        assert_eq!(
+            parse_ber(b"\x30\x80\x02\x01\x05\x01\x01\x01\x00\x00", |p| {
+                p.read_element::<crate::Sequence>()?.parse(|p| {
+                    Ok::<_, ParseError>((p.read_element::<u8>()?, p.read_element::<bool>()?))
+                })
+            }),
+            Ok((5, true))
+        );
+        assert_eq!(parse_single_ber::<u64>(b"\x02\x81\x01\x05"), Ok(5));
+        assert_eq!(
+            parse_ber(
+                b"\x31\x06\x02\x01\x03\x02\x01\x01",
+                |p| Ok::<_, ParseError>(p.read_element::<crate::SetOf<u64>>()?.collect::<Vec<_>>())
+            ),
+            Ok(vec![1, 3])
+        );
+        // Everything other than the encoding's structure is still checked.
+        assert_eq!(
+            parse_single_ber::<u64>(b"\x02\x02\x00\x05"),
+            Err(ParseError::new(ParseErrorKind::InvalidValue))
+        );
+        assert_eq!(
+            parse_single_ber::<u64>(b"\x02\x01\x05\x05\x00"),
+            Err(ParseError::new(ParseErrorKind::ExtraData))
+        );
+    }
+
+    #[test]
+    fn test_ber_input_borrowed() {
+        let input = BerInput::new(b"\x24\x80\x04\x02ab\x04\x01c\x00\x00").unwrap();
+        assert!(!input.is_der());
+        assert_eq!(input.parse_single::<&[u8]>(), Ok(&b"abc"[..]));
+
+        let input = BerInput::new(b"\x23\x80\x03\x02\x00\xff\x03\x02\x01\xff\x00\x00").unwrap();
+        assert_eq!(
+            input.parse_single::<BitString<'_>>(),
+            Ok(BitString::new(b"\xff\xfe", 1).unwrap())
+        );
+
+        let input = BerInput::new(b"\x04\x03abc\x04\x00").unwrap();
+        assert!(input.is_der());
+        assert_eq!(input.as_der(), b"\x04\x03abc\x04\x00");
+        let result: ParseResult<Vec<&[u8]>> =
+            input.parse(|p| Ok(vec![p.read_element()?, p.read_element()?]));
+        assert_eq!(result, Ok(vec![&b"abc"[..], b""]));
+    }
+}
@@ -0,0 +1,410 @@
+//! A command-line tool for inspecting and converting DER, built with the
+//! `cli` feature.
+
+use asn1::{ParseError, ParseErrorKind, Tag, TagClass, Tlv, Value, MAX_DEPTH};
+use std::io::{self, Read, Write};
+use std::{env, fs, process, str};
+
+const USAGE: &str = "usage: asn1 <command> [options] [FILE]
+
+Reads FILE, or standard input if FILE is omitted or `-`.
+
+commands:
+  dump               show the input as an annotated tree
+  validate           check that the input is strict DER
+  extract PATH       write the element at PATH, a `.` separated series of
+                     child indexes, e.g. `0.2.1`; index 0 of an OCTET STRING
+                     or BIT STRING is the DER it contains
+  convert            write the input in the format given by --outform
+
+options:
+  --inform FORMAT    the input format: der, pem, hex, or base64; detected
+                     if omitted
+  --outform FORMAT   the output format of extract and convert: der, pem,
+                     hex, or base64; defaults to der
+  --ber              accept BER input, re-encoding it as DER
+  --label LABEL      the label of PEM output; defaults to the label of PEM
+                     input
+  --no-offsets       don't show offsets and lengths in dump
+  --no-encapsulated  don't show the contents of OCTET STRINGs and BIT
+                     STRINGs as DER in dump
+  --indent N         indent nested values by N spaces in dump
+";
+
+#[derive(Clone, Copy)]
+enum Format {
+    Der,
+    Pem,
+    Hex,
+    Base64,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Result<Format, String> {
+        match name {
+            "der" => Ok(Format::Der),
+            "pem" => Ok(Format::Pem),
+            "hex" => Ok(Format::Hex),
+            "base64" => Ok(Format::Base64),
+            _ => Err(format!("unknown format: {}", name)),
+        }
+    }
+
+    // Guesses the format of `data`. Binary data is assumed to be DER.
+    fn detect(data: &[u8]) -> Format {
+        let text = match str::from_utf8(data) {
+            Ok(text) => text.trim(),
+            Err(_) => return Format::Der,
+        };
+        if text.contains("-----BEGIN ") {
+            Format::Pem
+        } else if text.is_empty() {
+            Format::Der
+        } else if text
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || c.is_ascii_whitespace())
+        {
+            Format::Hex
+        } else if text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+/=".contains(c) || c.is_ascii_whitespace())
+        {
+            Format::Base64
+        } else {
+            Format::Der
+        }
+    }
+}
+
+enum Command {
+    Dump,
+    Validate,
+    Extract(Vec<usize>),
+    Convert,
+}
+
+struct Options {
+    command: Command,
+    inform: Option<Format>,
+    outform: Format,
+    ber: bool,
+    label: Option<String>,
+    dump: asn1::DumpOptions,
+    file: Option<String>,
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, option: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("{} requires a value", option))
+}
+
+fn parse_path(path: &str) -> Result<Vec<usize>, String> {
+    if path.is_empty() {
+        return Ok(Vec::new());
+    }
+    path.split('.')
+        .map(|index| index.parse().map_err(|_| format!("invalid path: {}", path)))
+        .collect()
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let command = args.next().ok_or("missing command")?;
+    let mut options = Options {
+        command: Command::Dump,
+        inform: None,
+        outform: Format::Der,
+        ber: false,
+        label: None,
+        dump: asn1::DumpOptions::new(),
+        file: None,
+    };
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--inform" => options.inform = Some(Format::from_name(&next_value(&mut args, &arg)?)?),
+            "--outform" => options.outform = Format::from_name(&next_value(&mut args, &arg)?)?,
+            "--ber" => options.ber = true,
+            "--label" => options.label = Some(next_value(&mut args, &arg)?),
+            "--no-offsets" => options.dump = options.dump.offsets(false),
+            "--no-encapsulated" => options.dump = options.dump.encapsulated(false),
+            "--indent" => {
+                let indent = next_value(&mut args, &arg)?;
+                let indent = indent
+                    .parse()
+                    .map_err(|_| format!("invalid indent: {}", indent))?;
+                options.dump = options.dump.indent(indent);
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {}", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    options.command = match command.as_str() {
+        "dump" => Command::Dump,
+        "validate" => Command::Validate,
+        "extract" => Command::Extract(parse_path(
+            &positional.next().ok_or("extract requires a PATH")?,
+        )?),
+        "convert" => Command::Convert,
+        _ => return Err(format!("unknown command: {}", command)),
+    };
+    options.file = positional.next();
+    if positional.next().is_some() {
+        return Err("too many arguments".to_string());
+    }
+    Ok(options)
+}
+
+// Base64 is decoded and encoded by way of the PEM support, which handles
+// the base64 body of a PEM block.
+fn decode_base64(text: &str) -> Result<Vec<u8>, String> {
+    let pem = format!("-----BEGIN DATA-----\n{}\n-----END DATA-----\n", text);
+    asn1::pem_decode(&pem)
+        .map(asn1::Pem::into_contents)
+        .map_err(|_| "invalid base64".to_string())
+}
+
+fn encode_base64(data: &[u8]) -> String {
+    asn1::pem_encode("DATA", data, asn1::PEM_LINE_WIDTH)
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits = text
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    let pairs = digits.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err("invalid hex: odd number of digits".to_string());
+    }
+    pairs
+        .map(|pair| {
+            str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| "invalid hex".to_string())
+        })
+        .collect()
+}
+
+fn encode_hex(data: &[u8]) -> String {
+    let mut out = data
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    out.push('\n');
+    out
+}
+
+// Reads the input, returning its DER and, for PEM input, its label.
+fn read_input(options: &Options) -> Result<(Vec<u8>, Option<String>), String> {
+    let mut data = Vec::new();
+    match options.file.as_deref() {
+        None | Some("-") => io::stdin().read_to_end(&mut data),
+        Some(file) => fs::File::open(file).and_then(|mut f| f.read_to_end(&mut data)),
+    }
+    .map_err(|e| format!("failed to read input: {}", e))?;
+
+    let format = options.inform.unwrap_or_else(|| Format::detect(&data));
+    let (der, label) = match format {
+        Format::Der => (data, None),
+        Format::Pem => {
+            let pem = asn1::pem_decode(as_text(&data)?).map_err(|e| e.to_string())?;
+            let label = pem.label().to_string();
+            (pem.into_contents(), Some(label))
+        }
+        Format::Hex => (decode_hex(as_text(&data)?)?, None),
+        Format::Base64 => (decode_base64(as_text(&data)?)?, None),
+    };
+
+    if options.ber {
+        let der = asn1::ber_to_der(&der).map_err(|e| format!("invalid BER: {}", e))?;
+        Ok((der, label))
+    } else {
+        Ok((der, label))
+    }
+}
+
+fn as_text(data: &[u8]) -> Result<&str, String> {
+    str::from_utf8(data).map_err(|_| "input is not text".to_string())
+}
+
+fn write_output(data: &[u8], format: Format, label: Option<&str>) -> Result<(), String> {
+    let output = match format {
+        Format::Der => data.to_vec(),
+        Format::Pem => {
+            let label = label.ok_or("--label is required for PEM output")?;
+            asn1::pem_encode(label, data, asn1::PEM_LINE_WIDTH).into_bytes()
+        }
+        Format::Hex => encode_hex(data).into_bytes(),
+        Format::Base64 => encode_base64(data).into_bytes(),
+    };
+    io::stdout()
+        .write_all(&output)
+        .map_err(|e| format!("failed to write output: {}", e))
+}
+
+// A failure to validate, at `offset` in the input.
+struct Invalid {
+    offset: usize,
+    error: ParseError,
+}
+
+impl From<ParseError> for Invalid {
+    fn from(error: ParseError) -> Invalid {
+        Invalid { offset: 0, error }
+    }
+}
+
+// Checks that `data` is a series of strictly DER encoded elements, returning
+// their encodings.
+fn validate_elements(data: &[u8], base: usize, depth: u32) -> Result<Vec<&[u8]>, Invalid> {
+    asn1::parse(data, |p| {
+        let mut elements = Vec::new();
+        let mut offset = base;
+        while !p.is_empty() {
+            let tlv = p
+                .read_element::<Tlv>()
+                .map_err(|error| Invalid { offset, error })?;
+            validate_element(tlv, offset, depth)?;
+            elements.push(tlv.full_data());
+            offset += tlv.full_data().len();
+        }
+        Ok(elements)
+    })
+}
+
+fn validate_element(tlv: Tlv<'_>, offset: usize, depth: u32) -> Result<(), Invalid> {
+    let invalid = |kind| {
+        Err(Invalid {
+            offset,
+            error: ParseError::new(kind),
+        })
+    };
+    let tag = tlv.tag();
+    let universal = tag.class() == TagClass::Universal;
+
+    if tag.is_constructed() {
+        if depth >= MAX_DEPTH {
+            return invalid(ParseErrorKind::ExceedsMaxDepth);
+        }
+        // DER requires everything else, e.g. strings, to be primitive.
+        if universal && !matches!(tag.value(), 0x08 | 0x0b | 0x10 | 0x11 | 0x1d) {
+            return invalid(ParseErrorKind::InvalidValue);
+        }
+        let header_len = tlv.full_data().len() - tlv.data().len();
+        let children = validate_elements(tlv.data(), offset + header_len, depth + 1)?;
+        if tag == Tag::constructed(0x11) && !is_set_ordered(&children) {
+            return invalid(ParseErrorKind::InvalidSetOrdering);
+        }
+        return Ok(());
+    }
+
+    if universal && matches!(tag.value(), 0x10 | 0x11) {
+        return invalid(ParseErrorKind::InvalidValue);
+    }
+    match asn1::parse_single::<Value>(tlv.full_data()) {
+        Err(error) => Err(Invalid { offset, error }),
+        // A `UTCTime` which isn't in its DER form.
+        Ok(Value::Other(_)) if tag == Tag::primitive(0x17) => invalid(ParseErrorKind::InvalidValue),
+        Ok(_) => Ok(()),
+    }
+}
+
+// Whether the elements of a SET are in an order DER allows: ascending order
+// of their tags for a `SET`, or of their encodings for a `SET OF`. Without
+// the schema, either is accepted.
+fn is_set_ordered(elements: &[&[u8]]) -> bool {
+    let tag = |element: &[u8]| {
+        let tag = asn1::parse_single::<Tlv>(element).unwrap().tag();
+        (tag.class(), tag.value())
+    };
+    elements.windows(2).all(|w| tag(w[0]) < tag(w[1])) || elements.windows(2).all(|w| w[0] <= w[1])
+}
+
+fn format_path(path: &[usize]) -> String {
+    path.iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn extract(value: Value<'_>, path: &[usize], depth: usize) -> Result<Vec<u8>, String> {
+    let index = match path.get(depth) {
+        Some(&index) => index,
+        None => return asn1::write_single(&value).map_err(|e| e.to_string()),
+    };
+    let child = match value {
+        Value::OctetString(data) if index == 0 => asn1::parse_single::<Value>(data).ok(),
+        Value::BitString(ref bits) if index == 0 && bits.padding_bits() == 0 => {
+            asn1::parse_single::<Value>(bits.as_bytes()).ok()
+        }
+        _ => value.get(index).cloned(),
+    };
+    match child {
+        Some(child) => extract(child, path, depth + 1),
+        None => Err(format!(
+            "no element at path {}",
+            format_path(&path[..=depth])
+        )),
+    }
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let (der, label) = read_input(&options)?;
+    match options.command {
+        Command::Dump => {
+            let mut out = String::new();
+            asn1::dump(&der, &mut out, options.dump).map_err(|e| e.to_string())?;
+            print!("{}", out);
+        }
+        Command::Validate => {
+            if der.is_empty() {
+                return Err("invalid DER at offset 0: input is empty".to_string());
+            }
+            if let Err(Invalid { offset, error }) = validate_elements(&der, 0, 0) {
+                return Err(format!(
+                    "invalid DER at offset {}: {:?} ({})",
+                    offset,
+                    error.kind(),
+                    error
+                ));
+            }
+            println!("OK");
+        }
+        Command::Extract(ref path) => {
+            let value = asn1::parse_single::<Value>(&der).map_err(|e| e.to_string())?;
+            let element = extract(value, path, 0)?;
+            write_output(&element, options.outform, options.label.as_deref())?;
+        }
+        Command::Convert => {
+            let label = options.label.as_deref().or(label.as_deref());
+            write_output(&der, options.outform, label)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        print!("{}", USAGE);
+        return;
+    }
+    let options = match parse_args(args.into_iter()) {
+        Ok(options) => options,
+        Err(e) => {
+            eprint!("asn1: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(e) = run(options) {
+        eprintln!("asn1: {}", e);
+        process::exit(1);
+    }
+}
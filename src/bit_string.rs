@@ -1,3 +1,4 @@
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
 /// Represents an ASN.1 `BIT STRING` whose contents is borrowed.
@@ -44,12 +45,14 @@ impl<'a> BitString<'a> {
 }
 
 /// Represents an ASN.1 `BIT STRING` whose contents owned.
+#[cfg(feature = "alloc")]
 #[derive(Debug, PartialEq, Clone, Hash)]
 pub struct OwnedBitString {
     data: Vec<u8>,
     padding_bits: u8,
 }
 
+#[cfg(feature = "alloc")]
 impl OwnedBitString {
     pub fn new(data: Vec<u8>, padding_bits: u8) -> Option<OwnedBitString> {
         BitString::new(&data, padding_bits)?;
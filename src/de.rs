@@ -0,0 +1,678 @@
+use crate::ber::MAX_DEPTH;
+use crate::serde_impls::Special;
+use crate::types::SimpleAsn1Readable;
+use crate::{
+    explicit_tag, IA5String, ParseError, ParseErrorKind, Parser, PrintableString, Real, SerdeError,
+    SerdeOptions, Tag, TagClass, Tlv, Utf8String, VisibleString,
+};
+use serde::de::value::{BorrowedBytesDeserializer, U32Deserializer};
+use serde::de::{self, Deserialize, DeserializeSeed, IntoDeserializer, Visitor};
+
+const SEQUENCE_TAG: Tag = Tag::constructed(0x10);
+
+/// Deserializes a `T` from DER, as encoded by [`to_der`](crate::to_der).
+pub fn from_der<'de, T: Deserialize<'de>>(data: &'de [u8]) -> Result<T, SerdeError> {
+    from_der_with_options(data, &SerdeOptions::new())
+}
+
+/// Deserializes a `T` from DER, like [`from_der`], with `options`.
+pub fn from_der_with_options<'de, T: Deserialize<'de>>(
+    data: &'de [u8],
+    options: &SerdeOptions,
+) -> Result<T, SerdeError> {
+    let mut deserializer = Deserializer::new(data, options);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+fn unexpected_tag(actual: Tag) -> SerdeError {
+    ParseError::new(ParseErrorKind::UnexpectedTag { actual }).into()
+}
+
+/// A serde `Deserializer` which reads DER. See [`to_der`](crate::to_der) for
+/// how values are encoded.
+pub struct Deserializer<'a, 'de> {
+    parser: Parser<'de>,
+    options: &'a SerdeOptions,
+    // The index of the value in the enclosing struct or tuple, if it's a
+    // field of one.
+    field: Option<u32>,
+    depth: u32,
+}
+
+impl<'a, 'de> Deserializer<'a, 'de> {
+    /// Creates a `Deserializer` which reads from `data`.
+    pub fn new(data: &'de [u8], options: &'a SerdeOptions) -> Deserializer<'a, 'de> {
+        Deserializer {
+            parser: Parser::new(data),
+            options,
+            field: None,
+            depth: 0,
+        }
+    }
+
+    /// Checks that all of the data has been read. This should be called after
+    /// deserializing a value.
+    pub fn end(&self) -> Result<(), SerdeError> {
+        if !self.parser.is_empty() {
+            return Err(ParseError::new(ParseErrorKind::ExtraData).into());
+        }
+        Ok(())
+    }
+
+    // Returns a `Deserializer` for the contents of a constructed value.
+    fn child(&self, data: &'de [u8]) -> Result<Deserializer<'a, 'de>, SerdeError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(ParseError::new(ParseErrorKind::ExceedsMaxDepth).into());
+        }
+        Ok(Deserializer {
+            parser: Parser::new(data),
+            options: self.options,
+            field: None,
+            depth: self.depth + 1,
+        })
+    }
+
+    fn read<T: SimpleAsn1Readable<'de>>(&mut self) -> Result<T, SerdeError> {
+        Ok(self.parser.read_element::<T>()?)
+    }
+
+    fn read_tagged(&mut self, tag: Tag) -> Result<Tlv<'de>, SerdeError> {
+        let tlv = self.parser.read_tlv()?;
+        if tlv.tag() != tag {
+            return Err(unexpected_tag(tlv.tag()));
+        }
+        Ok(tlv)
+    }
+
+    fn read_str(&mut self) -> Result<&'de str, SerdeError> {
+        let tlv = self.parser.read_tlv()?;
+        let data = tlv.data();
+        if tlv.tag().class() != TagClass::Universal || tlv.tag().is_constructed() {
+            return Err(unexpected_tag(tlv.tag()));
+        }
+        // Any of the string types whose contents are UTF-8 are accepted,
+        // though only `UTF8String`s are written.
+        Ok(match tlv.tag().value() {
+            0x0c => Utf8String::parse_data(data)?.as_str(),
+            0x13 => PrintableString::parse_data(data)?.as_str(),
+            0x16 => IA5String::parse_data(data)?.as_str(),
+            0x1a => VisibleString::parse_data(data)?.as_str(),
+            _ => return Err(unexpected_tag(tlv.tag())),
+        })
+    }
+
+    fn visit_elements<V: Visitor<'de>>(
+        &mut self,
+        data: &'de [u8],
+        len: Option<usize>,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        let mut deserializer = self.child(data)?;
+        let value = visitor.visit_seq(Elements {
+            de: &mut deserializer,
+            index: 0,
+            len,
+        })?;
+        deserializer.end()?;
+        Ok(value)
+    }
+}
+
+impl<'de, 'a, 'b> de::Deserializer<'de> for &'b mut Deserializer<'a, 'de> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let tag = match self.parser.peek_tag() {
+            Some(tag) => tag,
+            None => return Err(ParseError::new(ParseErrorKind::ShortData).into()),
+        };
+        if tag == SEQUENCE_TAG {
+            return self.deserialize_seq(visitor);
+        }
+        if tag.class() != TagClass::Universal || tag.is_constructed() {
+            return Err(unexpected_tag(tag));
+        }
+        match tag.value() {
+            0x01 => self.deserialize_bool(visitor),
+            0x02 => {
+                let tlv = self.parser.read_tlv()?;
+                if let Ok(v) = i64::parse_data(tlv.data()) {
+                    visitor.visit_i64(v)
+                } else if let Ok(v) = u64::parse_data(tlv.data()) {
+                    visitor.visit_u64(v)
+                } else {
+                    // Larger values are visited in their text form.
+                    let text = Special::BigUint.der_to_text(tlv.data()).ok_or_else(|| {
+                        SerdeError::from(ParseError::new(ParseErrorKind::IntegerOverflow))
+                    })?;
+                    visitor.visit_string(text)
+                }
+            }
+            0x03 | 0x06 | 0x17 | 0x18 => {
+                let special = match tag.value() {
+                    0x03 => Special::BitString,
+                    0x06 => Special::ObjectIdentifier,
+                    0x17 => Special::UtcTime,
+                    _ => Special::GeneralizedTime,
+                };
+                let tlv = self.parser.read_tlv()?;
+                let text = special.der_to_text(tlv.data()).ok_or_else(|| {
+                    SerdeError::from(ParseError::new(ParseErrorKind::InvalidValue))
+                })?;
+                visitor.visit_string(text)
+            }
+            0x04 => self.deserialize_bytes(visitor),
+            0x05 => self.deserialize_unit(visitor),
+            0x09 => self.deserialize_f64(visitor),
+            0x0c | 0x13 | 0x16 | 0x1a => self.deserialize_str(visitor),
+            _ => Err(unexpected_tag(tag)),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_bool(self.read()?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_i8(self.read()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_i32(self.read()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_i32(self.read()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_i64(self.read()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_u8(self.read()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_u32(self.read()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_u32(self.read()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_u64(self.read()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_f64(self.read::<Real>()?.to_f64())
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let s = self.read_str()?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => visitor.visit_borrowed_str(s),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_borrowed_str(self.read_str()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_borrowed_bytes(self.read()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let field = self.field.take().ok_or_else(|| {
+            SerdeError::Message(
+                "an Option can only be deserialized as a field of a struct or tuple".into(),
+            )
+        })?;
+        if self.parser.peek_tag() != Some(explicit_tag(field)) {
+            return visitor.visit_none();
+        }
+        let tlv = self.parser.read_tlv()?;
+        let mut deserializer = self.child(tlv.data())?;
+        let value = visitor.visit_some(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.read::<()>()?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        let special = match Special::from_name(name) {
+            Some(special) => special,
+            None => return visitor.visit_newtype_struct(self),
+        };
+        // The crate's own types are given the contents of their encoding.
+        let tlv = self.read_tagged(special.tag())?;
+        visitor.visit_newtype_struct(BorrowedBytesDeserializer::new(tlv.data()))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let tlv = self.read_tagged(SEQUENCE_TAG)?;
+        self.visit_elements(tlv.data(), None, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        let tlv = self.read_tagged(SEQUENCE_TAG)?;
+        self.visit_elements(tlv.data(), Some(len), visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let tlv = self.read_tagged(SEQUENCE_TAG)?;
+        let mut deserializer = self.child(tlv.data())?;
+        let value = visitor.visit_map(Entries {
+            de: &mut deserializer,
+            entry: None,
+        })?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        let tlv = self.parser.read_tlv()?;
+        let tag = tlv.tag();
+        let index = if tag.class() == TagClass::ContextSpecific {
+            (0..variants.len() as u32)
+                .find(|&i| self.options.variant_tag(name, variants[i as usize], i) == tag.value())
+        } else {
+            None
+        };
+        let index = index.ok_or_else(|| unexpected_tag(tag))?;
+        let deserializer = self.child(tlv.data())?;
+        visitor.visit_enum(Variant {
+            de: deserializer,
+            tag,
+            index,
+        })
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.parser.read_tlv()?;
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+// The elements of a `SEQUENCE`. For structs and tuples, `len` is the number of
+// fields; any trailing `OPTIONAL` fields may be omitted.
+struct Elements<'b, 'a, 'de> {
+    de: &'b mut Deserializer<'a, 'de>,
+    index: u32,
+    len: Option<usize>,
+}
+
+impl<'de> de::SeqAccess<'de> for Elements<'_, '_, 'de> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        match self.len {
+            Some(len) if self.index as usize >= len => return Ok(None),
+            Some(_) => self.de.field = Some(self.index),
+            None if self.de.parser.is_empty() => return Ok(None),
+            None => self.de.field = None,
+        }
+        self.index += 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.len.map(|len| len - self.index as usize)
+    }
+}
+
+// The entries of a map, each a `SEQUENCE { key, value }`.
+struct Entries<'b, 'a, 'de> {
+    de: &'b mut Deserializer<'a, 'de>,
+    entry: Option<Deserializer<'a, 'de>>,
+}
+
+impl<'de> de::MapAccess<'de> for Entries<'_, '_, 'de> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SerdeError> {
+        if self.de.parser.is_empty() {
+            return Ok(None);
+        }
+        let tlv = self.de.read_tagged(SEQUENCE_TAG)?;
+        let mut entry = self.de.child(tlv.data())?;
+        entry.field = Some(0);
+        let key = seed.deserialize(&mut entry)?;
+        self.entry = Some(entry);
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SerdeError> {
+        let mut entry = self
+            .entry
+            .take()
+            .ok_or_else(|| SerdeError::Message("value requested before key".into()))?;
+        entry.field = Some(1);
+        let value = seed.deserialize(&mut entry)?;
+        entry.end()?;
+        Ok(value)
+    }
+}
+
+// A `CHOICE`, whose variant was identified by `tag`.
+struct Variant<'a, 'de> {
+    de: Deserializer<'a, 'de>,
+    tag: Tag,
+    index: u32,
+}
+
+impl<'a, 'de> de::EnumAccess<'de> for Variant<'a, 'de> {
+    type Error = SerdeError;
+    type Variant = Variant<'a, 'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Variant<'a, 'de>), SerdeError> {
+        let index: U32Deserializer<SerdeError> = self.index.into_deserializer();
+        Ok((seed.deserialize(index)?, self))
+    }
+}
+
+impl<'de> Variant<'_, 'de> {
+    fn check_constructed(&self, constructed: bool) -> Result<(), SerdeError> {
+        if self.tag.is_constructed() != constructed {
+            return Err(unexpected_tag(self.tag));
+        }
+        Ok(())
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Variant<'_, 'de> {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        self.check_constructed(false)?;
+        self.de.end()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        mut self,
+        seed: T,
+    ) -> Result<T::Value, SerdeError> {
+        self.check_constructed(true)?;
+        let value = seed.deserialize(&mut self.de)?;
+        self.de.end()?;
+        Ok(value)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        mut self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.check_constructed(true)?;
+        let value = visitor.visit_seq(Elements {
+            de: &mut self.de,
+            index: 0,
+            len: Some(len),
+        })?;
+        self.de.end()?;
+        Ok(value)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.tuple_variant(fields.len(), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_der, from_der_with_options, MAX_DEPTH, SEQUENCE_TAG};
+    use crate::{
+        to_der, to_der_with_options, DateTime, GeneralizedTime, ObjectIdentifier, OwnedBigUint,
+        OwnedBitString, ParseError, ParseErrorKind, SerdeError, SerdeOptions, Tag, UtcTime,
+    };
+    use alloc::collections::BTreeMap;
+    use alloc::string::String;
+    use alloc::vec;
+    use serde::de::value::{Error, StrDeserializer};
+    use serde::de::{Deserialize, IntoDeserializer, SeqAccess, Visitor};
+    use serde::Serialize;
+
+    fn assert_roundtrips<T>(value: T)
+    where
+        T: Serialize + for<'de> Deserialize<'de> + PartialEq + core::fmt::Debug,
+    {
+        let data = to_der(&value).unwrap();
+        assert_eq!(from_der::<T>(&data).unwrap(), value);
+    }
+
+    fn parse_error(kind: ParseErrorKind) -> SerdeError {
+        SerdeError::Parse(ParseError::new(kind))
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        assert_roundtrips(true);
+        assert_roundtrips(-129i16);
+        assert_roundtrips(u64::MAX);
+        assert_roundtrips(-0.25f64);
+        assert_roundtrips('\u{e9}');
+        assert_roundtrips(String::from("hello"));
+        assert_roundtrips(());
+        assert_roundtrips(vec![vec![1u32], vec![]]);
+        assert_roundtrips((1u8, Some(String::from("x")), None::<bool>, 2i32));
+        assert_roundtrips(vec![Ok::<(u8, u8), bool>((1, 2)), Err(false)]);
+
+        let mut map = BTreeMap::new();
+        map.insert(String::from("a"), vec![1u8]);
+        map.insert(String::from("b"), vec![]);
+        assert_roundtrips(map);
+    }
+
+    #[test]
+    fn test_from_der() {
+        assert_eq!(from_der::<&str>(b"\x13\x02ab"), Ok("ab"));
+        assert_eq!(from_der::<&[u8]>(b"\x04\x02ab"), Ok(&b"ab"[..]));
+        // Trailing `OPTIONAL` fields may be omitted.
+        assert_eq!(
+            from_der::<(u8, Option<u8>, Option<u8>)>(b"\x30\x08\x02\x01\x01\xa1\x03\x02\x01\x02"),
+            Ok((1, Some(2), None))
+        );
+        let options = SerdeOptions::new().choice_tag("Result", "Ok", 5);
+        assert_eq!(
+            from_der_with_options::<Result<u8, u8>>(b"\xa5\x03\x02\x01\x07", &options),
+            Ok(Ok(7))
+        );
+        assert_eq!(
+            from_der_with_options::<Result<u8, u8>>(
+                &to_der_with_options(&Ok::<u8, u8>(7), &options).unwrap(),
+                &options
+            ),
+            Ok(Ok(7))
+        );
+    }
+
+    #[test]
+    fn test_from_der_errors() {
+        assert_eq!(
+            from_der::<u8>(b"\x02\x01\x01\x00"),
+            Err(parse_error(ParseErrorKind::ExtraData))
+        );
+        assert_eq!(
+            from_der::<(u8,)>(b"\x30\x06\x02\x01\x01\x02\x01\x02"),
+            Err(parse_error(ParseErrorKind::ExtraData))
+        );
+        assert_eq!(
+            from_der::<bool>(b"\x02\x01\x01"),
+            Err(parse_error(ParseErrorKind::UnexpectedTag {
+                actual: Tag::primitive(0x02)
+            }))
+        );
+        assert_eq!(
+            from_der::<Result<u8, u8>>(b"\xa2\x03\x02\x01\x07"),
+            Err(parse_error(ParseErrorKind::UnexpectedTag {
+                actual: crate::explicit_tag(2)
+            }))
+        );
+        assert!(matches!(
+            from_der::<Option<u8>>(b"\x02\x01\x01"),
+            Err(SerdeError::Message(_))
+        ));
+
+        // A `SEQUENCE OF` itself, of any depth.
+        struct Nested;
+
+        impl<'de> Deserialize<'de> for Nested {
+            fn deserialize<D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Nested, D::Error> {
+                struct NestedVisitor;
+
+                impl<'de> Visitor<'de> for NestedVisitor {
+                    type Value = Nested;
+
+                    fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                        f.write_str("a sequence")
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Nested, A::Error> {
+                        while seq.next_element::<Nested>()?.is_some() {}
+                        Ok(Nested)
+                    }
+                }
+
+                deserializer.deserialize_seq(NestedVisitor)
+            }
+        }
+
+        let mut data = vec![];
+        for _ in 0..MAX_DEPTH {
+            data = crate::write(|w| w.write_tlv(SEQUENCE_TAG, |d| d.push_slice(&data))).unwrap();
+        }
+        assert!(from_der::<Nested>(&data).is_ok());
+        let data = crate::write(|w| w.write_tlv(SEQUENCE_TAG, |d| d.push_slice(&data))).unwrap();
+        assert_eq!(
+            from_der::<Nested>(&data).map(|_| ()),
+            Err(parse_error(ParseErrorKind::ExceedsMaxDepth))
+        );
+    }
+
+    #[test]
+    fn test_crate_types() {
+        assert_roundtrips(ObjectIdentifier::from_string("1.2.840.113549.1.1.11").unwrap());
+        assert_roundtrips(OwnedBitString::new(vec![0x6e, 0x5d, 0xc0], 6).unwrap());
+        let dt = DateTime::new(2021, 3, 4, 5, 6, 7).unwrap();
+        assert_roundtrips(UtcTime::new(dt).unwrap());
+        assert_roundtrips(GeneralizedTime::new(dt.with_nanosecond(1_500).unwrap()));
+        assert_roundtrips(dt);
+        assert_roundtrips(OwnedBigUint::new(vec![0x00, 0xff, 0x00, 0x01]).unwrap());
+
+        // Text forms, as used by other formats.
+        fn from_text<T: for<'de> Deserialize<'de>>(text: &str) -> Result<T, Error> {
+            let deserializer: StrDeserializer<Error> = text.into_deserializer();
+            T::deserialize(deserializer)
+        }
+        assert_eq!(
+            from_text::<ObjectIdentifier>("1.2.840.113549"),
+            Ok(ObjectIdentifier::from_string("1.2.840.113549").unwrap())
+        );
+        assert_eq!(
+            from_text::<OwnedBitString>("011011100101110111"),
+            Ok(OwnedBitString::new(vec![0x6e, 0x5d, 0xc0], 6).unwrap())
+        );
+        assert_eq!(
+            from_text::<GeneralizedTime>("2021-03-04T05:06:07.0000015Z"),
+            Ok(GeneralizedTime::new(dt.with_nanosecond(1_500).unwrap()))
+        );
+        assert!(from_text::<UtcTime>("2050-03-04T05:06:07Z").is_err());
+        assert!(from_text::<DateTime>("2021-03-04T05:06:07+01:00").is_err());
+        assert_eq!(
+            from_text::<OwnedBigUint>("340282366920938463463374607431768211456"),
+            Ok(OwnedBigUint::new(vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).unwrap())
+        );
+        assert_eq!(
+            from_text::<OwnedBigUint>("128"),
+            Ok(OwnedBigUint::new(vec![0, 0x80]).unwrap())
+        );
+        assert!(from_text::<OwnedBigUint>("12a").is_err());
+    }
+}
@@ -0,0 +1,467 @@
+use crate::ber::MAX_DEPTH;
+use crate::parser::{ParseError, ParseErrorKind, Parser};
+use crate::{parse_single, DateTime, Tag, TagClass, Tlv, Value};
+use alloc::format;
+use alloc::string::String;
+use core::{char, fmt};
+
+// Names of the universal tags, indexed by tag number.
+const UNIVERSAL_NAMES: &[&str] = &[
+    "",
+    "BOOLEAN",
+    "INTEGER",
+    "BIT STRING",
+    "OCTET STRING",
+    "NULL",
+    "OBJECT IDENTIFIER",
+    "ObjectDescriptor",
+    "EXTERNAL",
+    "REAL",
+    "ENUMERATED",
+    "EMBEDDED PDV",
+    "UTF8String",
+    "RELATIVE-OID",
+    "TIME",
+    "",
+    "SEQUENCE",
+    "SET",
+    "NumericString",
+    "PrintableString",
+    "T61String",
+    "VideotexString",
+    "IA5String",
+    "UTCTime",
+    "GeneralizedTime",
+    "GraphicString",
+    "VisibleString",
+    "GeneralString",
+    "UniversalString",
+    "CHARACTER STRING",
+    "BMPString",
+    "DATE",
+    "TIME-OF-DAY",
+    "DATE-TIME",
+    "DURATION",
+    "OID-IRI",
+    "RELATIVE-OID-IRI",
+];
+
+const HEX_BYTES_PER_LINE: usize = 16;
+
+/// Options controlling the output of [`dump`].
+#[derive(Debug, Clone, Copy)]
+pub struct DumpOptions {
+    indent: usize,
+    offsets: bool,
+    max_hex_bytes: Option<usize>,
+    encapsulated: bool,
+    #[cfg(feature = "oid-registry")]
+    oid_names: bool,
+}
+
+impl Default for DumpOptions {
+    fn default() -> DumpOptions {
+        DumpOptions {
+            indent: 2,
+            offsets: true,
+            max_hex_bytes: Some(128),
+            encapsulated: true,
+            #[cfg(feature = "oid-registry")]
+            oid_names: true,
+        }
+    }
+}
+
+impl DumpOptions {
+    pub fn new() -> DumpOptions {
+        DumpOptions::default()
+    }
+
+    /// The number of spaces each level of nesting is indented by. Defaults
+    /// to 2.
+    pub fn indent(mut self, indent: usize) -> DumpOptions {
+        self.indent = indent;
+        self
+    }
+
+    /// Whether each line starts with the offset of the value and the lengths
+    /// of its header and contents. Defaults to `true`.
+    pub fn offsets(mut self, offsets: bool) -> DumpOptions {
+        self.offsets = offsets;
+        self
+    }
+
+    /// The maximum number of bytes of opaque data which are shown as hex, or
+    /// `None` to show all of it. Defaults to 128.
+    pub fn max_hex_bytes(mut self, max_hex_bytes: Option<usize>) -> DumpOptions {
+        self.max_hex_bytes = max_hex_bytes;
+        self
+    }
+
+    /// Whether `OCTET STRING`s and `BIT STRING`s whose contents are
+    /// themselves DER are shown as the values they encapsulate. Defaults to
+    /// `true`.
+    pub fn encapsulated(mut self, encapsulated: bool) -> DumpOptions {
+        self.encapsulated = encapsulated;
+        self
+    }
+
+    /// Whether well-known `OBJECT IDENTIFIER`s are shown with their names.
+    /// Defaults to `true`.
+    #[cfg(feature = "oid-registry")]
+    pub fn oid_names(mut self, oid_names: bool) -> DumpOptions {
+        self.oid_names = oid_names;
+        self
+    }
+}
+
+/// Writes `data`, which should contain one or more DER encoded values, to
+/// `out` as an indented tree, in the style of `dumpasn1`. Each value is shown
+/// with its tag and, for primitive values, its decoded contents.
+///
+/// Malformed input doesn't stop the dump: the error is shown where it
+/// occurred, and the remaining contents of the enclosing value are skipped.
+///
+/// ```
+/// let mut out = String::new();
+/// asn1::dump(b"\x30\x06\x02\x01\x05\x01\x01\xff", &mut out, asn1::DumpOptions::new()).unwrap();
+/// assert_eq!(
+///     out,
+///     "     0  2+6     : SEQUENCE {
+///      2  2+1     :   INTEGER 5
+///      5  2+1     :   BOOLEAN TRUE
+///                 : }
+/// "
+/// );
+/// ```
+pub fn dump<W: fmt::Write>(data: &[u8], out: &mut W, options: DumpOptions) -> fmt::Result {
+    Dumper { out, options }.dump_elements(data, 0, 0)
+}
+
+struct Dumper<'w, W> {
+    out: &'w mut W,
+    options: DumpOptions,
+}
+
+impl<W: fmt::Write> Dumper<'_, W> {
+    fn start_line(
+        &mut self,
+        offset: Option<usize>,
+        lengths: Option<(usize, usize)>,
+        depth: u32,
+    ) -> fmt::Result {
+        if self.options.offsets {
+            match offset {
+                Some(offset) => write!(self.out, "{:>6} ", offset)?,
+                None => write!(self.out, "{:7}", "")?,
+            }
+            match lengths {
+                Some((header, contents)) => write!(self.out, "{:>2}+{:<6}: ", header, contents)?,
+                None => write!(self.out, "{:9}: ", "")?,
+            }
+        }
+        write!(self.out, "{:1$}", "", depth as usize * self.options.indent)
+    }
+
+    fn error(&mut self, offset: usize, depth: u32, e: ParseError, skipped: usize) -> fmt::Result {
+        self.start_line(Some(offset), None, depth)?;
+        writeln!(self.out, "Error: {} ({} bytes skipped)", e, skipped)
+    }
+
+    fn dump_elements(&mut self, data: &[u8], base: usize, depth: u32) -> fmt::Result {
+        let mut p = Parser::new(data);
+        let mut pos = 0;
+        while !p.is_empty() {
+            let tlv = match p.read_tlv() {
+                Ok(tlv) => tlv,
+                Err(e) => return self.error(base + pos, depth, e, data.len() - pos),
+            };
+            self.dump_tlv(tlv, base + pos, depth)?;
+            pos += tlv.full_data().len();
+        }
+        Ok(())
+    }
+
+    fn dump_tlv(&mut self, tlv: Tlv<'_>, offset: usize, depth: u32) -> fmt::Result {
+        let contents_len = tlv.data().len();
+        let header_len = tlv.full_data().len() - contents_len;
+        self.start_line(Some(offset), Some((header_len, contents_len)), depth)?;
+        write_tag_name(self.out, tlv.tag())?;
+
+        if tlv.tag().is_constructed() {
+            writeln!(self.out, " {{")?;
+            if depth >= MAX_DEPTH {
+                let e = ParseError::new(ParseErrorKind::ExceedsMaxDepth);
+                self.error(offset + header_len, depth + 1, e, contents_len)?;
+            } else {
+                self.dump_elements(tlv.data(), offset + header_len, depth + 1)?;
+            }
+            return self.close(depth);
+        }
+        self.dump_primitive(tlv, offset + header_len, depth)
+    }
+
+    fn close(&mut self, depth: u32) -> fmt::Result {
+        self.start_line(None, None, depth)?;
+        writeln!(self.out, "}}")
+    }
+
+    fn dump_primitive(&mut self, tlv: Tlv<'_>, offset: usize, depth: u32) -> fmt::Result {
+        let value = match parse_single::<Value>(tlv.full_data()) {
+            Ok(value) => value,
+            Err(e) => {
+                writeln!(self.out, " Error: {}", e)?;
+                return self.hex(tlv.data(), depth + 1);
+            }
+        };
+        match value {
+            Value::Boolean(v) => writeln!(self.out, " {}", if v { "TRUE" } else { "FALSE" }),
+            Value::Integer(v) | Value::Enumerated(v) => self.integer(v.as_bytes(), depth),
+            Value::BitString(v) => {
+                if v.padding_bits() != 0 {
+                    write!(self.out, ", {} unused bits", v.padding_bits())?;
+                    writeln!(self.out)?;
+                    return self.hex(v.as_bytes(), depth + 1);
+                }
+                self.opaque(v.as_bytes(), offset + 1, depth)
+            }
+            Value::OctetString(v) => self.opaque(v, offset, depth),
+            Value::Null => writeln!(self.out),
+            Value::ObjectIdentifier(v) => {
+                #[cfg(feature = "oid-registry")]
+                {
+                    if self.options.oid_names {
+                        return writeln!(self.out, " {:#}", v);
+                    }
+                }
+                writeln!(self.out, " {}", v)
+            }
+            Value::RelativeOid(v) => writeln!(self.out, " {}", v),
+            Value::Real(v) => writeln!(self.out, " {}", v.to_f64()),
+            Value::Utf8String(v) => writeln!(self.out, " {:?}", v.as_str()),
+            Value::PrintableString(v) => writeln!(self.out, " {:?}", v.as_str()),
+            Value::IA5String(v) => writeln!(self.out, " {:?}", v.as_str()),
+            Value::VisibleString(v) => writeln!(self.out, " {:?}", v.as_str()),
+            Value::BMPString(v) => {
+                let units = v
+                    .as_utf16_be_bytes()
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]));
+                let s = char::decode_utf16(units)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect::<String>();
+                writeln!(self.out, " {:?}", s)
+            }
+            Value::UniversalString(v) => {
+                let s = v
+                    .as_utf32_be_bytes()
+                    .chunks_exact(4)
+                    .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+                    .map(|c| char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect::<String>();
+                writeln!(self.out, " {:?}", s)
+            }
+            Value::UtcTime(v) => self.time(v.as_datetime()),
+            Value::GeneralizedTime(v) => self.time(v.as_datetime()),
+            _ => {
+                writeln!(self.out)?;
+                self.hex(tlv.data(), depth + 1)
+            }
+        }
+    }
+
+    fn integer(&mut self, data: &[u8], depth: u32) -> fmt::Result {
+        if data.len() > 8 {
+            writeln!(self.out)?;
+            return self.hex(data, depth + 1);
+        }
+        let mut v: i64 = if data[0] & 0x80 != 0 { -1 } else { 0 };
+        for b in data {
+            v = (v << 8) | i64::from(*b);
+        }
+        writeln!(self.out, " {}", v)
+    }
+
+    fn time(&mut self, v: &DateTime) -> fmt::Result {
+        write!(
+            self.out,
+            " {:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            v.year(),
+            v.month(),
+            v.day(),
+            v.hour(),
+            v.minute(),
+            v.second()
+        )?;
+        if v.nanosecond() != 0 {
+            let fraction = format!("{:09}", v.nanosecond());
+            write!(self.out, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        writeln!(self.out, " UTC")
+    }
+
+    // Writes the contents of an `OCTET STRING` or `BIT STRING`, descending
+    // into them if they contain DER.
+    fn opaque(&mut self, data: &[u8], offset: usize, depth: u32) -> fmt::Result {
+        if self.options.encapsulated
+            && depth < MAX_DEPTH
+            && !data.is_empty()
+            && parse_single::<Value>(data).is_ok()
+        {
+            writeln!(self.out, ", encapsulates {{")?;
+            self.dump_elements(data, offset, depth + 1)?;
+            return self.close(depth);
+        }
+        writeln!(self.out)?;
+        self.hex(data, depth + 1)
+    }
+
+    fn hex(&mut self, data: &[u8], depth: u32) -> fmt::Result {
+        let shown = match self.options.max_hex_bytes {
+            Some(max) if max < data.len() => &data[..max],
+            _ => data,
+        };
+        for chunk in shown.chunks(HEX_BYTES_PER_LINE) {
+            self.start_line(None, None, depth)?;
+            for (i, b) in chunk.iter().enumerate() {
+                if i != 0 {
+                    write!(self.out, " ")?;
+                }
+                write!(self.out, "{:02X}", b)?;
+            }
+            writeln!(self.out)?;
+        }
+        if shown.len() < data.len() {
+            self.start_line(None, None, depth)?;
+            writeln!(
+                self.out,
+                "[ Another {} bytes skipped ]",
+                data.len() - shown.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn write_tag_name<W: fmt::Write>(out: &mut W, tag: Tag) -> fmt::Result {
+    if tag.class() == TagClass::Universal {
+        if let Some(name) = UNIVERSAL_NAMES.get(tag.value() as usize) {
+            if !name.is_empty() {
+                return write!(out, "{}", name);
+            }
+        }
+    }
+    write!(out, "{}", tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dump, DumpOptions};
+    use alloc::string::String;
+
+    fn dump_to_string(data: &[u8], options: DumpOptions) -> String {
+        let mut out = String::new();
+        dump(data, &mut out, options).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_dump() {
+        // SEQUENCE {
+        //   [0] EXPLICIT INTEGER -129,
+        //   OBJECT IDENTIFIER 1.2.840.113549,
+        //   OCTET STRING containing SEQUENCE { NULL },
+        //   BIT STRING 0x0ab0 with 4 unused bits,
+        //   UTF8String "hé",
+        //   GeneralizedTime 20200101123456.5Z,
+        //   [APPLICATION 3] IMPLICIT OCTET STRING 0x01..0x11,
+        // }
+        let data = b"\x30\x44\xa0\x04\x02\x02\xff\x7f\x06\x06\x2a\x86\x48\x86\xf7\x0d\x04\x04\x30\x02\x05\x00\x03\x03\x04\x0a\xb0\x0c\x03h\xc3\xa9\x18\x1120200101123456.5Z\x43\x11\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11";
+        assert_eq!(
+            dump_to_string(data, DumpOptions::new()),
+            "     0  2+68    : SEQUENCE {
+     2  2+4     :   [0] {
+     4  2+2     :     INTEGER -129
+                :   }
+     8  2+6     :   OBJECT IDENTIFIER 1.2.840.113549
+    16  2+4     :   OCTET STRING, encapsulates {
+    18  2+2     :     SEQUENCE {
+    20  2+0     :       NULL
+                :     }
+                :   }
+    22  2+3     :   BIT STRING, 4 unused bits
+                :     0A B0
+    27  2+3     :   UTF8String \"hé\"
+    32  2+17    :   GeneralizedTime 2020-01-01 12:34:56.5 UTC
+    51  2+17    :   [APPLICATION 3]
+                :     01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F 10
+                :     11
+                : }
+"
+        );
+
+        assert_eq!(
+            dump_to_string(
+                data,
+                DumpOptions::new()
+                    .offsets(false)
+                    .indent(1)
+                    .encapsulated(false)
+                    .max_hex_bytes(Some(4))
+            ),
+            "SEQUENCE {
+ [0] {
+  INTEGER -129
+ }
+ OBJECT IDENTIFIER 1.2.840.113549
+ OCTET STRING
+  30 02 05 00
+ BIT STRING, 4 unused bits
+  0A B0
+ UTF8String \"hé\"
+ GeneralizedTime 2020-01-01 12:34:56.5 UTC
+ [APPLICATION 3]
+  01 02 03 04
+  [ Another 13 bytes skipped ]
+}
+"
+        );
+    }
+
+    #[cfg(feature = "oid-registry")]
+    #[test]
+    fn test_dump_oid_names() {
+        let data = b"\x06\x09\x2a\x86\x48\x86\xf7\x0d\x01\x01\x0b";
+        assert_eq!(
+            dump_to_string(data, DumpOptions::new().offsets(false)),
+            "OBJECT IDENTIFIER sha256WithRSAEncryption (1.2.840.113549.1.1.11)\n"
+        );
+        assert_eq!(
+            dump_to_string(data, DumpOptions::new().offsets(false).oid_names(false)),
+            "OBJECT IDENTIFIER 1.2.840.113549.1.1.11\n"
+        );
+    }
+
+    #[test]
+    fn test_dump_malformed() {
+        // A SEQUENCE containing an invalid BOOLEAN, an INTEGER, and then a
+        // truncated element, followed by a valid NULL.
+        let data = b"\x30\x0a\x01\x01\x01\x02\x02\x01\x00\x04\x05\x00\x05\x00";
+        assert_eq!(
+            dump_to_string(data, DumpOptions::new()),
+            "     0  2+10    : SEQUENCE {
+     2  2+1     :   BOOLEAN Error: ASN.1 parsing error: invalid value
+                :     01
+     5  2+2     :   INTEGER 256
+     9          :   Error: ASN.1 parsing error: short data (3 bytes skipped)
+                : }
+    12  2+0     : NULL
+"
+        );
+
+        assert_eq!(
+            dump_to_string(b"\x02\x01", DumpOptions::new()),
+            "     0          : Error: ASN.1 parsing error: short data (2 bytes skipped)\n"
+        );
+    }
+}
@@ -4,9 +4,13 @@
 //! This crate provides you with the ability to generate and parse ASN.1
 //! encoded data. More precisely, it provides you with the ability to generate
 //! and parse data encoded with ASN.1's DER (Distinguished Encoding Rules)
-//! encoding. It does not support BER (Basic Encoding Rules), CER (Canonical
-//! Encoding Rules), XER (XML Encoding Rules), CXER (Canonical XML Encoding
-//! Rules), or any other alphabet soup encodings -- and it never will.
+//! encoding. It does not support CER (Canonical Encoding Rules), XER (XML
+//! Encoding Rules), CXER (Canonical XML Encoding Rules), or any other alphabet
+//! soup encodings -- and it never will.
+//!
+//! BER (Basic Encoding Rules) input can be parsed with [`parse_ber`] and
+//! [`BerInput`], which re-encode it as DER before parsing, or converted to DER
+//! with [`ber_to_der`]. Parsing with [`parse`] always requires DER.
 //!
 //! If you wanted to parse an ASN.1 structure like this:
 //! ```text
@@ -37,18 +41,39 @@
 //! handling tagged values, `Choice1`, `Choice2`, and `Choice3` available for
 //! choices, and `Option<T>` for handling `OPTIONAL` values.
 //!
+//! Most of these types borrow from the data being parsed. Most have an owned
+//! counterpart for when values need to outlive it: `String` for
+//! `Utf8String`, `Vec<T>` (or `Box<[T]>`) for `SequenceOf<T>`, `OwnedSetOf<T>`
+//! for `SetOf<T>`, `OwnedOctetString` for `&[u8]`, and `OwnedTlv`, `OwnedValue`,
+//! `OwnedBitString`, `OwnedBigInt`, `OwnedBigUint`, `OwnedSequence`,
+//! `OwnedPrintableString`, `OwnedIA5String`, `OwnedVisibleString`,
+//! `OwnedBMPString`, `OwnedUniversalString`, `OwnedOidIri`, and
+//! `OwnedRelativeOidIri` for the types of the same names. Unlike their
+//! borrowed counterparts, these can be both read and written, so structs
+//! using them can derive both `Asn1Read` and `Asn1Write`.
+//!
 //! To serialize DER for the `Sequence` structure, you'd write the following:
 //! ```
 //! # let r = 0u64;
 //! # let s = 0u64;
 //! let result = asn1::write(|w| {
 //!     w.write_element(&asn1::SequenceWriter::new(&|w| {
-//!         w.write_element(&r);
-//!         w.write_element(&s);
-//!     }));
+//!         w.write_element(&r)?;
+//!         w.write_element(&s)?;
+//!         Ok(())
+//!     }))
 //! });
 //! ```
 //!
+//! Writing returns a [`WriteResult`]: it fails if a value can't be encoded,
+//! or, with [`write_with_limit`], if the output would be larger than the
+//! given limit. Callbacks which need to return their own errors may use
+//! [`try_write`].
+//!
+//! Values can also be written into a caller-provided buffer, without
+//! allocating, with [`write_to_slice`] and [`write_single_to_slice`]. This is
+//! the only way to write them without the `alloc` feature.
+//!
 //! # Derive
 //!
 //! When built with the `derive` feature (enabled by default), these can also
@@ -96,32 +121,153 @@
 //! ```
 //!
 //! All variants must have a single un-named field.
+//!
+//! Structs annotated with `#[asn1(set)]` are encoded as a `SET` rather than a
+//! `SEQUENCE`. When parsing, their elements may appear in any order and are
+//! matched to fields by their tags. When writing, they are emitted in the
+//! canonical order of their tags, as DER requires.
+//! ```text
+//! #[derive(asn1::Asn1Read, asn1::Asn1Write)]
+//! #[asn1(set)]
+//! struct Attributes<'a> {
+//!     #[implicit(0)]
+//!     name: Option<asn1::Utf8String<'a>>,
+//!     version: u64,
+//! }
+//! ```
+//!
+//! `ANY DEFINED BY` values, whose type is selected by an `OBJECT IDENTIFIER`
+//! earlier in the structure, are expressed with a `#[defined_by(field)]`
+//! field whose type derives `Asn1DefinedByRead` and `Asn1DefinedByWrite`.
+//! Each variant gives the `OBJECT IDENTIFIER` which selects it, and the
+//! optional `#[defined_by(unknown)]` variant holds unknown `OBJECT
+//! IDENTIFIER`s along with their values. The field being referred to is a
+//! [`DefinedByMarker`], since its value is determined by the variant.
+//! ```text
+//! #[derive(asn1::Asn1DefinedByRead, asn1::Asn1DefinedByWrite)]
+//! enum AlgorithmParameters<'a> {
+//!     #[defined_by(asn1::oid!(1, 2, 840, 113549, 1, 1, 11))]
+//!     Sha256WithRsa(asn1::Null),
+//!     #[defined_by(asn1::oid!(1, 2, 840, 10045, 4, 3, 2))]
+//!     EcdsaWithSha256,
+//!     #[defined_by(unknown)]
+//!     Other(asn1::ObjectIdentifier, Option<asn1::Tlv<'a>>),
+//! }
+//!
+//! #[derive(asn1::Asn1Read, asn1::Asn1Write)]
+//! struct AlgorithmIdentifier<'a> {
+//!     algorithm: asn1::DefinedByMarker,
+//!     #[defined_by(algorithm)]
+//!     params: AlgorithmParameters<'a>,
+//! }
+//! ```
+//!
+//! Types annotated with `#[asn1(extensible)]` correspond to ASN.1 types with
+//! an extension marker (`...`). Unknown trailing elements of a `SEQUENCE`,
+//! and elements with unknown tags in a `SET`, are skipped rather than
+//! rejected. To keep them instead, annotate the last field of a `SEQUENCE`
+//! with `#[extensions]`; its type may be `Vec<asn1::Tlv>`,
+//! `Vec<asn1::OwnedTlv>`, or `&[u8]`, and its contents are written back
+//! verbatim. Similarly, a `CHOICE` may have a single `#[extensions]` variant
+//! holding an `asn1::Tlv`, which receives any alternative that is not
+//! otherwise recognized. Because such a `CHOICE` accepts any tag, it can't be
+//! told apart from the element after it when it's `OPTIONAL` or has a
+//! `DEFAULT`: when it's absent, it consumes that element instead. Fields like
+//! these must be `#[explicit]`ly tagged.
+//! ```text
+//! #[derive(asn1::Asn1Read, asn1::Asn1Write)]
+//! struct Extensible<'a> {
+//!     version: u64,
+//!     #[extensions]
+//!     extensions: Vec<asn1::Tlv<'a>>,
+//! }
+//! ```
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "serde")]
+extern crate serde_crate as serde;
 
+#[cfg(feature = "alloc")]
+mod ber;
 mod bit_string;
+#[cfg(feature = "serde")]
+mod de;
+#[cfg(feature = "alloc")]
+mod dump;
 mod object_identifier;
+#[cfg(feature = "oid-registry")]
+mod oid_registry;
 mod parser;
+#[cfg(feature = "alloc")]
+mod pem;
+mod real;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod tag;
+mod time;
 mod types;
+#[cfg(feature = "alloc")]
+mod value;
 mod writer;
 
-pub use crate::bit_string::{BitString, OwnedBitString};
-pub use crate::object_identifier::ObjectIdentifier;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub use crate::ber::MAX_DEPTH;
+#[cfg(feature = "alloc")]
+pub use crate::ber::{ber_to_der, parse_ber, parse_single_ber, BerInput};
+pub use crate::bit_string::BitString;
+#[cfg(feature = "alloc")]
+pub use crate::bit_string::OwnedBitString;
+#[cfg(feature = "serde")]
+pub use crate::de::{from_der, from_der_with_options, Deserializer};
+#[cfg(feature = "alloc")]
+pub use crate::dump::{dump, DumpOptions};
+pub use crate::object_identifier::{Arcs, ObjectIdentifier, ParseOidError, RelativeOid};
+#[cfg(feature = "oid-registry")]
+pub use crate::oid_registry::{OidDisplay, OidEntry, OidRegistry};
 pub use crate::parser::{
     parse, parse_single, ParseError, ParseErrorKind, ParseLocation, ParseResult, Parser,
 };
+#[cfg(feature = "alloc")]
+pub use crate::pem::{
+    parse_pem, pem_decode, pem_decode_all, pem_encode, Pem, PemError, PEM_LINE_WIDTH,
+};
+pub use crate::real::Real;
+#[cfg(feature = "serde")]
+pub use crate::ser::{to_der, to_der_with_options, SerdeError, SerdeOptions, Serializer};
+pub use crate::tag::{Tag, TagClass};
+pub use crate::time::DateTime;
 pub use crate::types::{
-    Asn1Readable, Asn1Writable, BMPString, BigInt, BigUint, Choice1, Choice2, Choice3, Enumerated,
-    GeneralizedTime, IA5String, Null, PrintableString, Sequence, SequenceOf, SequenceOfWriter,
-    SequenceWriter, SetOf, SetOfWriter, SimpleAsn1Readable, SimpleAsn1Writable, Tlv,
-    UniversalString, UtcTime, Utf8String, VisibleString,
+    Asn1DefinedByReadable, Asn1DefinedByWritable, Asn1ExtensionsReadable, Asn1ExtensionsWritable,
+    Asn1Readable, Asn1Writable, BMPString, BigInt, BigUint, Choice1, Choice2, Choice3,
+    DefinedByMarker, Enumerated, GeneralizedTime, IA5String, Null, OidIri, PrintableString,
+    RelativeOidIri, Sequence, SequenceOf, SequenceOfWriter, SequenceWriter, SetOf, SetOfWriter,
+    SimpleAsn1Readable, SimpleAsn1Writable, Tlv, UniversalString, UtcTime, Utf8String,
+    VisibleString,
 };
 #[cfg(feature = "const-generics")]
 pub use crate::types::{Explicit, Implicit};
-pub use crate::writer::{write, write_single, Writer};
+#[cfg(feature = "alloc")]
+pub use crate::types::{
+    OwnedBMPString, OwnedBigInt, OwnedBigUint, OwnedIA5String, OwnedOctetString, OwnedOidIri,
+    OwnedPrintableString, OwnedRelativeOidIri, OwnedSequence, OwnedSetOf, OwnedTlv,
+    OwnedUniversalString, OwnedVisibleString,
+};
+#[cfg(feature = "alloc")]
+pub use crate::value::{OwnedValue, Value};
+#[cfg(feature = "alloc")]
+pub use crate::writer::{
+    try_write, write, write_single, write_single_with_limit, write_with_limit,
+};
+pub use crate::writer::{
+    write_single_to_slice, write_to_slice, WriteBuf, WriteError, WriteResult, Writer,
+};
 
 #[cfg(feature = "derive")]
-pub use asn1_derive::{oid, Asn1Read, Asn1Write};
+pub use asn1_derive::{oid, Asn1DefinedByRead, Asn1DefinedByWrite, Asn1Read, Asn1Write};
 
 /// Decodes an `OPTIONAL` ASN.1 value which has a `DEFAULT`. Generaly called
 /// immediately after [`Parser::read_element`].
@@ -143,16 +289,43 @@ pub fn to_optional_default<'a, T: PartialEq>(v: &'a T, default: &'a T) -> Option
     }
 }
 
+/// Sorts the elements of a `SET` written to `dest` since `start` into the
+/// canonical order of their tags. This API is public so that it may be used
+/// from macros, but should not be considered a part of the supported API
+/// surface.
+#[doc(hidden)]
+pub fn sort_set_elements(dest: &mut WriteBuf, start: usize) -> WriteResult {
+    let tag = |el: &[u8]| Tag::from_bytes(el).ok().map(|(tag, _)| tag);
+    dest.sort_elements_from(start, |a, b| tag(a) < tag(b));
+    Ok(())
+}
+
 /// This API is public so that it may be used from macros, but should not be
 /// considered a part of the supported API surface.
 #[doc(hidden)]
-pub const fn implicit_tag(tag: u8, inner_tag: u8) -> u8 {
-    types::CONTEXT_SPECIFIC | tag | (inner_tag & types::CONSTRUCTED)
+pub const fn implicit_tag(tag: u32, inner_tag: Tag) -> Tag {
+    Tag::new(tag, TagClass::ContextSpecific, inner_tag.is_constructed())
 }
 
 /// This API is public so that it may be used from macros, but should not be
 /// considered a part of the supported API surface.
 #[doc(hidden)]
-pub const fn explicit_tag(tag: u8) -> u8 {
-    types::CONTEXT_SPECIFIC | types::CONSTRUCTED | tag
+pub const fn explicit_tag(tag: u32) -> Tag {
+    Tag::new(tag, TagClass::ContextSpecific, true)
+}
+
+/// Returns the length of `v` written with [`Writer::write_explicit_element`],
+/// if known. This API is public so that it may be used from macros, but
+/// should not be considered a part of the supported API surface.
+#[doc(hidden)]
+pub fn explicit_encoded_len<'a, T: Asn1Writable<'a>>(v: &T, tag: u32) -> Option<usize> {
+    writer::_tlv_len(explicit_tag(tag), v.encoded_len()?)
+}
+
+/// Returns the length of `v` written with [`Writer::write_implicit_element`],
+/// if known. This API is public so that it may be used from macros, but
+/// should not be considered a part of the supported API surface.
+#[doc(hidden)]
+pub fn implicit_encoded_len<'a, T: SimpleAsn1Writable<'a>>(v: &T, tag: u32) -> Option<usize> {
+    writer::_tlv_len(implicit_tag(tag, T::TAG), v.data_len()?)
 }
@@ -1,43 +1,109 @@
 use crate::parser::{ParseError, ParseErrorKind, ParseResult};
-use alloc::fmt;
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{cmp, fmt, iter, slice, str};
 
-const MAX_OID_LENGTH: usize = 63;
+const MAX_INLINE_OID_LENGTH: usize = 63;
 
-/// Represents an ASN.1 `OBJECT IDENTIFIER`. ObjectIdentifiers are opaque, the only thing may be
-/// done with them is test if they are equal to another `ObjectIdentifier`. The generally
-/// recommended practice for handling them is to create some `ObjectIdentifier` constants with
-/// `asn1::oid!()` and then compare ObjectIdentifiers you get from parsing to
-/// those.
+/// Represents an ASN.1 `OBJECT IDENTIFIER`. The generally recommended practice for handling
+/// them is to create some `ObjectIdentifier` constants with `asn1::oid!()` (or
+/// `ObjectIdentifier::from_dotted_str`) and then compare ObjectIdentifiers you get from parsing
+/// to those. Their arcs can also be inspected with `arcs()`, and they can be compared to, and
+/// built from, other OIDs with `starts_with()`, `parent()`, and `with_child()`.
 ///
 /// `asn1::oid!()` takes a series of arcs, for example: `asn1::oid!(1, 2, 3)`.
 ///
-/// rust-asn1 stores `ObjectIdentifier`s in a fixed-size buffer, therefore
-/// they are limited to OID values whose DER encoding fits into that buffer.
-/// This buffer is sufficiently large to fit all known publically known OIDs,
-/// so this should not effect most people.
+/// Arcs may be as large as `u128`, which is enough for the UUID-based OIDs
+/// under `2.25`. OIDs whose DER encoding is short (which is all commonly used
+/// ones) are stored inline, without allocating; longer ones are stored on the
+/// heap, or, for `asn1::oid!()`, in static data. Those longer `asn1::oid!()`
+/// values can still be constants, but can't be used as patterns. Without the
+/// `alloc` feature, only the inline form is available, and parsing longer
+/// OIDs fails with [`ParseErrorKind::OidTooLong`].
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct ObjectIdentifier {
     // Store the OID as DER encoded.
-    pub der_encoded: [u8; MAX_OID_LENGTH],
-    pub der_encoded_len: u8,
+    der_encoded: OidStorage,
 }
 
-fn _read_base128_int<I: Iterator<Item = u8>>(mut reader: I) -> ParseResult<u32> {
-    let mut ret = 0u32;
-    for _ in 0..4 {
+// An OID is stored inline if, and only if, its encoding fits, so that the
+// derived comparisons (and matching against constants) are by value.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+enum OidStorage {
+    Inline {
+        data: [u8; MAX_INLINE_OID_LENGTH],
+        len: u8,
+    },
+    #[cfg(feature = "alloc")]
+    Long(Cow<'static, [u8]>),
+}
+
+impl OidStorage {
+    // Returns `None` if `data` is too long to be stored without allocating,
+    // and allocation isn't available.
+    fn new(data: &[u8]) -> Option<OidStorage> {
+        if data.len() <= MAX_INLINE_OID_LENGTH {
+            let mut storage = [0; MAX_INLINE_OID_LENGTH];
+            storage[..data.len()].copy_from_slice(data);
+            Some(OidStorage::Inline {
+                data: storage,
+                len: data.len() as u8,
+            })
+        } else {
+            #[cfg(feature = "alloc")]
+            return Some(OidStorage::Long(Cow::Owned(data.to_vec())));
+            #[cfg(not(feature = "alloc"))]
+            return None;
+        }
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            OidStorage::Inline { data, len } => &data[..*len as usize],
+            #[cfg(feature = "alloc")]
+            OidStorage::Long(data) => data,
+        }
+    }
+}
+
+// Checks that `data` is a non-empty series of base-128 integers, as both
+// `OBJECT IDENTIFIER`s and `RELATIVE-OID`s are encoded.
+fn validate_base128_ints(data: &[u8]) -> ParseResult<()> {
+    if data.is_empty() {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue));
+    }
+    let mut cursor = data.iter().copied();
+    while cursor.len() > 0 {
+        _read_base128_int(&mut cursor)?;
+    }
+    Ok(())
+}
+
+fn _read_base128_int<I: Iterator<Item = u8>>(mut reader: I) -> ParseResult<u128> {
+    let mut ret = 0u128;
+    loop {
         let b = reader
             .next()
             .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))?;
+        // Arcs must be minimally encoded (X.690 8.19.2), i.e. without a
+        // leading 0x80 byte.
+        if ret == 0 && b == 0x80 {
+            return Err(ParseError::new(ParseErrorKind::InvalidValue));
+        }
+        if ret.leading_zeros() < 7 {
+            return Err(ParseError::new(ParseErrorKind::InvalidValue));
+        }
         ret <<= 7;
-        ret |= u32::from(b & 0x7f);
+        ret |= u128::from(b & 0x7f);
         if b & 0x80 == 0 {
             return Ok(ret);
         }
     }
-    Err(ParseError::new(ParseErrorKind::InvalidValue))
 }
 
-fn _write_base128_int(mut data: &mut [u8], n: u32) -> Option<usize> {
+pub(crate) fn _write_base128_int(mut data: &mut [u8], n: u128) -> Option<usize> {
     if n == 0 {
         if data.is_empty() {
             return None;
@@ -69,93 +135,473 @@ fn _write_base128_int(mut data: &mut [u8], n: u32) -> Option<usize> {
     Some(length)
 }
 
-impl ObjectIdentifier {
-    /// Parses an OID from a dotted string, e.g. `"1.2.840.113549"`.
-    pub fn from_string(oid: &str) -> Option<ObjectIdentifier> {
-        let mut parts = oid.split('.');
+#[cfg(feature = "alloc")]
+fn _push_base128_int(data: &mut Vec<u8>, n: u128) {
+    // A `u128` takes at most 19 base-128 digits.
+    let mut buf = [0; 19];
+    let length = _write_base128_int(&mut buf, n).unwrap();
+    data.extend_from_slice(&buf[..length]);
+}
 
-        let first = parts.next()?.parse::<u32>().ok()?;
-        let second = parts.next()?.parse::<u32>().ok()?;
-        if first > 2 || (first < 2 && second >= 40) {
-            return None;
-        }
+/// The error returned when an `ObjectIdentifier` can't be parsed from a
+/// dotted string.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ParseOidError {
+    /// There were fewer than two arcs.
+    TooFewArcs,
+    /// An arc was empty, or contained something other than decimal digits.
+    InvalidArc,
+    /// An arc was too large to be represented.
+    ArcTooLarge,
+    /// The first arc was not 0, 1, or 2, or the first arc was 0 or 1 and the
+    /// second was 40 or greater.
+    InvalidRootArcs,
+}
 
-        let mut der_data = [0; MAX_OID_LENGTH];
-        let mut der_data_len = 0;
-        der_data_len += _write_base128_int(&mut der_data[der_data_len..], 40 * first + second)?;
-        for part in parts {
-            der_data_len +=
-                _write_base128_int(&mut der_data[der_data_len..], part.parse::<u32>().ok()?)?;
+impl fmt::Display for ParseOidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseOidError::TooFewArcs => write!(f, "OBJECT IDENTIFIER had fewer than two arcs"),
+            ParseOidError::InvalidArc => {
+                write!(f, "OBJECT IDENTIFIER arc was not a decimal number")
+            }
+            ParseOidError::ArcTooLarge => write!(f, "OBJECT IDENTIFIER arc was too large"),
+            ParseOidError::InvalidRootArcs => {
+                write!(f, "OBJECT IDENTIFIER had invalid first and second arcs")
+            }
         }
-        Some(ObjectIdentifier {
-            der_encoded: der_data,
-            der_encoded_len: der_data_len as u8,
-        })
     }
+}
 
-    /// Creates an `ObjectIdentifier` from its DER representation. This does
-    /// not perform any allocations or copies.
-    pub fn from_der(data: &[u8]) -> ParseResult<ObjectIdentifier> {
-        if data.is_empty() {
-            return Err(ParseError::new(ParseErrorKind::InvalidValue));
-        } else if data.len() > MAX_OID_LENGTH {
-            return Err(ParseError::new(ParseErrorKind::OidTooLong));
+#[cfg(feature = "std")]
+impl std::error::Error for ParseOidError {}
+
+// The dotted string parsing helpers are `const fn`s, so that they can be
+// shared by `FromStr` and `ObjectIdentifier::from_dotted_str`.
+
+// Parses the arc starting at `pos`, returning it and the position of the next
+// arc (or the end of the string).
+const fn parse_arc(s: &[u8], mut pos: usize) -> Result<(u128, usize), ParseOidError> {
+    let start = pos;
+    let mut arc: u128 = 0;
+    while pos < s.len() && s[pos] != b'.' {
+        let digit = match s[pos] {
+            b'0'..=b'9' => (s[pos] - b'0') as u128,
+            _ => return Err(ParseOidError::InvalidArc),
+        };
+        if arc > (u128::MAX - digit) / 10 {
+            return Err(ParseOidError::ArcTooLarge);
         }
-        let mut cursor = data.iter().copied();
-        while cursor.len() > 0 {
-            _read_base128_int(&mut cursor)?;
+        arc = arc * 10 + digit;
+        pos += 1;
+    }
+    if pos == start {
+        return Err(ParseOidError::InvalidArc);
+    }
+    if pos < s.len() {
+        // Skip the `.`, which must be followed by another arc.
+        pos += 1;
+        if pos == s.len() {
+            return Err(ParseOidError::InvalidArc);
+        }
+    }
+    Ok((arc, pos))
+}
+
+// Parses the first two arcs, returning the single value they're encoded as.
+const fn parse_root_arcs(s: &[u8]) -> Result<(u128, usize), ParseOidError> {
+    let (first, pos) = match parse_arc(s, 0) {
+        Ok(v) => v,
+        Err(e) => return Err(e),
+    };
+    if pos == s.len() {
+        return Err(ParseOidError::TooFewArcs);
+    }
+    let (second, pos) = match parse_arc(s, pos) {
+        Ok(v) => v,
+        Err(e) => return Err(e),
+    };
+    if first > 2 || (first < 2 && second >= 40) {
+        return Err(ParseOidError::InvalidRootArcs);
+    }
+    if second > u128::MAX - 40 * first {
+        return Err(ParseOidError::ArcTooLarge);
+    }
+    Ok((40 * first + second, pos))
+}
+
+const fn push_inline_base128_int(
+    mut data: [u8; MAX_INLINE_OID_LENGTH],
+    mut len: usize,
+    n: u128,
+) -> Option<([u8; MAX_INLINE_OID_LENGTH], usize)> {
+    let mut digits = 1;
+    let mut i = n >> 7;
+    while i > 0 {
+        digits += 1;
+        i >>= 7;
+    }
+    if len + digits > MAX_INLINE_OID_LENGTH {
+        return None;
+    }
+    while digits > 0 {
+        digits -= 1;
+        let mut o = (n >> (digits * 7)) as u8 & 0x7f;
+        if digits != 0 {
+            o |= 0x80;
         }
+        data[len] = o;
+        len += 1;
+    }
+    Some((data, len))
+}
+
+const fn encode_inline_dotted_str(s: &[u8]) -> Option<([u8; MAX_INLINE_OID_LENGTH], usize)> {
+    let (root, mut pos) = match parse_root_arcs(s) {
+        Ok(v) => v,
+        Err(_) => return None,
+    };
+    let (mut data, mut len) = match push_inline_base128_int([0; MAX_INLINE_OID_LENGTH], 0, root) {
+        Some(v) => v,
+        None => return None,
+    };
+    while pos < s.len() {
+        let (arc, next) = match parse_arc(s, pos) {
+            Ok(v) => v,
+            Err(_) => return None,
+        };
+        let pushed = match push_inline_base128_int(data, len, arc) {
+            Some(v) => v,
+            None => return None,
+        };
+        data = pushed.0;
+        len = pushed.1;
+        pos = next;
+    }
+    Some((data, len))
+}
+
+impl ObjectIdentifier {
+    /// Parses an OID from a dotted string, e.g. `"1.2.840.113549"`. See the
+    /// `FromStr` implementation for a version which describes why parsing
+    /// failed.
+    #[cfg(feature = "alloc")]
+    pub fn from_string(oid: &str) -> Option<ObjectIdentifier> {
+        oid.parse().ok()
+    }
 
-        let mut storage = [0; MAX_OID_LENGTH];
-        storage[..data.len()].copy_from_slice(data);
+    /// Parses an OID from a dotted string, e.g. `"1.2.840.113549"`, in a
+    /// `const` context. This allows defining constants without the `oid!()`
+    /// macro:
+    ///
+    /// ```
+    /// const RSA: asn1::ObjectIdentifier =
+    ///     asn1::ObjectIdentifier::from_dotted_str("1.2.840.113549.1.1.1");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `oid` is not a valid OID, or if it is too long to be stored
+    /// inline (which is a compile error when defining a constant).
+    pub const fn from_dotted_str(oid: &str) -> ObjectIdentifier {
+        let (data, len, valid) = match encode_inline_dotted_str(oid.as_bytes()) {
+            Some((data, len)) => (data, len, true),
+            None => ([0; MAX_INLINE_OID_LENGTH], 0, false),
+        };
+        // `panic!()` can't be used in a `const fn` on our MSRV, but indexing
+        // out of bounds fails in the same way.
+        let invalid_or_too_long_object_identifier = [(); 1];
+        #[allow(clippy::no_effect)]
+        invalid_or_too_long_object_identifier[!valid as usize];
+        ObjectIdentifier::from_der_unchecked(data, len as u8)
+    }
 
+    /// Creates an `ObjectIdentifier` from its DER representation. This only
+    /// allocates if the OID is too long to be stored inline, and fails with
+    /// [`ParseErrorKind::OidTooLong`] in that case without the `alloc`
+    /// feature.
+    pub fn from_der(data: &[u8]) -> ParseResult<ObjectIdentifier> {
+        validate_base128_ints(data)?;
         Ok(ObjectIdentifier {
-            der_encoded: storage,
-            der_encoded_len: data.len() as u8,
+            der_encoded: OidStorage::new(data)
+                .ok_or_else(|| ParseError::new(ParseErrorKind::OidTooLong))?,
         })
     }
 
+    // `data` must fit inline if the `alloc` feature isn't enabled.
+    fn from_valid_der(data: &[u8]) -> ObjectIdentifier {
+        ObjectIdentifier {
+            der_encoded: OidStorage::new(data).unwrap(),
+        }
+    }
+
     /// Creates an `ObjectIdentifier` from its DER representation. Does not
     /// check that the DER is valid. Intended only for use from the `oid!()`
     /// macro. Do not use yourself!
     #[doc(hidden)]
-    pub const fn from_der_unchecked(data: [u8; MAX_OID_LENGTH], data_len: u8) -> ObjectIdentifier {
+    pub const fn from_der_unchecked(
+        data: [u8; MAX_INLINE_OID_LENGTH],
+        data_len: u8,
+    ) -> ObjectIdentifier {
+        ObjectIdentifier {
+            der_encoded: OidStorage::Inline {
+                data,
+                len: data_len,
+            },
+        }
+    }
+
+    /// Creates an `ObjectIdentifier` from its DER representation, which is
+    /// too long to be stored inline. Does not check that the DER is valid.
+    /// Intended only for use from the `oid!()` macro. Do not use yourself!
+    #[doc(hidden)]
+    #[cfg(feature = "alloc")]
+    pub const fn from_static_der_unchecked(data: &'static [u8]) -> ObjectIdentifier {
         ObjectIdentifier {
-            der_encoded: data,
-            der_encoded_len: data_len,
+            der_encoded: OidStorage::Long(Cow::Borrowed(data)),
         }
     }
 
     pub(crate) fn as_der(&self) -> &[u8] {
-        &self.der_encoded[..self.der_encoded_len as usize]
+        self.der_encoded.as_slice()
+    }
+
+    fn fmt_dotted(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut arcs = self.arcs();
+        write!(f, "{}", arcs.next().unwrap())?;
+        for arc in arcs {
+            write!(f, ".{}", arc)?;
+        }
+        Ok(())
+    }
+
+    /// Returns an iterator over the arcs of this OID, e.g. `1`, `2`, `840`,
+    /// `113549` for `1.2.840.113549`.
+    pub fn arcs(&self) -> Arcs<'_> {
+        let mut rest = self.as_der().iter().copied();
+        let root = _read_base128_int(&mut rest).unwrap();
+        let (first, second) = if root < 80 {
+            (root / 40, root % 40)
+        } else {
+            (2, root - 80)
+        };
+        Arcs {
+            first: Some(first),
+            second: Some(second),
+            rest,
+        }
+    }
+
+    /// Returns whether `prefix`'s arcs are a prefix of this OID's arcs. An
+    /// OID starts with itself.
+    pub fn starts_with(&self, prefix: &ObjectIdentifier) -> bool {
+        // Every encoded arc ends in a byte without the high bit set, so a
+        // prefix of the encoding always ends on an arc boundary.
+        self.as_der().starts_with(prefix.as_der())
+    }
+
+    /// Returns whether this OID is an immediate child of `parent`, that is
+    /// it's `parent` with a single additional arc.
+    pub fn is_child_of(&self, parent: &ObjectIdentifier) -> bool {
+        if !self.starts_with(parent) {
+            return false;
+        }
+        match self.as_der()[parent.as_der().len()..].split_last() {
+            Some((_, leading)) => leading.iter().all(|b| b & 0x80 != 0),
+            None => false,
+        }
+    }
+
+    /// Returns this OID with its last arc removed, or `None` if it only has
+    /// two arcs (as OIDs can't have fewer).
+    pub fn parent(&self) -> Option<ObjectIdentifier> {
+        let der = self.as_der();
+        let last_arc_start = der[..der.len() - 1].iter().rposition(|b| b & 0x80 == 0)? + 1;
+        // Shorter than this OID, so it fits wherever this OID does.
+        Some(ObjectIdentifier::from_valid_der(&der[..last_arc_start]))
+    }
+
+    /// Returns a new OID consisting of this OID with `arc` added to the end.
+    #[cfg(feature = "alloc")]
+    pub fn with_child(&self, arc: u128) -> ObjectIdentifier {
+        let mut der_data = self.as_der().to_vec();
+        _push_base128_int(&mut der_data, arc);
+        ObjectIdentifier::from_valid_der(&der_data)
+    }
+
+    /// Returns a new OID consisting of this OID with the arcs of `relative`
+    /// added to the end.
+    #[cfg(feature = "alloc")]
+    pub fn join(&self, relative: &RelativeOid) -> ObjectIdentifier {
+        let mut der_data = self.as_der().to_vec();
+        der_data.extend_from_slice(relative.as_der());
+        ObjectIdentifier::from_valid_der(&der_data)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl str::FromStr for ObjectIdentifier {
+    type Err = ParseOidError;
+
+    /// Parses an OID from a dotted string, e.g. `"1.2.840.113549"`.
+    fn from_str(oid: &str) -> Result<ObjectIdentifier, ParseOidError> {
+        let s = oid.as_bytes();
+        let (root, mut pos) = parse_root_arcs(s)?;
+        let mut der_data = Vec::new();
+        _push_base128_int(&mut der_data, root);
+        while pos < s.len() {
+            let (arc, next) = parse_arc(s, pos)?;
+            _push_base128_int(&mut der_data, arc);
+            pos = next;
+        }
+        Ok(ObjectIdentifier::from_valid_der(&der_data))
+    }
+}
+
+impl PartialOrd for ObjectIdentifier {
+    fn partial_cmp(&self, other: &ObjectIdentifier) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ObjectIdentifier {
+    /// Compares OIDs arc by arc, so that `1.2.3` < `1.2.3.4` < `1.2.10`.
+    fn cmp(&self, other: &ObjectIdentifier) -> cmp::Ordering {
+        self.arcs().cmp(other.arcs())
     }
 }
 
 impl fmt::Display for ObjectIdentifier {
     /// Converts an `ObjectIdentifier` to a dotted string, e.g.
     /// "1.2.840.113549".
+    ///
+    /// With the `oid-registry` feature, the alternate form (`{:#}`) prefixes
+    /// well-known OIDs with their name, e.g.
+    /// "sha256WithRSAEncryption (1.2.840.113549.1.1.11)".
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut cursor = self.as_der().iter().copied();
+        #[cfg(feature = "oid-registry")]
+        {
+            if f.alternate() {
+                if let Some(name) = crate::oid_registry::well_known_name(self) {
+                    write!(f, "{} (", name)?;
+                    self.fmt_dotted(f)?;
+                    return write!(f, ")");
+                }
+            }
+        }
 
-        let first = _read_base128_int(&mut cursor).unwrap();
-        if first < 80 {
-            write!(f, "{}.{}", first / 40, first % 40)?;
-        } else {
-            write!(f, "2.{}", first - 80)?;
+        self.fmt_dotted(f)
+    }
+}
+
+/// Represents an ASN.1 `RELATIVE-OID`: a series of arcs identifying an
+/// object relative to some `ObjectIdentifier` known from context. The full
+/// `ObjectIdentifier` can be recovered with `ObjectIdentifier::join`.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct RelativeOid {
+    // Store the RELATIVE-OID as DER encoded.
+    der_encoded: OidStorage,
+}
+
+impl RelativeOid {
+    /// Parses a `RelativeOid` from a dotted string, e.g. `"8.571.1"`.
+    #[cfg(feature = "alloc")]
+    pub fn from_string(oid: &str) -> Option<RelativeOid> {
+        oid.parse().ok()
+    }
+
+    /// Creates a `RelativeOid` from its DER representation. This only
+    /// allocates if it is too long to be stored inline, and fails with
+    /// [`ParseErrorKind::OidTooLong`] in that case without the `alloc`
+    /// feature.
+    pub fn from_der(data: &[u8]) -> ParseResult<RelativeOid> {
+        validate_base128_ints(data)?;
+        Ok(RelativeOid {
+            der_encoded: OidStorage::new(data)
+                .ok_or_else(|| ParseError::new(ParseErrorKind::OidTooLong))?,
+        })
+    }
+
+    pub(crate) fn as_der(&self) -> &[u8] {
+        self.der_encoded.as_slice()
+    }
+
+    /// Returns an iterator over the arcs of this `RelativeOid`.
+    pub fn arcs(&self) -> Arcs<'_> {
+        Arcs {
+            first: None,
+            second: None,
+            rest: self.as_der().iter().copied(),
         }
+    }
+}
 
-        while cursor.len() > 0 {
-            let digit = _read_base128_int(&mut cursor).unwrap();
-            write!(f, ".{}", digit)?;
+#[cfg(feature = "alloc")]
+impl str::FromStr for RelativeOid {
+    type Err = ParseOidError;
+
+    /// Parses a `RelativeOid` from a dotted string, e.g. `"8.571.1"`.
+    fn from_str(oid: &str) -> Result<RelativeOid, ParseOidError> {
+        let s = oid.as_bytes();
+        let mut der_data = Vec::new();
+        let mut pos = 0;
+        loop {
+            let (arc, next) = parse_arc(s, pos)?;
+            _push_base128_int(&mut der_data, arc);
+            pos = next;
+            if pos == s.len() {
+                break;
+            }
         }
+        Ok(RelativeOid {
+            der_encoded: OidStorage::new(&der_data).unwrap(),
+        })
+    }
+}
 
+impl fmt::Display for RelativeOid {
+    /// Converts a `RelativeOid` to a dotted string, e.g. "8.571.1".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut arcs = self.arcs();
+        write!(f, "{}", arcs.next().unwrap())?;
+        for arc in arcs {
+            write!(f, ".{}", arc)?;
+        }
         Ok(())
     }
 }
 
+/// An iterator over the arcs of an `ObjectIdentifier` or `RelativeOid`,
+/// returned by their `arcs` methods.
+#[derive(Debug, Clone)]
+pub struct Arcs<'a> {
+    first: Option<u128>,
+    second: Option<u128>,
+    rest: iter::Copied<slice::Iter<'a, u8>>,
+}
+
+impl Iterator for Arcs<'_> {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        if let Some(arc) = self.first.take() {
+            return Some(arc);
+        }
+        if let Some(arc) = self.second.take() {
+            return Some(arc);
+        }
+        if self.rest.len() == 0 {
+            return None;
+        }
+        Some(_read_base128_int(&mut self.rest).unwrap())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{ObjectIdentifier, ParseError, ParseErrorKind};
+    use crate::{ObjectIdentifier, ParseError, ParseErrorKind, ParseOidError, RelativeOid};
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     #[test]
     fn test_object_identifier_from_string() {
@@ -170,7 +616,7 @@ mod tests {
             ".2.5",
             "2..5",
             "2.5.",
-            "1.3.6.1.4.1.1248.1.1.2.1.3.21.69.112.115.111.110.32.83.116.121.108.117.115.32.80.114.111.32.52.57.48.48.123.124412.31.213321.123.110.32.83.116.121.108.117.115.32.80.114.111.32.52.57.48.48.123.124412.31.213321.123",
+            "2.25.340282366920938463463374607431768211456",
         ] {
             assert_eq!(ObjectIdentifier::from_string(val), None);
         }
@@ -182,6 +628,8 @@ mod tests {
             "1.2.3.4",
             "1.2.840.133549.1.1.5",
             "2.100.3",
+            "2.25.329800735698586629295641978511506172918",
+            "1.3.6.1.4.1.1248.1.1.2.1.3.21.69.112.115.111.110.32.83.116.121.108.117.115.32.80.114.111.32.52.57.48.48.123.124412.31.213321.123.110.32.83.116.121.108.117.115.32.80.114.111.32.52.57.48.48.123.124412.31.213321.123",
         ] {
             assert!(ObjectIdentifier::from_string(val).is_some());
         }
@@ -189,7 +637,17 @@ mod tests {
 
     #[test]
     fn test_from_der() {
-        assert_eq!(ObjectIdentifier::from_der(b"\x06\x40\x2b\x06\x01\x04\x01\x89\x60\x01\x01\x02\x01\x03\x15\x45\x70\x73\x6f\x6e\x20\x53\x74\x79\x6c\x75\x73\x20\x50\x72\x6f\x20\x34\x39\x30\x30\x7b\x87\xcb\x7c\x1f\x8d\x82\x49\x7b\x2b\x06\x01\x04\x01\x89\x60\x01\x01\x02\x01\x03\x15\x45\x70\x73\x6f\x6e\x20"), Err(ParseError::new(ParseErrorKind::OidTooLong)));
+        let long = ObjectIdentifier::from_der(b"\x06\x40\x2b\x06\x01\x04\x01\x89\x60\x01\x01\x02\x01\x03\x15\x45\x70\x73\x6f\x6e\x20\x53\x74\x79\x6c\x75\x73\x20\x50\x72\x6f\x20\x34\x39\x30\x30\x7b\x87\xcb\x7c\x1f\x8d\x82\x49\x7b\x2b\x06\x01\x04\x01\x89\x60\x01\x01\x02\x01\x03\x15\x45\x70\x73\x6f\x6e\x20").unwrap();
+        assert_eq!(long.as_der().len(), 64);
+        assert_eq!(ObjectIdentifier::from_der(long.as_der()), Ok(long));
+
+        // Arcs which don't fit in a u128.
+        assert_eq!(
+            ObjectIdentifier::from_der(
+                b"\x69\x84\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x00"
+            ),
+            Err(ParseError::new(ParseErrorKind::InvalidValue))
+        );
     }
 
     #[test]
@@ -202,6 +660,9 @@ mod tests {
             "1.2.3.4",
             "1.2.840.133549.1.1.5",
             "2.100.3",
+            "2.25.329800735698586629295641978511506172918",
+            "2.25.340282366920938463463374607431768211455",
+            "1.3.6.1.4.1.1248.1.1.2.1.3.21.69.112.115.111.110.32.83.116.121.108.117.115.32.80.114.111.32.52.57.48.48.123.124412.31.213321.123.110.32.83.116.121.108.117.115.32.80.114.111.32.52.57.48.48.123.124412.31.213321.123",
         ] {
             assert_eq!(
                 &ObjectIdentifier::from_string(val).unwrap().to_string(),
@@ -209,4 +670,174 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_from_str() {
+        for (val, expected) in &[
+            ("", ParseOidError::InvalidArc),
+            ("1", ParseOidError::TooFewArcs),
+            ("1.", ParseOidError::InvalidArc),
+            ("1.2.", ParseOidError::InvalidArc),
+            ("1..2", ParseOidError::InvalidArc),
+            ("1.+2", ParseOidError::InvalidArc),
+            ("3.1", ParseOidError::InvalidRootArcs),
+            ("1.40", ParseOidError::InvalidRootArcs),
+            (
+                "2.25.340282366920938463463374607431768211456",
+                ParseOidError::ArcTooLarge,
+            ),
+            (
+                "2.340282366920938463463374607431768211455",
+                ParseOidError::ArcTooLarge,
+            ),
+        ] {
+            assert_eq!(val.parse::<ObjectIdentifier>(), Err(*expected));
+        }
+
+        assert_eq!(
+            "1.2.840.113549".parse::<ObjectIdentifier>(),
+            Ok(ObjectIdentifier::from_string("1.2.840.113549").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_from_dotted_str() {
+        const OID: ObjectIdentifier = ObjectIdentifier::from_dotted_str("1.2.840.113549");
+        assert_eq!(
+            OID,
+            ObjectIdentifier::from_string("1.2.840.113549").unwrap()
+        );
+        for val in &[
+            "0.4",
+            "2.100.3",
+            "2.25.329800735698586629295641978511506172918",
+        ] {
+            assert_eq!(
+                ObjectIdentifier::from_dotted_str(val),
+                ObjectIdentifier::from_string(val).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_dotted_str_invalid() {
+        ObjectIdentifier::from_dotted_str("1.2.a");
+    }
+
+    #[test]
+    fn test_arcs() {
+        for (val, arcs) in &[
+            ("0.4", vec![0, 4]),
+            ("1.2.840.113549", vec![1, 2, 840, 113549]),
+            ("2.100.3", vec![2, 100, 3]),
+            (
+                "2.25.329800735698586629295641978511506172918",
+                vec![2, 25, 329800735698586629295641978511506172918],
+            ),
+        ] {
+            let oid = ObjectIdentifier::from_string(val).unwrap();
+            assert_eq!(&oid.arcs().collect::<Vec<_>>(), arcs);
+        }
+    }
+
+    #[test]
+    fn test_hierarchy() {
+        let microsoft = ObjectIdentifier::from_string("1.3.6.1.4.1.311").unwrap();
+        let child = ObjectIdentifier::from_string("1.3.6.1.4.1.311.20").unwrap();
+        let grandchild = ObjectIdentifier::from_string("1.3.6.1.4.1.311.20.2").unwrap();
+        let other = ObjectIdentifier::from_string("1.3.6.1.4.1.3110").unwrap();
+
+        assert!(microsoft.starts_with(&microsoft));
+        assert!(child.starts_with(&microsoft));
+        assert!(grandchild.starts_with(&microsoft));
+        assert!(!other.starts_with(&microsoft));
+        assert!(!microsoft.starts_with(&child));
+
+        assert!(child.is_child_of(&microsoft));
+        assert!(!grandchild.is_child_of(&microsoft));
+        assert!(!microsoft.is_child_of(&microsoft));
+        assert!(!other.is_child_of(&microsoft));
+
+        assert_eq!(grandchild.parent(), Some(child.clone()));
+        assert_eq!(child.parent(), Some(microsoft.clone()));
+        assert_eq!(ObjectIdentifier::from_string("2.5").unwrap().parent(), None);
+        assert_eq!(
+            ObjectIdentifier::from_string("2.999.1").unwrap().parent(),
+            ObjectIdentifier::from_string("2.999")
+        );
+
+        assert_eq!(microsoft.with_child(20), child);
+        assert_eq!(child.with_child(2), grandchild);
+        assert_eq!(grandchild.arcs().last(), Some(2));
+    }
+
+    #[test]
+    fn test_ord() {
+        let mut oids = [
+            "1.2.10",
+            "1.2.3.4",
+            "2.5",
+            "1.2.3",
+            "0.9",
+            "2.100",
+            "1.2.840.113549",
+        ]
+        .iter()
+        .map(|s| ObjectIdentifier::from_string(s).unwrap())
+        .collect::<Vec<_>>();
+        oids.sort();
+        assert_eq!(
+            oids.iter().map(|o| o.to_string()).collect::<Vec<_>>(),
+            [
+                "0.9",
+                "1.2.3",
+                "1.2.3.4",
+                "1.2.10",
+                "1.2.840.113549",
+                "2.5",
+                "2.100"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_relative_oid() {
+        for (val, expected) in &[
+            ("", ParseOidError::InvalidArc),
+            ("1.", ParseOidError::InvalidArc),
+            (".1", ParseOidError::InvalidArc),
+            ("1.a", ParseOidError::InvalidArc),
+            (
+                "340282366920938463463374607431768211456",
+                ParseOidError::ArcTooLarge,
+            ),
+        ] {
+            assert_eq!(val.parse::<RelativeOid>(), Err(*expected));
+        }
+
+        for val in &["0", "8.571.1", "329800735698586629295641978511506172918.5"] {
+            assert_eq!(&RelativeOid::from_string(val).unwrap().to_string(), val);
+        }
+        assert_eq!(
+            RelativeOid::from_string("8.571.1")
+                .unwrap()
+                .arcs()
+                .collect::<Vec<_>>(),
+            vec![8, 571, 1]
+        );
+    }
+
+    #[test]
+    fn test_join() {
+        let base = ObjectIdentifier::from_string("0.0.8.2250").unwrap();
+        assert_eq!(
+            base.join(&RelativeOid::from_string("0.4").unwrap()),
+            ObjectIdentifier::from_string("0.0.8.2250.0.4").unwrap()
+        );
+        assert_eq!(
+            base.join(&RelativeOid::from_string("7").unwrap()),
+            base.with_child(7)
+        );
+    }
 }
@@ -0,0 +1,745 @@
+use crate::ObjectIdentifier;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// An `ObjectIdentifier` with a name and a human readable description, as
+/// stored in an [`OidRegistry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OidEntry {
+    oid: ObjectIdentifier,
+    name: Cow<'static, str>,
+    description: Cow<'static, str>,
+}
+
+impl OidEntry {
+    /// Creates an entry for `oid`. Names are conventionally the identifier
+    /// used in the ASN.1 module defining the OID, e.g.
+    /// `"sha256WithRSAEncryption"`.
+    pub fn new(
+        oid: ObjectIdentifier,
+        name: impl Into<Cow<'static, str>>,
+        description: impl Into<Cow<'static, str>>,
+    ) -> OidEntry {
+        OidEntry {
+            oid,
+            name: name.into(),
+            description: description.into(),
+        }
+    }
+
+    const fn well_known(oid: &str, name: &'static str, description: &'static str) -> OidEntry {
+        OidEntry {
+            oid: ObjectIdentifier::from_dotted_str(oid),
+            name: Cow::Borrowed(name),
+            description: Cow::Borrowed(description),
+        }
+    }
+
+    /// The `ObjectIdentifier` this entry describes.
+    pub fn oid(&self) -> &ObjectIdentifier {
+        &self.oid
+    }
+
+    /// The entry's name, e.g. `"sha256WithRSAEncryption"`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The entry's description, e.g. `"SHA-256 with RSA encryption"`.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// A table of named `ObjectIdentifier`s, allowing them to be looked up by
+/// OID or by name.
+///
+/// A registry always contains a built-in table of well-known OIDs (from
+/// PKIX, PKCS #1, #5, #7, #9, and #12, X9.62, NIST, X.520, and Microsoft's
+/// and Netscape's arcs), and may be extended with application-specific
+/// entries using [`OidRegistry::register`]. Registered entries take
+/// precedence over the well-known ones, and later registrations over earlier
+/// ones.
+///
+/// The well-known names are also used when formatting an `ObjectIdentifier`
+/// with `{:#}`.
+#[derive(Debug, Clone, Default)]
+pub struct OidRegistry {
+    entries: Vec<OidEntry>,
+}
+
+impl OidRegistry {
+    /// Creates a registry containing only the well-known entries.
+    pub fn new() -> OidRegistry {
+        OidRegistry::default()
+    }
+
+    /// Returns the built-in table of well-known entries.
+    pub fn well_known() -> &'static [OidEntry] {
+        WELL_KNOWN
+    }
+
+    /// Adds an application-specific entry.
+    pub fn register(&mut self, entry: OidEntry) {
+        self.entries.push(entry);
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &OidEntry> {
+        self.entries.iter().rev().chain(WELL_KNOWN.iter())
+    }
+
+    /// Returns the entry for `oid`, if there is one.
+    pub fn get(&self, oid: &ObjectIdentifier) -> Option<&OidEntry> {
+        self.entries().find(|e| e.oid == *oid)
+    }
+
+    /// Returns the entry named `name`, if there is one.
+    pub fn get_by_name(&self, name: &str) -> Option<&OidEntry> {
+        self.entries().find(|e| e.name == name)
+    }
+
+    /// Returns a value which formats `oid` along with its name, e.g.
+    /// `sha256WithRSAEncryption (1.2.840.113549.1.1.11)`, using both `{}` and
+    /// `{:?}`. OIDs without an entry are formatted as just their dotted
+    /// string.
+    pub fn display<'a>(&'a self, oid: &'a ObjectIdentifier) -> OidDisplay<'a> {
+        OidDisplay {
+            oid,
+            entry: self.get(oid),
+        }
+    }
+}
+
+/// Formats an `ObjectIdentifier` along with its name. Returned by
+/// [`OidRegistry::display`].
+pub struct OidDisplay<'a> {
+    oid: &'a ObjectIdentifier,
+    entry: Option<&'a OidEntry>,
+}
+
+impl fmt::Display for OidDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.entry {
+            Some(entry) => write!(f, "{} ({})", entry.name, self.oid),
+            None => write!(f, "{}", self.oid),
+        }
+    }
+}
+
+impl fmt::Debug for OidDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+pub(crate) fn well_known_name(oid: &ObjectIdentifier) -> Option<&'static str> {
+    WELL_KNOWN
+        .iter()
+        .find(|e| e.oid == *oid)
+        .map(|e| e.name.as_ref())
+}
+
+static WELL_KNOWN: &[OidEntry] = &[
+    // PKCS #1
+    OidEntry::well_known("1.2.840.113549.1.1.1", "rsaEncryption", "RSA encryption"),
+    OidEntry::well_known(
+        "1.2.840.113549.1.1.2",
+        "md2WithRSAEncryption",
+        "MD2 with RSA encryption",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.1.4",
+        "md5WithRSAEncryption",
+        "MD5 with RSA encryption",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.1.5",
+        "sha1WithRSAEncryption",
+        "SHA-1 with RSA encryption",
+    ),
+    OidEntry::well_known("1.2.840.113549.1.1.7", "id-RSAES-OAEP", "RSAES-OAEP"),
+    OidEntry::well_known("1.2.840.113549.1.1.8", "id-mgf1", "MGF1"),
+    OidEntry::well_known(
+        "1.2.840.113549.1.1.9",
+        "id-pSpecified",
+        "RSAES-OAEP explicitly specified label",
+    ),
+    OidEntry::well_known("1.2.840.113549.1.1.10", "id-RSASSA-PSS", "RSASSA-PSS"),
+    OidEntry::well_known(
+        "1.2.840.113549.1.1.11",
+        "sha256WithRSAEncryption",
+        "SHA-256 with RSA encryption",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.1.12",
+        "sha384WithRSAEncryption",
+        "SHA-384 with RSA encryption",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.1.13",
+        "sha512WithRSAEncryption",
+        "SHA-512 with RSA encryption",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.1.14",
+        "sha224WithRSAEncryption",
+        "SHA-224 with RSA encryption",
+    ),
+    // PKCS #5
+    OidEntry::well_known("1.2.840.113549.1.5.12", "id-PBKDF2", "PBKDF2"),
+    OidEntry::well_known("1.2.840.113549.1.5.13", "id-PBES2", "PBES2"),
+    OidEntry::well_known("1.2.840.113549.2.5", "md5", "MD5"),
+    OidEntry::well_known("1.2.840.113549.2.7", "hmacWithSHA1", "HMAC with SHA-1"),
+    OidEntry::well_known("1.2.840.113549.2.9", "hmacWithSHA256", "HMAC with SHA-256"),
+    OidEntry::well_known("1.2.840.113549.2.10", "hmacWithSHA384", "HMAC with SHA-384"),
+    OidEntry::well_known("1.2.840.113549.2.11", "hmacWithSHA512", "HMAC with SHA-512"),
+    // PKCS #7
+    OidEntry::well_known("1.2.840.113549.1.7.1", "data", "PKCS #7 data"),
+    OidEntry::well_known("1.2.840.113549.1.7.2", "signedData", "PKCS #7 signed data"),
+    OidEntry::well_known(
+        "1.2.840.113549.1.7.3",
+        "envelopedData",
+        "PKCS #7 enveloped data",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.7.4",
+        "signedAndEnvelopedData",
+        "PKCS #7 signed and enveloped data",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.7.5",
+        "digestedData",
+        "PKCS #7 digested data",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.7.6",
+        "encryptedData",
+        "PKCS #7 encrypted data",
+    ),
+    // PKCS #9
+    OidEntry::well_known("1.2.840.113549.1.9.1", "emailAddress", "Email address"),
+    OidEntry::well_known(
+        "1.2.840.113549.1.9.2",
+        "unstructuredName",
+        "Unstructured name",
+    ),
+    OidEntry::well_known("1.2.840.113549.1.9.3", "contentType", "Content type"),
+    OidEntry::well_known("1.2.840.113549.1.9.4", "messageDigest", "Message digest"),
+    OidEntry::well_known("1.2.840.113549.1.9.5", "signingTime", "Signing time"),
+    OidEntry::well_known(
+        "1.2.840.113549.1.9.6",
+        "countersignature",
+        "Countersignature",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.9.7",
+        "challengePassword",
+        "Challenge password",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.9.8",
+        "unstructuredAddress",
+        "Unstructured address",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.9.14",
+        "extensionRequest",
+        "Extension request",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.9.15",
+        "smimeCapabilities",
+        "S/MIME capabilities",
+    ),
+    OidEntry::well_known("1.2.840.113549.1.9.20", "friendlyName", "Friendly name"),
+    OidEntry::well_known("1.2.840.113549.1.9.21", "localKeyId", "Local key ID"),
+    OidEntry::well_known(
+        "1.2.840.113549.1.9.22.1",
+        "x509Certificate",
+        "X.509 certificate",
+    ),
+    // PKCS #12
+    OidEntry::well_known(
+        "1.2.840.113549.1.12.1.3",
+        "pbeWithSHAAnd3-KeyTripleDES-CBC",
+        "PBE with SHA-1 and 3-key Triple DES in CBC mode",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.12.1.6",
+        "pbeWithSHAAnd40BitRC2-CBC",
+        "PBE with SHA-1 and 40-bit RC2 in CBC mode",
+    ),
+    OidEntry::well_known("1.2.840.113549.1.12.10.1.1", "keyBag", "PKCS #12 key bag"),
+    OidEntry::well_known(
+        "1.2.840.113549.1.12.10.1.2",
+        "pkcs8ShroudedKeyBag",
+        "PKCS #12 shrouded PKCS #8 key bag",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.12.10.1.3",
+        "certBag",
+        "PKCS #12 certificate bag",
+    ),
+    OidEntry::well_known("1.2.840.113549.1.12.10.1.4", "crlBag", "PKCS #12 CRL bag"),
+    OidEntry::well_known(
+        "1.2.840.113549.1.12.10.1.5",
+        "secretBag",
+        "PKCS #12 secret bag",
+    ),
+    OidEntry::well_known(
+        "1.2.840.113549.1.12.10.1.6",
+        "safeContentsBag",
+        "PKCS #12 safe contents bag",
+    ),
+    // X9.57 and X9.42
+    OidEntry::well_known("1.2.840.10040.4.1", "id-dsa", "DSA"),
+    OidEntry::well_known("1.2.840.10040.4.3", "id-dsa-with-sha1", "DSA with SHA-1"),
+    OidEntry::well_known("1.2.840.10046.2.1", "dhpublicnumber", "Diffie-Hellman"),
+    // X9.62 and SEC 2
+    OidEntry::well_known(
+        "1.2.840.10045.2.1",
+        "id-ecPublicKey",
+        "Elliptic curve public key",
+    ),
+    OidEntry::well_known("1.2.840.10045.3.1.7", "prime256v1", "NIST P-256 curve"),
+    OidEntry::well_known("1.3.132.0.10", "secp256k1", "secp256k1 curve"),
+    OidEntry::well_known("1.3.132.0.34", "secp384r1", "NIST P-384 curve"),
+    OidEntry::well_known("1.3.132.0.35", "secp521r1", "NIST P-521 curve"),
+    OidEntry::well_known("1.2.840.10045.4.1", "ecdsa-with-SHA1", "ECDSA with SHA-1"),
+    OidEntry::well_known(
+        "1.2.840.10045.4.3.1",
+        "ecdsa-with-SHA224",
+        "ECDSA with SHA-224",
+    ),
+    OidEntry::well_known(
+        "1.2.840.10045.4.3.2",
+        "ecdsa-with-SHA256",
+        "ECDSA with SHA-256",
+    ),
+    OidEntry::well_known(
+        "1.2.840.10045.4.3.3",
+        "ecdsa-with-SHA384",
+        "ECDSA with SHA-384",
+    ),
+    OidEntry::well_known(
+        "1.2.840.10045.4.3.4",
+        "ecdsa-with-SHA512",
+        "ECDSA with SHA-512",
+    ),
+    // RFC 8410
+    OidEntry::well_known("1.3.101.110", "id-X25519", "X25519"),
+    OidEntry::well_known("1.3.101.111", "id-X448", "X448"),
+    OidEntry::well_known("1.3.101.112", "id-Ed25519", "Ed25519"),
+    OidEntry::well_known("1.3.101.113", "id-Ed448", "Ed448"),
+    // NIST algorithms
+    OidEntry::well_known("1.3.14.3.2.26", "id-sha1", "SHA-1"),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.1.2",
+        "id-aes128-CBC",
+        "AES-128 in CBC mode",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.1.5",
+        "id-aes128-wrap",
+        "AES-128 key wrap",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.1.6",
+        "id-aes128-GCM",
+        "AES-128 in GCM mode",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.1.22",
+        "id-aes192-CBC",
+        "AES-192 in CBC mode",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.1.25",
+        "id-aes192-wrap",
+        "AES-192 key wrap",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.1.26",
+        "id-aes192-GCM",
+        "AES-192 in GCM mode",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.1.42",
+        "id-aes256-CBC",
+        "AES-256 in CBC mode",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.1.45",
+        "id-aes256-wrap",
+        "AES-256 key wrap",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.1.46",
+        "id-aes256-GCM",
+        "AES-256 in GCM mode",
+    ),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.1", "id-sha256", "SHA-256"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.2", "id-sha384", "SHA-384"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.3", "id-sha512", "SHA-512"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.4", "id-sha224", "SHA-224"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.5", "id-sha512-224", "SHA-512/224"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.6", "id-sha512-256", "SHA-512/256"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.7", "id-sha3-224", "SHA3-224"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.8", "id-sha3-256", "SHA3-256"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.9", "id-sha3-384", "SHA3-384"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.10", "id-sha3-512", "SHA3-512"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.11", "id-shake128", "SHAKE128"),
+    OidEntry::well_known("2.16.840.1.101.3.4.2.12", "id-shake256", "SHAKE256"),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.3.1",
+        "id-dsa-with-sha224",
+        "DSA with SHA-224",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.101.3.4.3.2",
+        "id-dsa-with-sha256",
+        "DSA with SHA-256",
+    ),
+    // X.520 attribute types
+    OidEntry::well_known("2.5.4.3", "commonName", "Common name"),
+    OidEntry::well_known("2.5.4.4", "surname", "Surname"),
+    OidEntry::well_known("2.5.4.5", "serialNumber", "Serial number"),
+    OidEntry::well_known("2.5.4.6", "countryName", "Country name"),
+    OidEntry::well_known("2.5.4.7", "localityName", "Locality name"),
+    OidEntry::well_known("2.5.4.8", "stateOrProvinceName", "State or province name"),
+    OidEntry::well_known("2.5.4.9", "streetAddress", "Street address"),
+    OidEntry::well_known("2.5.4.10", "organizationName", "Organization name"),
+    OidEntry::well_known(
+        "2.5.4.11",
+        "organizationalUnitName",
+        "Organizational unit name",
+    ),
+    OidEntry::well_known("2.5.4.12", "title", "Title"),
+    OidEntry::well_known("2.5.4.15", "businessCategory", "Business category"),
+    OidEntry::well_known("2.5.4.17", "postalCode", "Postal code"),
+    OidEntry::well_known("2.5.4.41", "name", "Name"),
+    OidEntry::well_known("2.5.4.42", "givenName", "Given name"),
+    OidEntry::well_known("2.5.4.43", "initials", "Initials"),
+    OidEntry::well_known("2.5.4.44", "generationQualifier", "Generation qualifier"),
+    OidEntry::well_known("2.5.4.46", "dnQualifier", "Distinguished name qualifier"),
+    OidEntry::well_known("2.5.4.65", "pseudonym", "Pseudonym"),
+    OidEntry::well_known(
+        "2.5.4.97",
+        "organizationIdentifier",
+        "Organization identifier",
+    ),
+    OidEntry::well_known("0.9.2342.19200300.100.1.1", "userId", "User ID"),
+    OidEntry::well_known(
+        "0.9.2342.19200300.100.1.25",
+        "domainComponent",
+        "Domain component",
+    ),
+    // X.509 certificate extensions
+    OidEntry::well_known(
+        "2.5.29.14",
+        "subjectKeyIdentifier",
+        "Subject key identifier",
+    ),
+    OidEntry::well_known("2.5.29.15", "keyUsage", "Key usage"),
+    OidEntry::well_known("2.5.29.17", "subjectAltName", "Subject alternative name"),
+    OidEntry::well_known("2.5.29.18", "issuerAltName", "Issuer alternative name"),
+    OidEntry::well_known("2.5.29.19", "basicConstraints", "Basic constraints"),
+    OidEntry::well_known("2.5.29.20", "cRLNumber", "CRL number"),
+    OidEntry::well_known("2.5.29.21", "cRLReason", "CRL reason code"),
+    OidEntry::well_known("2.5.29.24", "invalidityDate", "Invalidity date"),
+    OidEntry::well_known("2.5.29.27", "deltaCRLIndicator", "Delta CRL indicator"),
+    OidEntry::well_known(
+        "2.5.29.28",
+        "issuingDistributionPoint",
+        "Issuing distribution point",
+    ),
+    OidEntry::well_known("2.5.29.29", "certificateIssuer", "Certificate issuer"),
+    OidEntry::well_known("2.5.29.30", "nameConstraints", "Name constraints"),
+    OidEntry::well_known(
+        "2.5.29.31",
+        "cRLDistributionPoints",
+        "CRL distribution points",
+    ),
+    OidEntry::well_known("2.5.29.32", "certificatePolicies", "Certificate policies"),
+    OidEntry::well_known("2.5.29.32.0", "anyPolicy", "Any policy"),
+    OidEntry::well_known("2.5.29.33", "policyMappings", "Policy mappings"),
+    OidEntry::well_known(
+        "2.5.29.35",
+        "authorityKeyIdentifier",
+        "Authority key identifier",
+    ),
+    OidEntry::well_known("2.5.29.36", "policyConstraints", "Policy constraints"),
+    OidEntry::well_known("2.5.29.37", "extKeyUsage", "Extended key usage"),
+    OidEntry::well_known(
+        "2.5.29.37.0",
+        "anyExtendedKeyUsage",
+        "Any extended key usage",
+    ),
+    OidEntry::well_known("2.5.29.46", "freshestCRL", "Freshest CRL"),
+    OidEntry::well_known("2.5.29.54", "inhibitAnyPolicy", "Inhibit any policy"),
+    // PKIX
+    OidEntry::well_known(
+        "1.3.6.1.5.5.7.1.1",
+        "authorityInfoAccess",
+        "Authority information access",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.5.5.7.1.11",
+        "subjectInfoAccess",
+        "Subject information access",
+    ),
+    OidEntry::well_known("1.3.6.1.5.5.7.1.24", "id-pe-tlsfeature", "TLS feature"),
+    OidEntry::well_known("1.3.6.1.5.5.7.2.1", "id-qt-cps", "CPS pointer qualifier"),
+    OidEntry::well_known(
+        "1.3.6.1.5.5.7.2.2",
+        "id-qt-unotice",
+        "User notice qualifier",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.5.5.7.3.1",
+        "id-kp-serverAuth",
+        "TLS web server authentication",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.5.5.7.3.2",
+        "id-kp-clientAuth",
+        "TLS web client authentication",
+    ),
+    OidEntry::well_known("1.3.6.1.5.5.7.3.3", "id-kp-codeSigning", "Code signing"),
+    OidEntry::well_known(
+        "1.3.6.1.5.5.7.3.4",
+        "id-kp-emailProtection",
+        "Email protection",
+    ),
+    OidEntry::well_known("1.3.6.1.5.5.7.3.8", "id-kp-timeStamping", "Time stamping"),
+    OidEntry::well_known("1.3.6.1.5.5.7.3.9", "id-kp-OCSPSigning", "OCSP signing"),
+    OidEntry::well_known("1.3.6.1.5.5.7.48.1", "id-ad-ocsp", "OCSP"),
+    OidEntry::well_known(
+        "1.3.6.1.5.5.7.48.1.1",
+        "id-pkix-ocsp-basic",
+        "Basic OCSP response",
+    ),
+    OidEntry::well_known("1.3.6.1.5.5.7.48.1.2", "id-pkix-ocsp-nonce", "OCSP nonce"),
+    OidEntry::well_known(
+        "1.3.6.1.5.5.7.48.1.5",
+        "id-pkix-ocsp-nocheck",
+        "OCSP no check",
+    ),
+    OidEntry::well_known("1.3.6.1.5.5.7.48.2", "id-ad-caIssuers", "CA issuers"),
+    OidEntry::well_known(
+        "1.3.6.1.5.5.7.48.3",
+        "id-ad-timeStamping",
+        "Time stamping authority",
+    ),
+    OidEntry::well_known("1.3.6.1.5.5.7.48.5", "id-ad-caRepository", "CA repository"),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.11129.2.4.2",
+        "signedCertificateTimestampList",
+        "Certificate Transparency SCT list",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.11129.2.4.3",
+        "precertificatePoison",
+        "Certificate Transparency precertificate poison",
+    ),
+    // Microsoft
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.2.1.21",
+        "msCodeInd",
+        "Microsoft individual code signing",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.2.1.22",
+        "msCodeCom",
+        "Microsoft commercial code signing",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.10.3.1",
+        "msCTLSign",
+        "Microsoft trust list signing",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.10.3.3",
+        "msSGC",
+        "Microsoft server gated crypto",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.10.3.4",
+        "msEFS",
+        "Microsoft encrypted file system",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.20.2",
+        "msCertificateTemplateName",
+        "Microsoft certificate template name",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.20.2.2",
+        "msSmartcardLogin",
+        "Microsoft smartcard login",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.20.2.3",
+        "msUPN",
+        "Microsoft user principal name",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.21.1",
+        "msCAVersion",
+        "Microsoft CA version",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.21.7",
+        "msCertificateTemplate",
+        "Microsoft certificate template",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.21.10",
+        "msApplicationCertPolicies",
+        "Microsoft application policies",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.60.2.1.1",
+        "jurisdictionLocalityName",
+        "Jurisdiction of incorporation locality name",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.60.2.1.2",
+        "jurisdictionStateOrProvinceName",
+        "Jurisdiction of incorporation state or province name",
+    ),
+    OidEntry::well_known(
+        "1.3.6.1.4.1.311.60.2.1.3",
+        "jurisdictionCountryName",
+        "Jurisdiction of incorporation country name",
+    ),
+    // Netscape
+    OidEntry::well_known(
+        "2.16.840.1.113730.1.1",
+        "nsCertType",
+        "Netscape certificate type",
+    ),
+    OidEntry::well_known("2.16.840.1.113730.1.2", "nsBaseUrl", "Netscape base URL"),
+    OidEntry::well_known(
+        "2.16.840.1.113730.1.3",
+        "nsRevocationUrl",
+        "Netscape revocation URL",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.113730.1.4",
+        "nsCaRevocationUrl",
+        "Netscape CA revocation URL",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.113730.1.7",
+        "nsRenewalUrl",
+        "Netscape renewal URL",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.113730.1.8",
+        "nsCaPolicyUrl",
+        "Netscape CA policy URL",
+    ),
+    OidEntry::well_known(
+        "2.16.840.1.113730.1.12",
+        "nsSslServerName",
+        "Netscape SSL server name",
+    ),
+    OidEntry::well_known("2.16.840.1.113730.1.13", "nsComment", "Netscape comment"),
+    OidEntry::well_known(
+        "2.16.840.1.113730.4.1",
+        "nsSGC",
+        "Netscape server gated crypto",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::WELL_KNOWN;
+    use crate::{ObjectIdentifier, OidEntry, OidRegistry};
+    use alloc::format;
+
+    #[test]
+    fn test_well_known_unique() {
+        for (i, a) in WELL_KNOWN.iter().enumerate() {
+            for b in &WELL_KNOWN[i + 1..] {
+                assert_ne!(a.oid(), b.oid());
+                assert_ne!(a.name(), b.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let registry = OidRegistry::new();
+        let oid = ObjectIdentifier::from_string("1.2.840.113549.1.1.11").unwrap();
+
+        let entry = registry.get(&oid).unwrap();
+        assert_eq!(entry.name(), "sha256WithRSAEncryption");
+        assert_eq!(entry.description(), "SHA-256 with RSA encryption");
+        assert_eq!(
+            registry
+                .get_by_name("sha256WithRSAEncryption")
+                .unwrap()
+                .oid(),
+            &oid
+        );
+
+        assert!(registry.get(&oid.with_child(1)).is_none());
+        assert!(registry.get_by_name("sha256WithRsaEncryption").is_none());
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = OidRegistry::new();
+        let custom = ObjectIdentifier::from_string("1.3.6.1.4.1.55555.1").unwrap();
+        let common_name = ObjectIdentifier::from_string("2.5.4.3").unwrap();
+
+        registry.register(OidEntry::new(custom.clone(), "myAttribute", "My attribute"));
+        registry.register(OidEntry::new(common_name.clone(), "CN", "Common name"));
+
+        assert_eq!(registry.get(&custom).unwrap().name(), "myAttribute");
+        assert_eq!(registry.get_by_name("myAttribute").unwrap().oid(), &custom);
+        assert_eq!(registry.get(&common_name).unwrap().name(), "CN");
+        assert_eq!(
+            registry.get_by_name("commonName").unwrap().oid(),
+            &common_name
+        );
+
+        // Entries don't leak between registries.
+        assert!(OidRegistry::new().get(&custom).is_none());
+    }
+
+    #[test]
+    fn test_display() {
+        let mut registry = OidRegistry::new();
+        let sha256_rsa = ObjectIdentifier::from_string("1.2.840.113549.1.1.11").unwrap();
+        let custom = ObjectIdentifier::from_string("1.3.6.1.4.1.55555.1").unwrap();
+
+        assert_eq!(
+            format!("{}", registry.display(&sha256_rsa)),
+            "sha256WithRSAEncryption (1.2.840.113549.1.1.11)"
+        );
+        assert_eq!(
+            format!("{:?}", registry.display(&sha256_rsa)),
+            "sha256WithRSAEncryption (1.2.840.113549.1.1.11)"
+        );
+        assert_eq!(
+            format!("{}", registry.display(&custom)),
+            "1.3.6.1.4.1.55555.1"
+        );
+        registry.register(OidEntry::new(custom.clone(), "myAttribute", "My attribute"));
+        assert_eq!(
+            format!("{}", registry.display(&custom)),
+            "myAttribute (1.3.6.1.4.1.55555.1)"
+        );
+
+        assert_eq!(
+            format!("{:#}", sha256_rsa),
+            "sha256WithRSAEncryption (1.2.840.113549.1.1.11)"
+        );
+        assert_eq!(format!("{:#}", custom), "1.3.6.1.4.1.55555.1");
+        assert_eq!(format!("{}", sha256_rsa), "1.2.840.113549.1.1.11");
+    }
+}
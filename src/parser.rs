@@ -1,13 +1,14 @@
+use crate::tag::Tag;
 use crate::types::{Asn1Readable, SimpleAsn1Readable, Tlv};
 use core::fmt;
 
 /// ParseError are returned when there is an error parsing the ASN.1 data.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ParseErrorKind {
     /// Something about the value was invalid.
     InvalidValue,
     /// An unexpected tag was encountered.
-    UnexpectedTag { actual: u8 },
+    UnexpectedTag { actual: Tag },
     /// There was not enough data available to complete parsing.
     ShortData,
     /// An internal computation would have overflowed.
@@ -20,7 +21,22 @@ pub enum ParseErrorKind {
     EncodedDefault,
     /// OID value is longer than the maximum size rust-asn1 can store. This is
     /// a limitation of rust-asn1.
+    ///
+    /// `ObjectIdentifier`s may be of any length with the `alloc` feature, so
+    /// this is only returned without it.
     OidTooLong,
+    /// Values were nested more deeply than rust-asn1 allows.
+    ExceedsMaxDepth,
+    /// An element of a SET appeared more than once.
+    DuplicateSetElement,
+    /// A required element of a SET was not present.
+    MissingSetElement,
+    /// A time was encoded with an offset from UTC, rather than in UTC with a
+    /// trailing `Z` as DER requires.
+    TimeZoneOffset,
+    /// An `OBJECT IDENTIFIER` selecting the type of an `ANY DEFINED BY` value
+    /// was not one of the known ones.
+    UnknownDefinedBy,
 }
 
 #[derive(Debug, PartialEq)]
@@ -30,10 +46,24 @@ pub enum ParseLocation {
     Index(usize),
 }
 
+// The locations of the values an error occurred in are kept for up to this
+// many levels of nesting.
+#[cfg(feature = "alloc")]
+const MAX_PARSE_LOCATIONS: usize = 8;
+#[cfg(not(feature = "alloc"))]
+const MAX_PARSE_LOCATIONS: usize = 4;
+
+// So that `ParseResult` stays cheap to return by value, the locations are
+// boxed, and only once there are any. Without `alloc`, fewer are kept, inline.
+#[cfg(feature = "alloc")]
+type ParseLocations = Option<alloc::boxed::Box<[Option<ParseLocation>; MAX_PARSE_LOCATIONS]>>;
+#[cfg(not(feature = "alloc"))]
+type ParseLocations = [Option<ParseLocation>; MAX_PARSE_LOCATIONS];
+
 #[derive(PartialEq)]
 pub struct ParseError {
     kind: ParseErrorKind,
-    parse_locations: [Option<ParseLocation>; 8],
+    parse_locations: ParseLocations,
     parse_depth: u8,
 }
 
@@ -41,20 +71,47 @@ impl ParseError {
     pub fn new(kind: ParseErrorKind) -> ParseError {
         ParseError {
             kind,
-            parse_locations: [None, None, None, None, None, None, None, None],
+            #[cfg(feature = "alloc")]
+            parse_locations: None,
+            #[cfg(not(feature = "alloc"))]
+            parse_locations: [None, None, None, None],
             parse_depth: 0,
         }
     }
 
+    /// The kind of error which occurred.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
     #[doc(hidden)]
     #[must_use]
     pub fn add_location(mut self, loc: ParseLocation) -> Self {
-        if (self.parse_depth as usize) < self.parse_locations.len() {
-            self.parse_locations[self.parse_depth as usize] = Some(loc);
+        let depth = self.parse_depth as usize;
+        if depth < MAX_PARSE_LOCATIONS {
+            #[cfg(feature = "alloc")]
+            let locations = self.parse_locations.get_or_insert_with(|| {
+                alloc::boxed::Box::new([None, None, None, None, None, None, None, None])
+            });
+            #[cfg(not(feature = "alloc"))]
+            let locations = &mut self.parse_locations;
+            locations[depth] = Some(loc);
             self.parse_depth += 1;
         }
         self
     }
+
+    // The locations recorded so far, innermost first.
+    fn locations(&self) -> &[Option<ParseLocation>] {
+        #[cfg(feature = "alloc")]
+        let locations = match self.parse_locations {
+            Some(ref locations) => &locations[..],
+            None => return &[],
+        };
+        #[cfg(not(feature = "alloc"))]
+        let locations = &self.parse_locations[..];
+        &locations[..self.parse_depth as usize]
+    }
 }
 
 #[cfg(feature = "std")]
@@ -85,11 +142,7 @@ impl fmt::Debug for ParseError {
                 SomeFmtOption(None),
                 SomeFmtOption(None),
             ];
-            for (i, location) in self.parse_locations[..self.parse_depth as usize]
-                .iter()
-                .rev()
-                .enumerate()
-            {
+            for (i, location) in self.locations().iter().rev().enumerate() {
                 locations[i] = match location.as_ref().unwrap() {
                     ParseLocation::Field(ref f) => SomeFmtOption(Some(f as &dyn fmt::Debug)),
                     ParseLocation::Index(ref i) => SomeFmtOption(Some(i as &dyn fmt::Debug)),
@@ -119,6 +172,18 @@ impl fmt::Display for ParseError {
                 f,
                 "OBJECT IDENTIFIER was too large to be stored in rust-asn1's buffer"
             ),
+            ParseErrorKind::ExceedsMaxDepth => write!(f, "values were nested too deeply"),
+            ParseErrorKind::DuplicateSetElement => write!(f, "SET element was duplicated"),
+            ParseErrorKind::MissingSetElement => write!(f, "required SET element was missing"),
+            ParseErrorKind::TimeZoneOffset => {
+                write!(f, "time had a UTC offset, rather than being in UTC")
+            }
+            ParseErrorKind::UnknownDefinedBy => {
+                write!(
+                    f,
+                    "OBJECT IDENTIFIER for a DEFINED BY value was not recognized"
+                )
+            }
         }
     }
 }
@@ -169,12 +234,20 @@ impl<'a> Parser<'a> {
         Parser::new(self.data)
     }
 
-    pub(crate) fn peek_u8(&mut self) -> Option<u8> {
-        self.data.get(0).copied()
+    pub(crate) fn peek_tag(&mut self) -> Option<Tag> {
+        let (tag, _) = Tag::from_bytes(self.data).ok()?;
+        Some(tag)
+    }
+
+    #[inline]
+    pub(crate) fn read_tag(&mut self) -> ParseResult<Tag> {
+        let (tag, data) = Tag::from_bytes(self.data)?;
+        self.data = data;
+        Ok(tag)
     }
 
     #[inline]
-    fn read_u8(&mut self) -> ParseResult<u8> {
+    pub(crate) fn read_u8(&mut self) -> ParseResult<u8> {
         if self.data.is_empty() {
             return Err(ParseError::new(ParseErrorKind::ShortData));
         }
@@ -184,7 +257,7 @@ impl<'a> Parser<'a> {
     }
 
     #[inline]
-    fn read_bytes(&mut self, length: usize) -> ParseResult<&'a [u8]> {
+    pub(crate) fn read_bytes(&mut self, length: usize) -> ParseResult<&'a [u8]> {
         if length > self.data.len() {
             return Err(ParseError::new(ParseErrorKind::ShortData));
         }
@@ -207,7 +280,7 @@ impl<'a> Parser<'a> {
         let mut length = 0;
         for _ in 0..num_bytes {
             let b = self.read_u8()?;
-            if length > (usize::max_value() >> 8) {
+            if length > (usize::MAX >> 8) {
                 return Err(ParseError::new(ParseErrorKind::IntegerOverflow));
             }
             length <<= 8;
@@ -228,7 +301,7 @@ impl<'a> Parser<'a> {
     pub(crate) fn read_tlv(&mut self) -> ParseResult<Tlv<'a>> {
         let initial_data = self.data;
 
-        let tag = self.read_u8()?;
+        let tag = self.read_tag()?;
         let length = self.read_length()?;
         let data = self.read_bytes(length)?;
 
@@ -240,6 +313,15 @@ impl<'a> Parser<'a> {
         })
     }
 
+    // Reads all of the remaining elements, returning their encodings.
+    pub(crate) fn read_remaining_elements(&mut self) -> ParseResult<&'a [u8]> {
+        let data = self.data;
+        while !self.is_empty() {
+            self.read_tlv()?;
+        }
+        Ok(data)
+    }
+
     /// Tests whether there is any data remaining in the Parser. Generally
     /// useful when parsing a `SEQUENCE OF`.
     #[inline]
@@ -256,7 +338,7 @@ impl<'a> Parser<'a> {
 
     /// This is an alias for `read_element::<Explicit<T, tag>>` for use when
     /// MSRV is < 1.51.
-    pub fn read_explicit_element<T: Asn1Readable<'a>>(&mut self, tag: u8) -> ParseResult<T> {
+    pub fn read_explicit_element<T: Asn1Readable<'a>>(&mut self, tag: u32) -> ParseResult<T> {
         let expected_tag = crate::explicit_tag(tag);
         let tlv = self.read_tlv()?;
         if tlv.tag != expected_tag {
@@ -271,10 +353,10 @@ impl<'a> Parser<'a> {
     /// when MSRV is <1.51.
     pub fn read_optional_explicit_element<T: Asn1Readable<'a>>(
         &mut self,
-        tag: u8,
+        tag: u32,
     ) -> ParseResult<Option<T>> {
         let expected_tag = crate::explicit_tag(tag);
-        if self.peek_u8() != Some(expected_tag) {
+        if self.peek_tag() != Some(expected_tag) {
             return Ok(None);
         }
         let tlv = self.read_tlv()?;
@@ -283,7 +365,7 @@ impl<'a> Parser<'a> {
 
     /// This is an alias for `read_element::<Implicit<T, tag>>` for use when
     /// MSRV is <1.51.
-    pub fn read_implicit_element<T: SimpleAsn1Readable<'a>>(&mut self, tag: u8) -> ParseResult<T> {
+    pub fn read_implicit_element<T: SimpleAsn1Readable<'a>>(&mut self, tag: u32) -> ParseResult<T> {
         let expected_tag = crate::implicit_tag(tag, T::TAG);
         let tlv = self.read_tlv()?;
         if tlv.tag != expected_tag {
@@ -298,10 +380,10 @@ impl<'a> Parser<'a> {
     /// when MSRV is <1.51.
     pub fn read_optional_implicit_element<T: SimpleAsn1Readable<'a>>(
         &mut self,
-        tag: u8,
+        tag: u32,
     ) -> ParseResult<Option<T>> {
         let expected_tag = crate::implicit_tag(tag, T::TAG);
-        if self.peek_u8() != Some(expected_tag) {
+        if self.peek_tag() != Some(expected_tag) {
             return Ok(None);
         }
         let tlv = self.read_tlv()?;
@@ -314,15 +396,19 @@ mod tests {
     use super::Parser;
     use crate::types::Asn1Readable;
     use crate::{
-        BMPString, BigInt, BigUint, BitString, Choice1, Choice2, Choice3, Enumerated,
-        GeneralizedTime, IA5String, ObjectIdentifier, ParseError, ParseErrorKind, ParseLocation,
-        ParseResult, PrintableString, Sequence, SequenceOf, SetOf, Tlv, UniversalString, UtcTime,
-        Utf8String, VisibleString,
+        BMPString, BigInt, BigUint, BitString, Choice1, Choice2, Choice3, DateTime, Enumerated,
+        GeneralizedTime, IA5String, ObjectIdentifier, OidIri, OwnedBMPString, OwnedBigInt,
+        OwnedBigUint, OwnedBitString, OwnedIA5String, OwnedOctetString, OwnedOidIri,
+        OwnedPrintableString, OwnedRelativeOidIri, OwnedSequence, OwnedSetOf, OwnedTlv,
+        OwnedUniversalString, OwnedVisibleString, ParseError, ParseErrorKind, ParseLocation,
+        ParseResult, PrintableString, Real, RelativeOid, RelativeOidIri, Sequence, SequenceOf,
+        SetOf, Tag, TagClass, Tlv, UniversalString, UtcTime, Utf8String, VisibleString,
     };
     #[cfg(feature = "const-generics")]
     use crate::{Explicit, Implicit};
+    use alloc::string::String;
     use alloc::vec;
-    use chrono::{FixedOffset, TimeZone, Utc};
+    use alloc::vec::Vec;
     use core::fmt;
 
     #[test]
@@ -398,10 +484,12 @@ mod tests {
                 "ASN.1 parsing error: short data",
             ),
             (
-                ParseError::new(ParseErrorKind::UnexpectedTag { actual: 12 })
-                    .add_location(ParseLocation::Index(12))
-                    .add_location(ParseLocation::Field("Abc::123")),
-                "ASN.1 parsing error: unexpected tag (got 12)",
+                ParseError::new(ParseErrorKind::UnexpectedTag {
+                    actual: Tag::primitive(12),
+                })
+                .add_location(ParseLocation::Index(12))
+                .add_location(ParseLocation::Field("Abc::123")),
+                "ASN.1 parsing error: unexpected tag (got [UNIVERSAL 12])",
             ),
         ]
         .iter()
@@ -477,7 +565,7 @@ mod tests {
         assert_parses::<Tlv>(&[
             (
                 Ok(Tlv {
-                    tag: 0x4,
+                    tag: Tag::primitive(0x4),
                     data: b"abc",
                     full_data: b"\x04\x03abc",
                 }),
@@ -492,6 +580,50 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_tlv_high_tag_number() {
+        assert_parses::<Tlv>(&[
+            (
+                Ok(Tlv {
+                    tag: Tag::new(31, TagClass::ContextSpecific, false),
+                    data: b"abc",
+                    full_data: b"\x9f\x1f\x03abc",
+                }),
+                b"\x9f\x1f\x03abc",
+            ),
+            (
+                Ok(Tlv {
+                    tag: Tag::new(0x4321, TagClass::Application, true),
+                    data: b"",
+                    full_data: b"\x7f\x81\x86\x21\x00",
+                }),
+                b"\x7f\x81\x86\x21\x00",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x9f\x05\x00",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x9f\x80\x21\x00",
+            ),
+            (Err(ParseError::new(ParseErrorKind::ShortData)), b"\x9f\x81"),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_owned_tlv() {
+        let tlv = crate::parse_single::<OwnedTlv>(b"\x30\x03\x02\x01\x05").unwrap();
+        assert_eq!(tlv.as_tlv().tag(), Tag::constructed(0x10));
+        assert_eq!(tlv.as_tlv().data(), b"\x02\x01\x05");
+        assert_eq!(tlv.as_tlv().full_data(), b"\x30\x03\x02\x01\x05");
+
+        assert_parses::<OwnedTlv>(&[(
+            Err(ParseError::new(ParseErrorKind::ShortData)),
+            b"\x04\x02\x00",
+        )]);
+    }
+
     #[test]
     fn test_parse_null() {
         assert_parses::<()>(&[
@@ -548,6 +680,21 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_owned_octet_string() {
+        assert_parses::<OwnedOctetString>(&[
+            (Ok(OwnedOctetString::new(vec![])), b"\x04\x00"),
+            (
+                Ok(OwnedOctetString::new(vec![1, 2, 3])),
+                b"\x04\x03\x01\x02\x03",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::ShortData)),
+                b"\x04\x03\x01\x02",
+            ),
+        ]);
+    }
+
     #[test]
     fn test_parse_int_i64() {
         assert_parses::<i64>(&[
@@ -558,13 +705,10 @@ mod tests {
             (Ok(-128), b"\x02\x01\x80"),
             (Ok(-129), b"\x02\x02\xff\x7f"),
             (Ok(-256), b"\x02\x02\xff\x00"),
-            (
-                Ok(core::i64::MAX),
-                b"\x02\x08\x7f\xff\xff\xff\xff\xff\xff\xff",
-            ),
+            (Ok(i64::MAX), b"\x02\x08\x7f\xff\xff\xff\xff\xff\xff\xff"),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x3,
+                    actual: Tag::primitive(0x3),
                 })),
                 b"\x03\x00",
             ),
@@ -597,7 +741,7 @@ mod tests {
     fn parse_int_u64() {
         assert_parses::<u64>(&[
             (
-                Ok(core::u64::MAX),
+                Ok(u64::MAX),
                 b"\x02\x09\x00\xff\xff\xff\xff\xff\xff\xff\xff",
             ),
             (
@@ -621,10 +765,10 @@ mod tests {
             (Ok(-128), b"\x02\x01\x80"),
             (Ok(-129), b"\x02\x02\xff\x7f"),
             (Ok(-256), b"\x02\x02\xff\x00"),
-            (Ok(core::i32::MAX), b"\x02\x04\x7f\xff\xff\xff"),
+            (Ok(i32::MAX), b"\x02\x04\x7f\xff\xff\xff"),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x3,
+                    actual: Tag::primitive(0x3),
                 })),
                 b"\x03\x00",
             ),
@@ -650,6 +794,10 @@ mod tests {
                 Err(ParseError::new(ParseErrorKind::InvalidValue)),
                 b"\x02\x00",
             ),
+            (
+                Err(ParseError::new(ParseErrorKind::IntegerOverflow)),
+                b"\x02\x05\x00\x80\x00\x00\x00",
+            ),
         ])
     }
 
@@ -663,6 +811,10 @@ mod tests {
                 Err(ParseError::new(ParseErrorKind::IntegerOverflow)),
                 b"\x02\x02\x02\x00",
             ),
+            (
+                Err(ParseError::new(ParseErrorKind::IntegerOverflow)),
+                b"\x02\x02\x00\x80",
+            ),
             (
                 Err(ParseError::new(ParseErrorKind::InvalidValue)),
                 b"\x02\x00",
@@ -735,6 +887,191 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_owned_integers() {
+        assert_parses::<OwnedBigUint>(&[
+            (
+                Ok(OwnedBigUint::new(vec![0x00, 0xff]).unwrap()),
+                b"\x02\x02\x00\xff",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x02\x01\x80",
+            ),
+        ]);
+        assert_parses::<OwnedBigInt>(&[
+            (Ok(OwnedBigInt::new(vec![0x80]).unwrap()), b"\x02\x01\x80"),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x02\x02\xff\x80",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_real() {
+        assert_parses::<Real>(&[
+            (
+                Ok(Real::Binary {
+                    mantissa: 0,
+                    exponent: 0,
+                }),
+                b"\x09\x00",
+            ),
+            (
+                Ok(Real::Binary {
+                    mantissa: 1,
+                    exponent: 0,
+                }),
+                b"\x09\x03\x80\x00\x01",
+            ),
+            (
+                Ok(Real::Binary {
+                    mantissa: -3,
+                    exponent: -3,
+                }),
+                b"\x09\x03\xc0\xfd\x03",
+            ),
+            (
+                Ok(Real::Binary {
+                    mantissa: 0x1_0001,
+                    exponent: 300,
+                }),
+                b"\x09\x06\x81\x01\x2c\x01\x00\x01",
+            ),
+            (
+                Ok(Real::Binary {
+                    mantissa: 1,
+                    exponent: i32::MIN,
+                }),
+                b"\x09\x07\x83\x04\x80\x00\x00\x00\x01",
+            ),
+            (
+                Ok(Real::Decimal {
+                    mantissa: -15,
+                    exponent: -1,
+                }),
+                b"\x09\x08\x03-15.E-1",
+            ),
+            (
+                Ok(Real::Decimal {
+                    mantissa: 1,
+                    exponent: 0,
+                }),
+                b"\x09\x06\x031.E+0",
+            ),
+            (Ok(Real::PlusInfinity), b"\x09\x01\x40"),
+            (Ok(Real::MinusInfinity), b"\x09\x01\x41"),
+            (Ok(Real::NotANumber), b"\x09\x01\x42"),
+            (Ok(Real::MinusZero), b"\x09\x01\x43"),
+            // Special values must be alone.
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x02\x40\x00",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x01\x44",
+            ),
+            // Base 8, base 16, and scaling factors aren't allowed in DER.
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x03\x90\x00\x01",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x03\xa0\x00\x01",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x03\x84\x00\x01",
+            ),
+            // Mantissas must be odd, and present.
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x03\x80\x00\x02",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x04\x80\x00\x00\x01",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x02\x80\x00",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::IntegerOverflow)),
+                b"\x09\x0a\x80\x00\x80\x00\x00\x00\x00\x00\x00\x01",
+            ),
+            // Exponents must be minimal.
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x04\x81\x00\x01\x01",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x06\x83\x03\x01\x00\x00\x01",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::IntegerOverflow)),
+                b"\x09\x08\x83\x05\x01\x00\x00\x00\x00\x01",
+            ),
+            // Exponents must fit in an `i32`.
+            (
+                Err(ParseError::new(ParseErrorKind::IntegerOverflow)),
+                b"\x09\x08\x83\x05\x00\x80\x00\x00\x00\x01",
+            ),
+            // Only the canonical NR3 form is allowed.
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x04\x011.5",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x06\x0310.E1",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x06\x0301.E1",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x06\x031.E+1",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x05\x031.E0",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x06\x031.E01",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x06\x031.5E1",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x05\x03 1.E1",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x06\x030.E+0",
+            ),
+        ]);
+
+        assert_parses::<f64>(&[
+            (Ok(0.0), b"\x09\x00"),
+            (Ok(-0.375), b"\x09\x03\xc0\xfd\x03"),
+            (Ok(-1.5), b"\x09\x08\x03-15.E-1"),
+            (Ok(f64::INFINITY), b"\x09\x01\x40"),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x09\x03\x80\x00\x02",
+            ),
+        ]);
+    }
+
     #[test]
     fn test_parse_object_identifier() {
         assert_parses::<ObjectIdentifier>(&[
@@ -774,9 +1111,94 @@ mod tests {
                 Err(ParseError::new(ParseErrorKind::InvalidValue)),
                 b"\x06\x02\x2a\x86",
             ),
+            // Arcs must be minimally encoded.
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x06\x03\x2a\x80\x01",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x06\x02\x80\x2a",
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_parse_relative_oid() {
+        assert_parses::<RelativeOid>(&[
+            (
+                Ok(RelativeOid::from_string("8.571.1").unwrap()),
+                b"\x0d\x04\x08\x84\x3b\x01",
+            ),
+            (Ok(RelativeOid::from_string("0").unwrap()), b"\x0d\x01\x00"),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x0d\x00",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x0d\x02\x08\x84",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x0d\x03\x08\x80\x01",
+            ),
         ])
     }
 
+    #[test]
+    fn test_parse_oid_iri() {
+        assert_parses::<OidIri>(&[
+            (
+                Ok(OidIri::new("/ISO/Registration_Authority/19785.CBEFF").unwrap()),
+                b"\x1f\x23\x27/ISO/Registration_Authority/19785.CBEFF",
+            ),
+            (Ok(OidIri::new("/2/100").unwrap()), b"\x1f\x23\x06/2/100"),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x1f\x23\x04ISO/",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x1f\x23\x04/01/",
+            ),
+        ]);
+        assert_parses::<RelativeOidIri>(&[
+            (
+                Ok(RelativeOidIri::new("Registration_Authority/19785.CBEFF").unwrap()),
+                b"\x1f\x24\x22Registration_Authority/19785.CBEFF",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x1f\x24\x04/ISO",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x1f\x24\x05a b/c",
+            ),
+        ]);
+        assert_parses::<OwnedOidIri>(&[
+            (
+                Ok(OwnedOidIri::new(String::from("/2/100")).unwrap()),
+                b"\x1f\x23\x06/2/100",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x1f\x23\x04ISO/",
+            ),
+        ]);
+        assert_parses::<OwnedRelativeOidIri>(&[
+            (
+                Ok(OwnedRelativeOidIri::new(String::from("19785.CBEFF")).unwrap()),
+                b"\x1f\x24\x0b19785.CBEFF",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x1f\x24\x04/ISO",
+            ),
+        ]);
+    }
+
     #[test]
     fn test_parse_bit_string() {
         assert_parses::<BitString<'_>>(&[
@@ -806,6 +1228,20 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_owned_bit_string() {
+        assert_parses::<OwnedBitString>(&[
+            (
+                Ok(OwnedBitString::new(vec![0x81, 0xf0], 4).unwrap()),
+                b"\x03\x03\x04\x81\xf0",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x03\x02\x07\x01",
+            ),
+        ]);
+    }
+
     #[test]
     fn test_parse_printable_string() {
         assert_parses::<PrintableString>(&[
@@ -906,39 +1342,88 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_parse_owned_strings() {
+        assert_parses::<OwnedPrintableString>(&[
+            (
+                Ok(OwnedPrintableString::new(String::from("abc")).unwrap()),
+                b"\x13\x03abc",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x13\x03ab\x00",
+            ),
+        ]);
+        assert_parses::<OwnedIA5String>(&[
+            (
+                Ok(OwnedIA5String::new(String::from("abc")).unwrap()),
+                b"\x16\x03abc",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x16\x03ab\xff",
+            ),
+        ]);
+        assert_parses::<String>(&[
+            (Ok(String::from("abc")), b"\x0c\x03abc"),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x0c\x01\xff",
+            ),
+        ]);
+        assert_parses::<OwnedVisibleString>(&[
+            (
+                Ok(OwnedVisibleString::new(String::from("abc")).unwrap()),
+                b"\x1a\x03abc",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x1a\x03ab\n",
+            ),
+        ]);
+        assert_parses::<OwnedBMPString>(&[
+            (
+                Ok(OwnedBMPString::new(vec![0x00, 0x61]).unwrap()),
+                b"\x1e\x02\x00\x61",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x1e\x01\x00",
+            ),
+        ]);
+        assert_parses::<OwnedUniversalString>(&[
+            (
+                Ok(OwnedUniversalString::new(vec![0x00, 0x00, 0x00, 0x61]).unwrap()),
+                b"\x1c\x04\x00\x00\x00\x61",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x1c\x02\x00\x61",
+            ),
+        ]);
+    }
+
     #[test]
     fn test_parse_utctime() {
         assert_parses::<UtcTime>(&[
             (
-                Ok(UtcTime::new(
-                    FixedOffset::west(7 * 60 * 60)
-                        .ymd(1991, 5, 6)
-                        .and_hms(16, 45, 40)
-                        .into(),
-                )
-                .unwrap()),
+                Ok(UtcTime::new(DateTime::new(1991, 5, 6, 23, 45, 40).unwrap()).unwrap()),
                 b"\x17\x11910506164540-0700",
             ),
             (
-                Ok(UtcTime::new(
-                    FixedOffset::east(7 * 60 * 60 + 30 * 60)
-                        .ymd(1991, 5, 6)
-                        .and_hms(16, 45, 40)
-                        .into(),
-                )
-                .unwrap()),
+                Ok(UtcTime::new(DateTime::new(1991, 5, 6, 9, 15, 40).unwrap()).unwrap()),
                 b"\x17\x11910506164540+0730",
             ),
             (
-                Ok(UtcTime::new(Utc.ymd(1991, 5, 6).and_hms(23, 45, 40)).unwrap()),
+                Ok(UtcTime::new(DateTime::new(1991, 5, 6, 23, 45, 40).unwrap()).unwrap()),
                 b"\x17\x0d910506234540Z",
             ),
             (
-                Ok(UtcTime::new(Utc.ymd(1991, 5, 6).and_hms(23, 45, 0)).unwrap()),
+                Ok(UtcTime::new(DateTime::new(1991, 5, 6, 23, 45, 0).unwrap()).unwrap()),
                 b"\x17\x0b9105062345Z",
             ),
             (
-                Ok(UtcTime::new(Utc.ymd(1951, 5, 6).and_hms(23, 45, 0)).unwrap()),
+                Ok(UtcTime::new(DateTime::new(1951, 5, 6, 23, 45, 0).unwrap()).unwrap()),
                 b"\x17\x0b5105062345Z",
             ),
             (
@@ -1052,25 +1537,17 @@ mod tests {
     fn test_generalizedtime() {
         assert_parses::<GeneralizedTime>(&[
             (
-                Ok(GeneralizedTime::new(Utc.ymd(2010, 1, 2).and_hms(3, 4, 5))),
+                Ok(GeneralizedTime::new(
+                    DateTime::new(2010, 1, 2, 3, 4, 5).unwrap(),
+                )),
                 b"\x18\x0f20100102030405Z",
             ),
             (
-                Ok(GeneralizedTime::new(
-                    FixedOffset::east(6 * 60 * 60 + 7 * 60)
-                        .ymd(2010, 1, 2)
-                        .and_hms(3, 4, 5)
-                        .into(),
-                )),
+                Err(ParseError::new(ParseErrorKind::TimeZoneOffset)),
                 b"\x18\x1320100102030405+0607",
             ),
             (
-                Ok(GeneralizedTime::new(
-                    FixedOffset::west(6 * 60 * 60 + 7 * 60)
-                        .ymd(2010, 1, 2)
-                        .and_hms(3, 4, 5)
-                        .into(),
-                )),
+                Err(ParseError::new(ParseErrorKind::TimeZoneOffset)),
                 b"\x18\x1320100102030405-0607",
             ),
             (
@@ -1141,11 +1618,55 @@ mod tests {
                 Err(ParseError::new(ParseErrorKind::InvalidValue)),
                 b"\x18\x0f201001020304-10Z",
             ),
-            // Tests for fractional seconds, which we currently don't support
             (
-                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                Ok(GeneralizedTime::new(
+                    DateTime::new(2010, 1, 2, 3, 4, 5)
+                        .unwrap()
+                        .with_nanosecond(123_456_000)
+                        .unwrap(),
+                )),
                 b"\x18\x1620100102030405.123456Z",
             ),
+            (
+                Ok(GeneralizedTime::new(
+                    DateTime::new(2010, 1, 2, 3, 4, 5)
+                        .unwrap()
+                        .with_nanosecond(500_000_000)
+                        .unwrap(),
+                )),
+                b"\x18\x1120100102030405.5Z",
+            ),
+            (
+                Ok(GeneralizedTime::new(
+                    DateTime::new(2010, 1, 2, 3, 4, 5)
+                        .unwrap()
+                        .with_nanosecond(1)
+                        .unwrap(),
+                )),
+                b"\x18\x1920100102030405.000000001Z",
+            ),
+            // Fractions may not have trailing zeros, use ',', or be more
+            // precise than nanoseconds.
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x18\x1220100102030405.50Z",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x18\x1120100102030405.0Z",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x18\x1120100102030405,5Z",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+                b"\x18\x1a20100102030405.0000000001Z",
+            ),
+            (
+                Err(ParseError::new(ParseErrorKind::TimeZoneOffset)),
+                b"\x18\x1520100102030405.5+0000",
+            ),
             (
                 Err(ParseError::new(ParseErrorKind::InvalidValue)),
                 b"\x18\x1520100102030405.123456",
@@ -1190,6 +1711,29 @@ mod tests {
         ])
     }
 
+    #[test]
+    fn test_parse_owned_sequence() {
+        let data = b"\x30\x06\x02\x01\x01\x02\x01\x02".to_vec();
+        let seq = crate::parse_single::<OwnedSequence>(&data).unwrap();
+        drop(data);
+        assert_eq!(
+            seq.as_sequence(),
+            Sequence::new(b"\x02\x01\x01\x02\x01\x02")
+        );
+        assert_eq!(
+            seq.parse(|p| Ok::<_, ParseError>((
+                p.read_element::<u64>()?,
+                p.read_element::<u64>()?
+            ))),
+            Ok((1, 2))
+        );
+
+        assert_parses::<OwnedSequence>(&[(
+            Err(ParseError::new(ParseErrorKind::ShortData)),
+            b"\x30\x04\x02\x01\x01",
+        )]);
+    }
+
     #[test]
     fn test_sequence_parse() {
         assert_parses_cb(
@@ -1256,6 +1800,44 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_vec() {
+        assert_parses::<Vec<i64>>(&[
+            (
+                Ok(vec![1, 2, 3]),
+                b"\x30\x09\x02\x01\x01\x02\x01\x02\x02\x01\x03",
+            ),
+            (Ok(vec![]), b"\x30\x00"),
+            (
+                Err(ParseError::new(ParseErrorKind::UnexpectedTag {
+                    actual: Tag::primitive(0x04),
+                })
+                .add_location(ParseLocation::Index(1))),
+                b"\x30\x05\x02\x01\x01\x04\x00",
+            ),
+        ]);
+        assert_parses::<Vec<Vec<bool>>>(&[(
+            Ok(vec![vec![true], vec![]]),
+            b"\x30\x07\x30\x03\x01\x01\xff\x30\x00",
+        )]);
+    }
+
+    #[test]
+    fn test_parse_boxed_slice() {
+        assert_parses::<Box<[u8]>>(&[
+            (
+                Ok(vec![1, 2].into_boxed_slice()),
+                b"\x30\x06\x02\x01\x01\x02\x01\x02",
+            ),
+            (Ok(vec![].into_boxed_slice()), b"\x30\x00"),
+            (
+                Err(ParseError::new(ParseErrorKind::ShortData)
+                    .add_location(ParseLocation::Index(0))),
+                b"\x30\x02\x02\x01",
+            ),
+        ]);
+    }
+
     #[test]
     fn parse_set_of() {
         assert_parses_cb(
@@ -1265,21 +1847,30 @@ mod tests {
                     b"\x31\x09\x02\x01\x01\x02\x01\x02\x02\x01\x03",
                 ),
                 (Ok(vec![]), b"\x31\x00"),
+                (
+                    Ok(vec![1, 1, 3]),
+                    b"\x31\x09\x02\x01\x01\x02\x01\x01\x02\x01\x03",
+                ),
                 (
                     Err(ParseError::new(ParseErrorKind::InvalidSetOrdering)
                         .add_location(ParseLocation::Index(1))),
                     b"\x31\x06\x02\x01\x03\x02\x01\x01",
                 ),
+                (
+                    Err(ParseError::new(ParseErrorKind::InvalidSetOrdering)
+                        .add_location(ParseLocation::Index(2))),
+                    b"\x31\x09\x02\x01\x01\x02\x01\x03\x02\x01\x02",
+                ),
                 (
                     Err(ParseError::new(ParseErrorKind::ShortData)
                         .add_location(ParseLocation::Index(0))),
                     b"\x31\x01\x02",
                 ),
                 (
-                    Err(
-                        ParseError::new(ParseErrorKind::UnexpectedTag { actual: 0x1 })
-                            .add_location(ParseLocation::Index(0)),
-                    ),
+                    Err(ParseError::new(ParseErrorKind::UnexpectedTag {
+                        actual: Tag::primitive(0x1),
+                    })
+                    .add_location(ParseLocation::Index(0))),
                     b"\x31\x02\x01\x00",
                 ),
             ],
@@ -1287,6 +1878,22 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_owned_set_of() {
+        assert_parses::<OwnedSetOf<u64>>(&[
+            (
+                Ok(OwnedSetOf::new(vec![1, 1, 3])),
+                b"\x31\x09\x02\x01\x01\x02\x01\x01\x02\x01\x03",
+            ),
+            (Ok(OwnedSetOf::new(vec![])), b"\x31\x00"),
+            (
+                Err(ParseError::new(ParseErrorKind::InvalidSetOrdering)
+                    .add_location(ParseLocation::Index(1))),
+                b"\x31\x06\x02\x01\x03\x02\x01\x01",
+            ),
+        ]);
+    }
+
     #[test]
     fn test_parse_optional() {
         assert_parses_cb(
@@ -1310,7 +1917,7 @@ mod tests {
         assert_parses::<Option<Tlv>>(&[
             (
                 Ok(Some(Tlv {
-                    tag: 0x4,
+                    tag: Tag::primitive(0x4),
                     data: b"abc",
                     full_data: b"\x04\x03abc",
                 })),
@@ -1333,7 +1940,7 @@ mod tests {
             (Ok(Choice1::ChoiceA(true)), b"\x01\x01\xff"),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x03,
+                    actual: Tag::primitive(0x03),
                 })),
                 b"\x03\x00",
             ),
@@ -1348,7 +1955,7 @@ mod tests {
             (Ok(Choice2::ChoiceB(18)), b"\x02\x01\x12"),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x03,
+                    actual: Tag::primitive(0x03),
                 })),
                 b"\x03\x00",
             ),
@@ -1364,7 +1971,7 @@ mod tests {
             (Ok(Choice3::ChoiceC(())), b"\x05\x00"),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x03,
+                    actual: Tag::primitive(0x03),
                 })),
                 b"\x03\x00",
             ),
@@ -1380,13 +1987,13 @@ mod tests {
             (Ok(Implicit::new(false)), b"\x82\x01\x00"),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x01,
+                    actual: Tag::primitive(0x01),
                 })),
                 b"\x01\x01\xff",
             ),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x02,
+                    actual: Tag::primitive(0x02),
                 })),
                 b"\x02\x01\xff",
             ),
@@ -1397,13 +2004,13 @@ mod tests {
             (Ok(Implicit::new(Sequence::new(b""))), b"\xa2\x00"),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x01,
+                    actual: Tag::primitive(0x01),
                 })),
                 b"\x01\x01\xff",
             ),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x02,
+                    actual: Tag::primitive(0x02),
                 })),
                 b"\x02\x01\xff",
             ),
@@ -1452,19 +2059,19 @@ mod tests {
             (Ok(Explicit::new(false)), b"\xa2\x03\x01\x01\x00"),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x01,
+                    actual: Tag::primitive(0x01),
                 })),
                 b"\x01\x01\xff",
             ),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x02,
+                    actual: Tag::primitive(0x02),
                 })),
                 b"\x02\x01\xff",
             ),
             (
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                    actual: 0x03,
+                    actual: Tag::primitive(0x03),
                 })),
                 b"\xa2\x03\x03\x01\xff",
             ),
@@ -1481,7 +2088,7 @@ mod tests {
                 ),
                 (
                     Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                        actual: 0x03,
+                        actual: Tag::primitive(0x03),
                     })),
                     b"\xa2\x03\x03\x01\xff",
                 ),
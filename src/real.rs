@@ -0,0 +1,508 @@
+use crate::parser::{ParseError, ParseErrorKind, ParseResult};
+use crate::types::{int_data_len, SimpleAsn1Readable, SimpleAsn1Writable};
+use crate::writer::{WriteBuf, WriteError, WriteResult};
+use core::str;
+
+const PLUS_INFINITY: u8 = 0x40;
+const MINUS_INFINITY: u8 = 0x41;
+const NOT_A_NUMBER: u8 = 0x42;
+const MINUS_ZERO: u8 = 0x43;
+const NR3: u8 = 0x03;
+
+/// Represents an ASN.1 `REAL` exactly, for when converting to an `f64` would
+/// lose information.
+///
+/// Finite values are written in their normalized form: an odd (or zero)
+/// mantissa for `Binary`, and a mantissa without trailing zero digits for
+/// `Decimal`. Parsed values are always normalized. Writing fails with
+/// [`WriteError::InvalidValue`] if normalizing a value would take its exponent
+/// out of the range of an `i32`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Real {
+    /// The value `mantissa * 2^exponent`.
+    Binary {
+        mantissa: i64,
+        exponent: i32,
+    },
+    /// The value `mantissa * 10^exponent`.
+    Decimal {
+        mantissa: i64,
+        exponent: i32,
+    },
+    PlusInfinity,
+    MinusInfinity,
+    NotANumber,
+    MinusZero,
+}
+
+impl Real {
+    /// Converts to the nearest `f64`. Values whose magnitude is too large are
+    /// converted to infinity, as with `str::parse`.
+    pub fn to_f64(&self) -> f64 {
+        match *self {
+            Real::Binary { mantissa, exponent } => ldexp(mantissa, exponent),
+            Real::Decimal { mantissa, exponent } => decimal_to_f64(mantissa, exponent),
+            Real::PlusInfinity => f64::INFINITY,
+            Real::MinusInfinity => f64::NEG_INFINITY,
+            Real::NotANumber => f64::NAN,
+            Real::MinusZero => -0.0,
+        }
+    }
+}
+
+impl From<f64> for Real {
+    fn from(v: f64) -> Real {
+        if v.is_nan() {
+            return Real::NotANumber;
+        } else if v.is_infinite() {
+            return if v < 0.0 {
+                Real::MinusInfinity
+            } else {
+                Real::PlusInfinity
+            };
+        } else if v == 0.0 {
+            return if v.is_sign_negative() {
+                Real::MinusZero
+            } else {
+                Real::Binary {
+                    mantissa: 0,
+                    exponent: 0,
+                }
+            };
+        }
+
+        let bits = v.to_bits();
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = if biased_exponent == 0 {
+            // Subnormal
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased_exponent - 1075)
+        };
+        let trailing_zeros = mantissa.trailing_zeros();
+        let mantissa = (mantissa >> trailing_zeros) as i64;
+        Real::Binary {
+            mantissa: if v < 0.0 { -mantissa } else { mantissa },
+            exponent: exponent + trailing_zeros as i32,
+        }
+    }
+}
+
+// Computes `mantissa * 2^exponent`, rounded (half to even) to the nearest
+// `f64`.
+fn ldexp(mantissa: i64, exponent: i32) -> f64 {
+    let sign = if mantissa < 0 { 1 << 63 } else { 0 };
+    let n = u128::from(mantissa.wrapping_abs() as u64);
+    if n == 0 {
+        return f64::from_bits(sign);
+    }
+
+    let length = 128 - i64::from(n.leading_zeros());
+    // The exponent of the most significant bit.
+    let mut msb = i64::from(exponent) + length - 1;
+    if msb > 1023 {
+        return f64::from_bits(sign | f64::INFINITY.to_bits());
+    }
+    // Subnormal values have fewer significant bits available.
+    let precision = if msb >= -1022 { 53 } else { msb + 1075 };
+    if precision < 0 {
+        return f64::from_bits(sign);
+    }
+
+    let shift = length - precision;
+    let mut m = if shift <= 0 {
+        n << -shift
+    } else {
+        let m = n >> shift;
+        let remainder = n & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        if remainder > half || (remainder == half && m & 1 == 1) {
+            m + 1
+        } else {
+            m
+        }
+    } as u64;
+
+    let bits = if precision == 53 {
+        if m == 1 << 53 {
+            m >>= 1;
+            msb += 1;
+            if msb > 1023 {
+                return f64::from_bits(sign | f64::INFINITY.to_bits());
+            }
+        }
+        ((msb + 1023) as u64) << 52 | (m & ((1 << 52) - 1))
+    } else {
+        // If rounding carried into the 53rd bit, this is the smallest normal
+        // value, which is also what these bits represent.
+        m
+    };
+    f64::from_bits(sign | bits)
+}
+
+fn parse_binary(data: &[u8]) -> ParseResult<Real> {
+    let first = data[0];
+    // DER requires base 2 and a scaling factor of 0.
+    if first & 0x3c != 0 {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue));
+    }
+    let (exponent_length, data) = match first & 0x03 {
+        3 => {
+            let length = *data
+                .get(1)
+                .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))?;
+            // The long form may only be used when the short ones can't.
+            if length <= 3 {
+                return Err(ParseError::new(ParseErrorKind::InvalidValue));
+            }
+            if length > 4 {
+                return Err(ParseError::new(ParseErrorKind::IntegerOverflow));
+            }
+            (length as usize, &data[2..])
+        }
+        n => (n as usize + 1, &data[1..]),
+    };
+    if data.len() <= exponent_length {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue));
+    }
+    let (exponent, mantissa) = data.split_at(exponent_length);
+    // Exponents are minimally encoded two's complement integers, just like
+    // `INTEGER`s.
+    let exponent = i32::parse_data(exponent)?;
+
+    // The mantissa must be odd (zero has its own encoding), and so without
+    // leading zeros.
+    if mantissa[0] == 0 || mantissa[mantissa.len() - 1] & 1 == 0 {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue));
+    }
+    if mantissa.len() > 8 || (mantissa.len() == 8 && mantissa[0] & 0x80 != 0) {
+        return Err(ParseError::new(ParseErrorKind::IntegerOverflow));
+    }
+    let mut value = 0i64;
+    for b in mantissa {
+        value = (value << 8) | i64::from(*b);
+    }
+
+    Ok(Real::Binary {
+        mantissa: if first & 0x40 != 0 { -value } else { value },
+        exponent,
+    })
+}
+
+// Parses the NR3 form of ISO 6093, as restricted by DER: the mantissa has no
+// leading or trailing zeros and is followed by ".E", and the exponent has no
+// leading zeros and is only signed if negative, except for zero which is
+// written as "+0". For example, "-15.E-1" or "1.E+0".
+fn parse_decimal(data: &[u8]) -> ParseResult<Real> {
+    if data[0] != NR3 {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue));
+    }
+    let s = core::str::from_utf8(&data[1..])
+        .map_err(|_| ParseError::new(ParseErrorKind::InvalidValue))?;
+    let dot = s
+        .find(".E")
+        .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))?;
+    let (mantissa, exponent) = (&s[..dot], &s[dot + 2..]);
+
+    let digits = mantissa.strip_prefix('-').unwrap_or(mantissa);
+    if !is_canonical_digits(digits) || digits.ends_with('0') {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue));
+    }
+    if exponent != "+0" {
+        let digits = exponent.strip_prefix('-').unwrap_or(exponent);
+        if !is_canonical_digits(digits) {
+            return Err(ParseError::new(ParseErrorKind::InvalidValue));
+        }
+    }
+
+    Ok(Real::Decimal {
+        mantissa: mantissa
+            .parse()
+            .map_err(|_| ParseError::new(ParseErrorKind::IntegerOverflow))?,
+        exponent: exponent
+            .parse()
+            .map_err(|_| ParseError::new(ParseErrorKind::IntegerOverflow))?,
+    })
+}
+
+// Whether `s` is a non-empty series of digits without a leading zero.
+fn is_canonical_digits(s: &str) -> bool {
+    !s.is_empty() && !s.starts_with('0') && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+impl Real {
+    pub(crate) fn parse_data(data: &[u8]) -> ParseResult<Real> {
+        if data.is_empty() {
+            return Ok(Real::Binary {
+                mantissa: 0,
+                exponent: 0,
+            });
+        }
+        match data[0] >> 6 {
+            0b10 | 0b11 => parse_binary(data),
+            0b00 => parse_decimal(data),
+            _ => match data {
+                [PLUS_INFINITY] => Ok(Real::PlusInfinity),
+                [MINUS_INFINITY] => Ok(Real::MinusInfinity),
+                [NOT_A_NUMBER] => Ok(Real::NotANumber),
+                [MINUS_ZERO] => Ok(Real::MinusZero),
+                _ => Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            },
+        }
+    }
+
+    pub(crate) fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        match *self {
+            Real::Binary { mantissa: 0, .. } | Real::Decimal { mantissa: 0, .. } => Ok(()),
+            Real::Binary { mantissa, exponent } => {
+                let (n, exponent) =
+                    binary_parts(mantissa, exponent).ok_or(WriteError::InvalidValue)?;
+                let exponent_len = int_data_len(i128::from(exponent));
+
+                let mut first = 0x80;
+                if mantissa < 0 {
+                    first |= 0x40;
+                }
+                if exponent_len <= 3 {
+                    dest.push_byte(first | (exponent_len as u8 - 1))?;
+                } else {
+                    dest.push_byte(first | 0x03)?;
+                    dest.push_byte(exponent_len as u8)?;
+                }
+                exponent.write_data(dest)?;
+
+                let mantissa_length = 8 - n.leading_zeros() as usize / 8;
+                dest.push_slice(&n.to_be_bytes()[8 - mantissa_length..])
+            }
+            Real::Decimal { mantissa, exponent } => {
+                let (mantissa, exponent) =
+                    decimal_parts(mantissa, exponent).ok_or(WriteError::InvalidValue)?;
+                let mut buf = [0; MAX_DECIMAL_LEN];
+                dest.push_byte(NR3)?;
+                dest.push_slice(format_decimal(mantissa, &mut buf))?;
+                dest.push_slice(b".E")?;
+                if exponent == 0 {
+                    dest.push_slice(b"+0")
+                } else {
+                    dest.push_slice(format_decimal(i64::from(exponent), &mut buf))
+                }
+            }
+            Real::PlusInfinity => dest.push_byte(PLUS_INFINITY),
+            Real::MinusInfinity => dest.push_byte(MINUS_INFINITY),
+            Real::NotANumber => dest.push_byte(NOT_A_NUMBER),
+            Real::MinusZero => dest.push_byte(MINUS_ZERO),
+        }
+    }
+
+    // Returns `None` for values which can't be written.
+    pub(crate) fn data_len(&self) -> Option<usize> {
+        Some(match *self {
+            Real::Binary { mantissa: 0, .. } | Real::Decimal { mantissa: 0, .. } => 0,
+            Real::Binary { mantissa, exponent } => {
+                let (n, exponent) = binary_parts(mantissa, exponent)?;
+                let exponent_len = int_data_len(i128::from(exponent));
+                let header_len = if exponent_len <= 3 { 1 } else { 2 };
+                header_len + exponent_len + 8 - n.leading_zeros() as usize / 8
+            }
+            Real::Decimal { mantissa, exponent } => {
+                let (mantissa, exponent) = decimal_parts(mantissa, exponent)?;
+                let exponent_len = if exponent == 0 {
+                    2
+                } else {
+                    decimal_len(i64::from(exponent))
+                };
+                1 + decimal_len(mantissa) + 2 + exponent_len
+            }
+            Real::PlusInfinity | Real::MinusInfinity | Real::NotANumber | Real::MinusZero => 1,
+        })
+    }
+}
+
+// Returns the odd magnitude of a non-zero binary mantissa, and the exponent
+// adjusted to match, or `None` if that exponent doesn't fit in an `i32`.
+fn binary_parts(mantissa: i64, exponent: i32) -> Option<(u64, i32)> {
+    let n = mantissa.wrapping_abs() as u64;
+    let trailing_zeros = n.trailing_zeros();
+    Some((
+        n >> trailing_zeros,
+        exponent.checked_add(trailing_zeros as i32)?,
+    ))
+}
+
+// Returns a non-zero decimal mantissa without trailing zeros, and the
+// exponent adjusted to match, or `None` if that exponent doesn't fit in an
+// `i32`.
+fn decimal_parts(mut mantissa: i64, mut exponent: i32) -> Option<(i64, i32)> {
+    while mantissa % 10 == 0 {
+        mantissa /= 10;
+        exponent = exponent.checked_add(1)?;
+    }
+    Some((mantissa, exponent))
+}
+
+// Returns the length of `v` formatted in decimal, including its sign.
+fn decimal_len(v: i64) -> usize {
+    format_decimal(v, &mut [0; MAX_DECIMAL_LEN]).len()
+}
+
+// The length of the longest `i64` formatted in decimal, including its sign.
+const MAX_DECIMAL_LEN: usize = 20;
+
+// Formats `v` in decimal into the end of `buf`, returning the part of `buf`
+// it was formatted into.
+fn format_decimal(v: i64, buf: &mut [u8; MAX_DECIMAL_LEN]) -> &[u8] {
+    let mut pos = buf.len();
+    let mut n = v.wrapping_abs() as u64;
+    loop {
+        pos -= 1;
+        buf[pos] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    if v < 0 {
+        pos -= 1;
+        buf[pos] = b'-';
+    }
+    &buf[pos..]
+}
+
+// Computes `mantissa * 10^exponent`, rounded to the nearest `f64`. The
+// standard library already implements correctly rounded decimal to binary
+// conversion, so this formats the value for it to parse.
+fn decimal_to_f64(mantissa: i64, exponent: i32) -> f64 {
+    let mut s = [0; 2 * MAX_DECIMAL_LEN + 1];
+    let mut buf = [0; MAX_DECIMAL_LEN];
+    let mantissa = format_decimal(mantissa, &mut buf);
+    let mut len = mantissa.len();
+    s[..len].copy_from_slice(mantissa);
+    s[len] = b'e';
+    len += 1;
+    let exponent = format_decimal(i64::from(exponent), &mut buf);
+    s[len..len + exponent.len()].copy_from_slice(exponent);
+    len += exponent.len();
+    str::from_utf8(&s[..len]).unwrap().parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ldexp, Real};
+
+    #[test]
+    fn test_real_from_f64() {
+        for (v, expected) in [
+            (
+                0.0,
+                Real::Binary {
+                    mantissa: 0,
+                    exponent: 0,
+                },
+            ),
+            (-0.0, Real::MinusZero),
+            (f64::INFINITY, Real::PlusInfinity),
+            (f64::NEG_INFINITY, Real::MinusInfinity),
+            (f64::NAN, Real::NotANumber),
+            (
+                1.0,
+                Real::Binary {
+                    mantissa: 1,
+                    exponent: 0,
+                },
+            ),
+            (
+                -0.375,
+                Real::Binary {
+                    mantissa: -3,
+                    exponent: -3,
+                },
+            ),
+            (
+                1024.0,
+                Real::Binary {
+                    mantissa: 1,
+                    exponent: 10,
+                },
+            ),
+            (
+                f64::MAX,
+                Real::Binary {
+                    mantissa: (1 << 53) - 1,
+                    exponent: 971,
+                },
+            ),
+            (
+                f64::from_bits(1),
+                Real::Binary {
+                    mantissa: 1,
+                    exponent: -1074,
+                },
+            ),
+        ] {
+            assert_eq!(Real::from(v), expected);
+        }
+    }
+
+    #[test]
+    fn test_real_to_f64() {
+        for v in [
+            0.0,
+            1.0,
+            -0.375,
+            0.1,
+            1e300,
+            -1e-300,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            f64::from_bits(1),
+            f64::from_bits(0x000f_ffff_ffff_ffff),
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ] {
+            assert_eq!(Real::from(v).to_f64().to_bits(), v.to_bits());
+        }
+        assert!(Real::NotANumber.to_f64().is_nan());
+        assert_eq!(Real::MinusZero.to_f64().to_bits(), (-0.0f64).to_bits());
+
+        assert_eq!(
+            Real::Decimal {
+                mantissa: -15,
+                exponent: -1
+            }
+            .to_f64(),
+            -1.5
+        );
+        assert_eq!(
+            Real::Decimal {
+                mantissa: 1,
+                exponent: 400
+            }
+            .to_f64(),
+            f64::INFINITY
+        );
+    }
+
+    #[test]
+    fn test_ldexp_rounding() {
+        // Exact
+        assert_eq!(ldexp(3, -1), 1.5);
+        assert_eq!(ldexp(-1, 1023), f64::from_bits(0xffe0_0000_0000_0000));
+        // 2^53 + 1 is halfway between two values, and rounds to even.
+        assert_eq!(ldexp((1 << 53) + 1, 0), 9007199254740992.0);
+        assert_eq!(ldexp((1 << 53) + 3, 0), 9007199254740996.0);
+        // Rounding up can carry into the exponent.
+        assert_eq!(ldexp((1 << 54) - 1, 0), 18014398509481984.0);
+        assert_eq!(ldexp((1 << 54) - 1, 970), f64::INFINITY);
+        assert_eq!(ldexp(1, 1024), f64::INFINITY);
+        assert_eq!(ldexp(-1, 1024), f64::NEG_INFINITY);
+        // Subnormals
+        assert_eq!(ldexp(1, -1074), f64::from_bits(1));
+        assert_eq!(ldexp(3, -1075), f64::from_bits(2));
+        assert_eq!(ldexp(1, -1075), 0.0);
+        assert_eq!(ldexp(3, -1076), f64::from_bits(1));
+        assert_eq!(ldexp(1, -2000), 0.0);
+        assert_eq!(ldexp((1 << 53) - 1, -1075), f64::MIN_POSITIVE);
+        assert_eq!(ldexp(i64::MIN, 0), -9223372036854775808.0);
+    }
+}
@@ -0,0 +1,736 @@
+use crate::serde_impls::Special;
+use crate::writer::{_finish_tlv, _start_tlv, WriteBuf, WriteError};
+use crate::{explicit_tag, implicit_tag, ParseError, Real, Tag, Utf8String, Writer};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use serde::ser::{self, Serialize};
+
+const SEQUENCE_TAG: Tag = Tag::constructed(0x10);
+
+/// An error from the DER [`Serializer`] or
+/// [`Deserializer`](crate::Deserializer).
+#[derive(Debug, PartialEq)]
+pub enum SerdeError {
+    /// The DER being deserialized was invalid, or didn't match the type.
+    Parse(ParseError),
+    /// Writing the DER failed.
+    Write(WriteError),
+    /// Any other error, e.g. from a `Serialize` or `Deserialize`
+    /// implementation, or a value which can't be represented in DER.
+    Message(String),
+}
+
+impl From<ParseError> for SerdeError {
+    fn from(e: ParseError) -> SerdeError {
+        SerdeError::Parse(e)
+    }
+}
+
+impl From<WriteError> for SerdeError {
+    fn from(e: WriteError) -> SerdeError {
+        SerdeError::Write(e)
+    }
+}
+
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerdeError::Parse(e) => write!(f, "{}", e),
+            SerdeError::Write(e) => write!(f, "{}", e),
+            SerdeError::Message(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SerdeError {}
+
+impl ser::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> SerdeError {
+        SerdeError::Message(msg.to_string())
+    }
+}
+
+impl serde::de::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> SerdeError {
+        SerdeError::Message(msg.to_string())
+    }
+}
+
+/// Options for the DER [`Serializer`] and
+/// [`Deserializer`](crate::Deserializer).
+///
+/// Enums are encoded as a `CHOICE`, with each variant's value tagged with a
+/// context-specific tag. By default a variant's tag is its index, so the
+/// first variant is `[0]`; `choice_tag` overrides this to match an existing
+/// ASN.1 definition.
+#[derive(Debug, Clone, Default)]
+pub struct SerdeOptions {
+    choice_tags: Vec<(&'static str, &'static str, u32)>,
+}
+
+impl SerdeOptions {
+    pub fn new() -> SerdeOptions {
+        SerdeOptions::default()
+    }
+
+    /// Encodes the variant named `variant` of the enum named `name` with the
+    /// context-specific tag `tag`.
+    pub fn choice_tag(
+        mut self,
+        name: &'static str,
+        variant: &'static str,
+        tag: u32,
+    ) -> SerdeOptions {
+        self.choice_tags.push((name, variant, tag));
+        self
+    }
+
+    pub(crate) fn variant_tag(&self, name: &str, variant: &str, index: u32) -> u32 {
+        self.choice_tags
+            .iter()
+            .rev()
+            .find(|(n, v, _)| *n == name && *v == variant)
+            .map_or(index, |(_, _, tag)| *tag)
+    }
+}
+
+/// Serializes `value` as DER.
+///
+/// Structs and tuples are encoded as a `SEQUENCE`, and sequences (e.g. `Vec`)
+/// as a `SEQUENCE OF`. `Option`s must be fields of a struct or tuple, and are
+/// encoded as `OPTIONAL` values, explicitly tagged with the field's index as
+/// with `AUTOMATIC TAGS` (so that they can be told apart when
+/// deserializing). Enums are encoded as a `CHOICE`, as described for
+/// [`SerdeOptions`]: unit variants as an `IMPLICIT NULL`, newtype variants
+/// as `EXPLICIT`ly tagged values, and tuple and struct variants as an
+/// `IMPLICIT SEQUENCE`. Maps are encoded as a `SEQUENCE OF SEQUENCE { key,
+/// value }`.
+///
+/// Integers are encoded as an `INTEGER`, floats as a `REAL`, strings and
+/// `char`s as a `UTF8String`, bytes as an `OCTET STRING`, and `()` and unit
+/// structs as `NULL`. Newtype structs are encoded as the value they wrap.
+/// The crate's own types, e.g. `ObjectIdentifier`, use their usual encoding.
+pub fn to_der<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, SerdeError> {
+    to_der_with_options(value, &SerdeOptions::new())
+}
+
+/// Serializes `value` as DER, like [`to_der`], with `options`.
+pub fn to_der_with_options<T: Serialize + ?Sized>(
+    value: &T,
+    options: &SerdeOptions,
+) -> Result<Vec<u8>, SerdeError> {
+    let mut data = WriteBuf::new();
+    value.serialize(&mut Serializer::new(&mut data, options))?;
+    Ok(data.into_vec())
+}
+
+/// A serde `Serializer` which writes DER. See [`to_der`] for how values are
+/// encoded.
+pub struct Serializer<'a> {
+    data: &'a mut WriteBuf<'static>,
+    options: &'a SerdeOptions,
+    // The index of the value in the enclosing struct or tuple, if it's a
+    // field of one.
+    field: Option<u32>,
+}
+
+impl<'a> Serializer<'a> {
+    /// Creates a `Serializer` which appends to `data`, a `WriteBuf` created
+    /// with [`WriteBuf::new`] or [`WriteBuf::with_limit`].
+    pub fn new(data: &'a mut WriteBuf<'static>, options: &'a SerdeOptions) -> Serializer<'a> {
+        Serializer {
+            data,
+            options,
+            field: None,
+        }
+    }
+
+    fn child(&mut self, field: Option<u32>) -> Serializer<'_> {
+        Serializer {
+            data: self.data,
+            options: self.options,
+            field,
+        }
+    }
+
+    fn write<'b, T: crate::Asn1Writable<'b>>(&mut self, value: &T) -> Result<(), SerdeError> {
+        Writer::new(self.data).write_element(value)?;
+        Ok(())
+    }
+
+    fn start(&mut self, tag: Tag, indexed: bool) -> Result<Compound<'_, 'a>, SerdeError> {
+        let start = _start_tlv(self.data, tag)?;
+        Ok(Compound {
+            ser: self,
+            start,
+            index: if indexed { Some(0) } else { None },
+            entry_start: 0,
+        })
+    }
+
+    fn variant_tag(&self, name: &str, variant: &str, index: u32) -> u32 {
+        self.options.variant_tag(name, variant, index)
+    }
+}
+
+fn unsupported_option() -> SerdeError {
+    SerdeError::Message("an Option can only be serialized as a field of a struct or tuple".into())
+}
+
+impl<'a, 'b> ser::Serializer for &'b mut Serializer<'a> {
+    type Ok = ();
+    type Error = SerdeError;
+    type SerializeSeq = Compound<'b, 'a>;
+    type SerializeTuple = Compound<'b, 'a>;
+    type SerializeTupleStruct = Compound<'b, 'a>;
+    type SerializeTupleVariant = Compound<'b, 'a>;
+    type SerializeMap = Compound<'b, 'a>;
+    type SerializeStruct = Compound<'b, 'a>;
+    type SerializeStructVariant = Compound<'b, 'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), SerdeError> {
+        self.write(&v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), SerdeError> {
+        self.write(&v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), SerdeError> {
+        self.write(&i32::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), SerdeError> {
+        self.write(&v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), SerdeError> {
+        self.write(&v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), SerdeError> {
+        self.write(&v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), SerdeError> {
+        self.write(&u32::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), SerdeError> {
+        self.write(&v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), SerdeError> {
+        self.write(&v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), SerdeError> {
+        self.write(&Real::from(f64::from(v)))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), SerdeError> {
+        self.write(&Real::from(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), SerdeError> {
+        self.write(&Utf8String::new(v.encode_utf8(&mut [0; 4])))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), SerdeError> {
+        self.write(&Utf8String::new(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), SerdeError> {
+        self.write(&v)
+    }
+
+    fn serialize_none(self) -> Result<(), SerdeError> {
+        match self.field {
+            Some(_) => Ok(()),
+            None => Err(unsupported_option()),
+        }
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), SerdeError> {
+        let field = self.field.ok_or_else(unsupported_option)?;
+        let start = _start_tlv(self.data, explicit_tag(field))?;
+        value.serialize(&mut self.child(None))?;
+        _finish_tlv(self.data, start)?;
+        Ok(())
+    }
+
+    fn serialize_unit(self) -> Result<(), SerdeError> {
+        self.write(&())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), SerdeError> {
+        self.write(&())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), SerdeError> {
+        let tag = self.variant_tag(name, variant, variant_index);
+        let tag = implicit_tag(tag, <() as crate::SimpleAsn1Writable>::TAG);
+        Writer::new(self.data).write_tlv(tag, |_| Ok(()))?;
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        let special = match Special::from_name(name) {
+            Some(special) => special,
+            None => return value.serialize(self),
+        };
+        let text = value.serialize(TextSerializer)?;
+        let data = special
+            .text_to_der(&text)
+            .ok_or_else(|| SerdeError::Message(alloc::format!("invalid value: {}", text)))?;
+        Writer::new(self.data).write_tlv(special.tag(), |dest| dest.push_slice(&data))?;
+        Ok(())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        let tag = self.variant_tag(name, variant, variant_index);
+        let start = _start_tlv(self.data, explicit_tag(tag))?;
+        value.serialize(&mut self.child(None))?;
+        _finish_tlv(self.data, start)?;
+        Ok(())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'b, 'a>, SerdeError> {
+        self.start(SEQUENCE_TAG, false)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'b, 'a>, SerdeError> {
+        self.start(SEQUENCE_TAG, true)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'b, 'a>, SerdeError> {
+        self.start(SEQUENCE_TAG, true)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'b, 'a>, SerdeError> {
+        let tag = self.variant_tag(name, variant, variant_index);
+        self.start(implicit_tag(tag, SEQUENCE_TAG), true)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'b, 'a>, SerdeError> {
+        self.start(SEQUENCE_TAG, false)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'b, 'a>, SerdeError> {
+        self.start(SEQUENCE_TAG, true)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'b, 'a>, SerdeError> {
+        let tag = self.variant_tag(name, variant, variant_index);
+        self.start(implicit_tag(tag, SEQUENCE_TAG), true)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Serializes the elements of a constructed value.
+#[doc(hidden)]
+pub struct Compound<'b, 'a> {
+    ser: &'b mut Serializer<'a>,
+    start: usize,
+    // The index of the next element, for structs and tuples.
+    index: Option<u32>,
+    // Where the current map entry starts.
+    entry_start: usize,
+}
+
+impl Compound<'_, '_> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        let field = self.index;
+        if let Some(index) = self.index.as_mut() {
+            *index += 1;
+        }
+        value.serialize(&mut self.ser.child(field))
+    }
+
+    fn finish(self) -> Result<(), SerdeError> {
+        _finish_tlv(self.ser.data, self.start)?;
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_, '_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_, '_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_, '_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_, '_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Compound<'_, '_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerdeError> {
+        self.entry_start = _start_tlv(self.ser.data, SEQUENCE_TAG)?;
+        self.element(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.element(value)?;
+        _finish_tlv(self.ser.data, self.entry_start)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_, '_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_, '_> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), SerdeError> {
+        self.finish()
+    }
+}
+
+// Captures the string which the crate's own types serialize as their text
+// form.
+struct TextSerializer;
+
+fn not_text() -> SerdeError {
+    SerdeError::Message("expected a string".into())
+}
+
+impl ser::Serializer for TextSerializer {
+    type Ok = String;
+    type Error = SerdeError;
+    type SerializeSeq = ser::Impossible<String, SerdeError>;
+    type SerializeTuple = ser::Impossible<String, SerdeError>;
+    type SerializeTupleStruct = ser::Impossible<String, SerdeError>;
+    type SerializeTupleVariant = ser::Impossible<String, SerdeError>;
+    type SerializeMap = ser::Impossible<String, SerdeError>;
+    type SerializeStruct = ser::Impossible<String, SerdeError>;
+    type SerializeStructVariant = ser::Impossible<String, SerdeError>;
+
+    fn serialize_str(self, v: &str) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_char(self, _v: char) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_none(self) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_unit(self) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _value: &T,
+    ) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, SerdeError> {
+        Err(not_text())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, SerdeError> {
+        Err(not_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_der, to_der_with_options, SerdeError, SerdeOptions};
+    use crate::{BitString, GeneralizedTime, ObjectIdentifier, OwnedBigUint, UtcTime};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_to_der() {
+        assert_eq!(to_der(&true).unwrap(), b"\x01\x01\xff");
+        assert_eq!(to_der(&-2i16).unwrap(), b"\x02\x01\xfe");
+        assert_eq!(to_der(&1.5f64).unwrap(), b"\x09\x03\x80\xff\x03");
+        assert_eq!(to_der(&'a').unwrap(), b"\x0c\x01a");
+        assert_eq!(to_der(&()).unwrap(), b"\x05\x00");
+        assert_eq!(
+            to_der(&vec![1u8, 2]).unwrap(),
+            b"\x30\x06\x02\x01\x01\x02\x01\x02"
+        );
+        assert_eq!(
+            to_der(&(1u64, "a", Some(true), None::<u8>)).unwrap(),
+            b"\x30\x0b\x02\x01\x01\x0c\x01a\xa2\x03\x01\x01\xff"
+        );
+        let mut map = alloc::collections::BTreeMap::new();
+        map.insert("a", 1u8);
+        assert_eq!(
+            to_der(&map).unwrap(),
+            b"\x30\x08\x30\x06\x0c\x01a\x02\x01\x01"
+        );
+    }
+
+    #[test]
+    fn test_to_der_choice() {
+        assert_eq!(to_der(&Ok::<u8, ()>(5)).unwrap(), b"\xa0\x03\x02\x01\x05");
+        assert_eq!(to_der(&Err::<u8, ()>(())).unwrap(), b"\xa1\x02\x05\x00");
+        let options = SerdeOptions::new().choice_tag("Result", "Err", 3);
+        assert_eq!(
+            to_der_with_options(&Err::<u8, ()>(()), &options).unwrap(),
+            b"\xa3\x02\x05\x00"
+        );
+    }
+
+    #[test]
+    fn test_to_der_crate_types() {
+        let oid = ObjectIdentifier::from_string("1.2.840.113549").unwrap();
+        assert_eq!(to_der(&oid).unwrap(), crate::write_single(&oid).unwrap());
+        let bits = BitString::new(b"\x6e\x5d\xc0", 6).unwrap();
+        assert_eq!(to_der(&bits).unwrap(), crate::write_single(&bits).unwrap());
+        let dt = crate::DateTime::new(2021, 3, 4, 5, 6, 7).unwrap();
+        let utc = UtcTime::new(dt).unwrap();
+        assert_eq!(to_der(&utc).unwrap(), crate::write_single(&utc).unwrap());
+        let generalized = GeneralizedTime::new(dt.with_nanosecond(500_000_000).unwrap());
+        assert_eq!(
+            to_der(&generalized).unwrap(),
+            crate::write_single(&generalized).unwrap()
+        );
+        let n = OwnedBigUint::new(b"\x00\xff\x00\x00\x00\x00\x00\x00\x00\x01".to_vec()).unwrap();
+        assert_eq!(to_der(&n).unwrap(), crate::write_single(&n).unwrap());
+    }
+
+    #[test]
+    fn test_to_der_errors() {
+        assert!(matches!(to_der(&Some(1u8)), Err(SerdeError::Message(_))));
+        assert!(matches!(
+            to_der(&vec![None::<u8>]),
+            Err(SerdeError::Message(_))
+        ));
+        let _: Vec<u8> = to_der(&(None::<u8>,)).unwrap();
+    }
+}
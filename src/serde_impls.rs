@@ -0,0 +1,402 @@
+use crate::types::{SimpleAsn1Readable, SimpleAsn1Writable};
+use crate::{
+    BigUint, BitString, DateTime, GeneralizedTime, ObjectIdentifier, OwnedBigUint, OwnedBitString,
+    Tag, UtcTime, WriteBuf,
+};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+// The crate's types are serialized as newtype structs with these names,
+// wrapping their text form (e.g. "1.2.840.113549" for an `ObjectIdentifier`).
+// Most formats treat a newtype struct as the value it wraps, but the DER
+// `Serializer` and `Deserializer` recognize these names and use the type's
+// DER encoding instead.
+const OBJECT_IDENTIFIER: &str = "$asn1::ObjectIdentifier";
+const BIT_STRING: &str = "$asn1::BitString";
+const UTC_TIME: &str = "$asn1::UtcTime";
+const GENERALIZED_TIME: &str = "$asn1::GeneralizedTime";
+const BIG_UINT: &str = "$asn1::BigUint";
+
+/// One of the crate's types, as identified by the name it serializes with.
+#[derive(Clone, Copy)]
+pub(crate) enum Special {
+    ObjectIdentifier,
+    BitString,
+    UtcTime,
+    GeneralizedTime,
+    BigUint,
+}
+
+impl Special {
+    pub(crate) fn from_name(name: &str) -> Option<Special> {
+        match name {
+            OBJECT_IDENTIFIER => Some(Special::ObjectIdentifier),
+            BIT_STRING => Some(Special::BitString),
+            UTC_TIME => Some(Special::UtcTime),
+            GENERALIZED_TIME => Some(Special::GeneralizedTime),
+            BIG_UINT => Some(Special::BigUint),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn tag(self) -> Tag {
+        match self {
+            Special::ObjectIdentifier => <ObjectIdentifier as SimpleAsn1Writable>::TAG,
+            Special::BitString => <BitString as SimpleAsn1Writable>::TAG,
+            Special::UtcTime => <UtcTime as SimpleAsn1Writable>::TAG,
+            Special::GeneralizedTime => <GeneralizedTime as SimpleAsn1Writable>::TAG,
+            Special::BigUint => <BigUint as SimpleAsn1Writable>::TAG,
+        }
+    }
+
+    /// Converts the text form of a value to the contents of its DER
+    /// encoding.
+    pub(crate) fn text_to_der(self, text: &str) -> Option<Vec<u8>> {
+        let mut data = WriteBuf::new();
+        match self {
+            Special::ObjectIdentifier => ObjectIdentifier::from_string(text)?.write_data(&mut data),
+            Special::BitString => parse_bits(text)?.write_data(&mut data),
+            Special::UtcTime => UtcTime::new(parse_time(text)?)?.write_data(&mut data),
+            Special::GeneralizedTime => {
+                GeneralizedTime::new(parse_time(text)?).write_data(&mut data)
+            }
+            Special::BigUint => parse_decimal(text)?.write_data(&mut data),
+        }
+        .ok()?;
+        Some(data.into_vec())
+    }
+
+    /// Converts the contents of a value's DER encoding to its text form.
+    pub(crate) fn der_to_text(self, data: &[u8]) -> Option<String> {
+        Some(match self {
+            Special::ObjectIdentifier => from_der::<ObjectIdentifier>(data)?.to_string(),
+            Special::BitString => Bits(BitString::parse_data(data).ok()?).to_string(),
+            Special::UtcTime => Rfc3339(from_der::<UtcTime>(data)?.as_datetime()).to_string(),
+            Special::GeneralizedTime => {
+                Rfc3339(from_der::<GeneralizedTime>(data)?.as_datetime()).to_string()
+            }
+            Special::BigUint => Decimal(BigUint::parse_data(data).ok()?.as_bytes()).to_string(),
+        })
+    }
+}
+
+// Serializes the text form of a value, as a newtype struct named `name`.
+fn serialize_text<S: Serializer, T: fmt::Display>(
+    serializer: S,
+    name: &'static str,
+    value: T,
+) -> Result<S::Ok, S::Error> {
+    struct Text<T>(T);
+
+    impl<T: fmt::Display> Serialize for Text<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(&self.0)
+        }
+    }
+
+    serializer.serialize_newtype_struct(name, &Text(value))
+}
+
+// Accepts either the text form of a value, or (from the DER `Deserializer`)
+// the contents of its DER encoding as bytes.
+struct TextOrDerVisitor<T> {
+    expecting: &'static str,
+    from_text: fn(&str) -> Option<T>,
+    from_der: fn(&[u8]) -> Option<T>,
+}
+
+impl<'de, T> Visitor<'de> for TextOrDerVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        (self.from_text)(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+        (self.from_der)(v).ok_or_else(|| E::invalid_value(Unexpected::Bytes(v), &self))
+    }
+}
+
+fn deserialize_text<'de, D: Deserializer<'de>, T>(
+    deserializer: D,
+    name: &'static str,
+    visitor: TextOrDerVisitor<T>,
+) -> Result<T, D::Error> {
+    deserializer.deserialize_newtype_struct(name, visitor)
+}
+
+fn from_der<T: for<'a> SimpleAsn1Readable<'a>>(data: &[u8]) -> Option<T> {
+    T::parse_data(data).ok()
+}
+
+impl Serialize for ObjectIdentifier {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(serializer, OBJECT_IDENTIFIER, self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ObjectIdentifier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = TextOrDerVisitor {
+            expecting: "a dotted OBJECT IDENTIFIER",
+            from_text: ObjectIdentifier::from_string,
+            from_der,
+        };
+        deserialize_text(deserializer, OBJECT_IDENTIFIER, visitor)
+    }
+}
+
+// The text form of a `BIT STRING` is its bits, e.g. "0110".
+struct Bits<'a>(BitString<'a>);
+
+impl fmt::Display for Bits<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = self.0.as_bytes().len() * 8 - usize::from(self.0.padding_bits());
+        for i in 0..len {
+            f.write_str(if self.0.has_bit_set(i) { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_bits(text: &str) -> Option<OwnedBitString> {
+    let mut data = vec![];
+    for chunk in text.as_bytes().chunks(8) {
+        let mut byte = 0;
+        for (i, c) in chunk.iter().enumerate() {
+            match c {
+                b'0' => {}
+                b'1' => byte |= 0x80 >> i,
+                _ => return None,
+            }
+        }
+        data.push(byte);
+    }
+    let padding_bits = data.len() * 8 - text.len();
+    OwnedBitString::new(data, padding_bits as u8)
+}
+
+impl Serialize for BitString<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(serializer, BIT_STRING, Bits(self.clone()))
+    }
+}
+
+impl Serialize for OwnedBitString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(serializer, BIT_STRING, Bits(self.as_bitstring()))
+    }
+}
+
+impl<'de> Deserialize<'de> for OwnedBitString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = TextOrDerVisitor {
+            expecting: "a BIT STRING of 0s and 1s",
+            from_text: parse_bits,
+            from_der,
+        };
+        deserialize_text(deserializer, BIT_STRING, visitor)
+    }
+}
+
+// The text form of a time is in RFC 3339 format, in UTC, e.g.
+// "2021-03-04T05:06:07Z" or "2021-03-04T05:06:07.5Z".
+struct Rfc3339<'a>(&'a DateTime);
+
+impl fmt::Display for Rfc3339<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let v = self.0;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            v.year(),
+            v.month(),
+            v.day(),
+            v.hour(),
+            v.minute(),
+            v.second()
+        )?;
+        if v.nanosecond() != 0 {
+            let fraction = alloc::format!("{:09}", v.nanosecond());
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        f.write_str("Z")
+    }
+}
+
+fn parse_time(text: &str) -> Option<DateTime> {
+    fn digits(data: &[u8]) -> Option<u32> {
+        data.iter().try_fold(0u32, |acc, &c| match c {
+            b'0'..=b'9' => Some(acc * 10 + u32::from(c - b'0')),
+            _ => None,
+        })
+    }
+
+    let data = text.as_bytes();
+    if data.len() < 20 || data[data.len() - 1] != b'Z' {
+        return None;
+    }
+    for (i, separator) in &[(4, b'-'), (7, b'-'), (10, b'T'), (13, b':'), (16, b':')] {
+        if data[*i] != *separator {
+            return None;
+        }
+    }
+    let v = DateTime::new(
+        digits(&data[0..4])? as u16,
+        digits(&data[5..7])? as u8,
+        digits(&data[8..10])? as u8,
+        digits(&data[11..13])? as u8,
+        digits(&data[14..16])? as u8,
+        digits(&data[17..19])? as u8,
+    )?;
+
+    let fraction = &data[19..data.len() - 1];
+    if fraction.is_empty() {
+        return Some(v);
+    }
+    if fraction[0] != b'.' || !(2..=10).contains(&fraction.len()) {
+        return None;
+    }
+    let nanosecond = digits(&fraction[1..])? * 10u32.pow(10 - fraction.len() as u32);
+    v.with_nanosecond(nanosecond)
+}
+
+impl Serialize for UtcTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(serializer, UTC_TIME, Rfc3339(self.as_datetime()))
+    }
+}
+
+impl<'de> Deserialize<'de> for UtcTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = TextOrDerVisitor {
+            expecting: "an RFC 3339 time between 1950 and 2049",
+            from_text: |text| UtcTime::new(parse_time(text)?),
+            from_der,
+        };
+        deserialize_text(deserializer, UTC_TIME, visitor)
+    }
+}
+
+impl Serialize for GeneralizedTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(serializer, GENERALIZED_TIME, Rfc3339(self.as_datetime()))
+    }
+}
+
+impl<'de> Deserialize<'de> for GeneralizedTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = TextOrDerVisitor {
+            expecting: "an RFC 3339 time",
+            from_text: |text| Some(GeneralizedTime::new(parse_time(text)?)),
+            from_der,
+        };
+        deserialize_text(deserializer, GENERALIZED_TIME, visitor)
+    }
+}
+
+/// `DateTime`s are serialized as a `GeneralizedTime`.
+impl Serialize for DateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(serializer, GENERALIZED_TIME, Rfc3339(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = TextOrDerVisitor {
+            expecting: "an RFC 3339 time",
+            from_text: parse_time,
+            from_der: |data| Some(*from_der::<GeneralizedTime>(data)?.as_datetime()),
+        };
+        deserialize_text(deserializer, GENERALIZED_TIME, visitor)
+    }
+}
+
+// The text form of an `INTEGER` is in decimal, as a string so that it isn't
+// limited to the range of the format's numbers.
+struct Decimal<'a>(&'a [u8]);
+
+impl fmt::Display for Decimal<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut n = self.0.to_vec();
+        let mut digits = vec![];
+        loop {
+            // Divide `n` by 10, collecting the remainder as the next digit.
+            let mut remainder = 0;
+            for b in n.iter_mut() {
+                let v = (remainder << 8) | u32::from(*b);
+                *b = (v / 10) as u8;
+                remainder = v % 10;
+            }
+            digits.push(b'0' + remainder as u8);
+            if n.iter().all(|&b| b == 0) {
+                break;
+            }
+        }
+        digits.reverse();
+        f.write_str(core::str::from_utf8(&digits).unwrap())
+    }
+}
+
+fn parse_decimal(text: &str) -> Option<OwnedBigUint> {
+    if text.is_empty() {
+        return None;
+    }
+    let mut n = vec![0u8];
+    for c in text.bytes() {
+        let mut carry = match c {
+            b'0'..=b'9' => u32::from(c - b'0'),
+            _ => return None,
+        };
+        for b in n.iter_mut().rev() {
+            let v = u32::from(*b) * 10 + carry;
+            *b = v as u8;
+            carry = v >> 8;
+        }
+        if carry != 0 {
+            n.insert(0, carry as u8);
+        }
+    }
+    let first = n.iter().position(|&b| b != 0).unwrap_or(n.len() - 1);
+    let mut data = n.split_off(first);
+    if data[0] & 0x80 != 0 {
+        data.insert(0, 0);
+    }
+    OwnedBigUint::new(data)
+}
+
+impl Serialize for BigUint<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(serializer, BIG_UINT, Decimal(self.as_bytes()))
+    }
+}
+
+impl Serialize for OwnedBigUint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_text(serializer, BIG_UINT, Decimal(self.as_bytes()))
+    }
+}
+
+impl<'de> Deserialize<'de> for OwnedBigUint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = TextOrDerVisitor {
+            expecting: "a non-negative decimal integer",
+            from_text: parse_decimal,
+            from_der,
+        };
+        deserialize_text(deserializer, BIG_UINT, visitor)
+    }
+}
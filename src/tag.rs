@@ -0,0 +1,273 @@
+use crate::object_identifier::_write_base128_int;
+use crate::parser::{ParseError, ParseErrorKind, ParseResult};
+use crate::writer::{WriteBuf, WriteResult};
+use core::fmt;
+
+const HIGH_TAG_NUMBER: u8 = 0x1f;
+const CONSTRUCTED: u8 = 0x20;
+
+/// The class portion of an ASN.1 tag.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum TagClass {
+    Universal = 0b00,
+    Application = 0b01,
+    ContextSpecific = 0b10,
+    Private = 0b11,
+}
+
+/// An ASN.1 tag: its class, whether the encoding is constructed, and its
+/// number. Tag numbers of 31 or larger are encoded using the multi-octet
+/// identifier form.
+///
+/// Tags are ordered by class and then by number, which is the canonical
+/// order DER uses for the elements of a `SET`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Tag {
+    class: TagClass,
+    value: u32,
+    constructed: bool,
+}
+
+impl Tag {
+    /// Creates a new `Tag` from its parts.
+    pub const fn new(value: u32, class: TagClass, constructed: bool) -> Tag {
+        Tag {
+            class,
+            value,
+            constructed,
+        }
+    }
+
+    /// A primitive tag in the universal class.
+    pub const fn primitive(value: u32) -> Tag {
+        Tag::new(value, TagClass::Universal, false)
+    }
+
+    /// A constructed tag in the universal class.
+    pub const fn constructed(value: u32) -> Tag {
+        Tag::new(value, TagClass::Universal, true)
+    }
+
+    /// Parses a DER encoded identifier from the start of `data`, returning
+    /// the `Tag` and the remaining data.
+    pub(crate) fn from_bytes(data: &[u8]) -> ParseResult<(Tag, &[u8])> {
+        let (&first, mut data) = data
+            .split_first()
+            .ok_or_else(|| ParseError::new(ParseErrorKind::ShortData))?;
+
+        let class = match first >> 6 {
+            0b00 => TagClass::Universal,
+            0b01 => TagClass::Application,
+            0b10 => TagClass::ContextSpecific,
+            _ => TagClass::Private,
+        };
+        let constructed = first & CONSTRUCTED == CONSTRUCTED;
+        let mut value = u32::from(first & HIGH_TAG_NUMBER);
+
+        if value == u32::from(HIGH_TAG_NUMBER) {
+            value = 0;
+            loop {
+                let (&b, rest) = data
+                    .split_first()
+                    .ok_or_else(|| ParseError::new(ParseErrorKind::ShortData))?;
+                data = rest;
+                // The first subsequent octet may not have a value of 0 (i.e.
+                // the tag number must be minimally encoded).
+                if value == 0 && b == 0x80 {
+                    return Err(ParseError::new(ParseErrorKind::InvalidValue));
+                }
+                if value > (u32::MAX >> 7) {
+                    return Err(ParseError::new(ParseErrorKind::IntegerOverflow));
+                }
+                value = (value << 7) | u32::from(b & 0x7f);
+                if b & 0x80 == 0 {
+                    break;
+                }
+            }
+            // Tag numbers which fit in the low tag number form must use it.
+            if value < u32::from(HIGH_TAG_NUMBER) {
+                return Err(ParseError::new(ParseErrorKind::InvalidValue));
+            }
+        }
+
+        Ok((
+            Tag {
+                class,
+                value,
+                constructed,
+            },
+            data,
+        ))
+    }
+
+    /// If this tag fits in a single identifier octet, returns that octet.
+    pub fn as_u8(&self) -> Option<u8> {
+        if self.value >= u32::from(HIGH_TAG_NUMBER) {
+            return None;
+        }
+        Some(self.initial_octet() | self.value as u8)
+    }
+
+    fn initial_octet(&self) -> u8 {
+        let mut b = (self.class as u8) << 6;
+        if self.constructed {
+            b |= CONSTRUCTED;
+        }
+        b
+    }
+
+    // The number of bytes `write_bytes` writes.
+    #[inline]
+    pub(crate) fn encoded_len(&self) -> usize {
+        if self.as_u8().is_some() {
+            1
+        } else {
+            let mut buf = [0; 5];
+            1 + _write_base128_int(&mut buf, u128::from(self.value)).unwrap()
+        }
+    }
+
+    pub(crate) fn write_bytes(&self, dest: &mut WriteBuf) -> WriteResult {
+        if let Some(b) = self.as_u8() {
+            dest.push_byte(b)
+        } else {
+            dest.push_byte(self.initial_octet() | HIGH_TAG_NUMBER)?;
+            let mut buf = [0; 5];
+            let n = _write_base128_int(&mut buf, u128::from(self.value)).unwrap();
+            dest.push_slice(&buf[..n])
+        }
+    }
+
+    /// The class of the tag.
+    pub const fn class(&self) -> TagClass {
+        self.class
+    }
+
+    /// Whether the tag's value is encoded in constructed form.
+    pub const fn is_constructed(&self) -> bool {
+        self.constructed
+    }
+
+    /// The tag number.
+    pub const fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+/// Formats the tag the way it's written in ASN.1 notation, e.g. `[0]` or
+/// `[UNIVERSAL 12]`.
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.class {
+            TagClass::Universal => write!(f, "[UNIVERSAL {}]", self.value),
+            TagClass::Application => write!(f, "[APPLICATION {}]", self.value),
+            TagClass::ContextSpecific => write!(f, "[{}]", self.value),
+            TagClass::Private => write!(f, "[PRIVATE {}]", self.value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Tag, TagClass};
+    use crate::{ParseError, ParseErrorKind, WriteBuf};
+
+    #[test]
+    fn test_tag_from_bytes() {
+        for (data, expected) in [
+            (&b"\x02"[..], Ok((Tag::primitive(2), &b""[..]))),
+            (b"\x30\x00", Ok((Tag::constructed(0x10), b"\x00"))),
+            (
+                b"\xa5",
+                Ok((Tag::new(5, TagClass::ContextSpecific, true), b"")),
+            ),
+            (
+                b"\x7e",
+                Ok((Tag::new(30, TagClass::Application, true), b"")),
+            ),
+            (
+                b"\xdf\x1f",
+                Ok((Tag::new(31, TagClass::Private, false), b"")),
+            ),
+            (
+                b"\x9f\x81\x00\x01",
+                Ok((Tag::new(128, TagClass::ContextSpecific, false), b"\x01")),
+            ),
+            (
+                b"\x5f\x8f\xff\xff\xff\x7f",
+                Ok((Tag::new(u32::MAX, TagClass::Application, false), b"")),
+            ),
+            (b"", Err(ParseError::new(ParseErrorKind::ShortData))),
+            (b"\x1f", Err(ParseError::new(ParseErrorKind::ShortData))),
+            (b"\x1f\x81", Err(ParseError::new(ParseErrorKind::ShortData))),
+            (
+                b"\x1f\x80\x20",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x1f\x1e",
+                Err(ParseError::new(ParseErrorKind::InvalidValue)),
+            ),
+            (
+                b"\x1f\x90\x80\x80\x80\x00",
+                Err(ParseError::new(ParseErrorKind::IntegerOverflow)),
+            ),
+        ] {
+            assert_eq!(Tag::from_bytes(data), expected);
+        }
+    }
+
+    #[test]
+    fn test_tag_write_bytes() {
+        for (tag, expected) in [
+            (Tag::primitive(2), &b"\x02"[..]),
+            (Tag::constructed(0x10), b"\x30"),
+            (Tag::new(30, TagClass::Application, true), b"\x7e"),
+            (Tag::new(31, TagClass::Private, false), b"\xdf\x1f"),
+            (
+                Tag::new(128, TagClass::ContextSpecific, true),
+                b"\xbf\x81\x00",
+            ),
+            (
+                Tag::new(u32::MAX, TagClass::Application, false),
+                b"\x5f\x8f\xff\xff\xff\x7f",
+            ),
+        ] {
+            let mut buf = WriteBuf::new();
+            tag.write_bytes(&mut buf).unwrap();
+            assert_eq!(buf.as_slice(), expected);
+        }
+    }
+
+    #[test]
+    fn test_tag_as_u8() {
+        assert_eq!(Tag::primitive(2).as_u8(), Some(0x02));
+        assert_eq!(
+            Tag::new(30, TagClass::ContextSpecific, true).as_u8(),
+            Some(0xbe)
+        );
+        assert_eq!(Tag::primitive(31).as_u8(), None);
+    }
+
+    #[test]
+    fn test_tag_display() {
+        for (tag, expected) in [
+            (Tag::primitive(12), "[UNIVERSAL 12]"),
+            (Tag::new(3, TagClass::Application, true), "[APPLICATION 3]"),
+            (Tag::new(0, TagClass::ContextSpecific, true), "[0]"),
+            (Tag::new(200, TagClass::Private, false), "[PRIVATE 200]"),
+        ] {
+            assert_eq!(alloc::format!("{}", tag), expected);
+        }
+    }
+
+    #[test]
+    fn test_tag_ordering() {
+        assert!(Tag::primitive(5) < Tag::constructed(0x10));
+        assert!(Tag::constructed(1) < Tag::primitive(2));
+        assert!(Tag::primitive(200) < Tag::new(0, TagClass::Application, false));
+        assert!(
+            Tag::new(3, TagClass::ContextSpecific, true) < Tag::new(0, TagClass::Private, false)
+        );
+    }
+}
@@ -0,0 +1,372 @@
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A date and time in UTC, with nanosecond precision, as used by
+/// [`UtcTime`](crate::UtcTime) and [`GeneralizedTime`](crate::GeneralizedTime).
+///
+/// Years from 0 to 9999 are supported, which is the range `GeneralizedTime`
+/// can encode. Leap seconds are not, as ASN.1 doesn't allow them.
+///
+/// Conversions to and from `chrono` and `time` types are available with the
+/// `chrono` and `time` features, and to and from `std::time::SystemTime`
+/// with the `std` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+}
+
+fn is_leap_year(year: u16) -> bool {
+    match (year % 4, year % 100, year % 400) {
+        (_, _, 0) => true,
+        (_, 0, _) => false,
+        (0, _, _) => true,
+        _ => false,
+    }
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// The number of days between 1970-01-01 and the given date, from
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// The inverse of `days_from_civil`, from
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+impl DateTime {
+    /// Creates a new `DateTime`, with a nanosecond of 0. Returns `None` if
+    /// the date or time is invalid, or the year is larger than 9999.
+    pub fn new(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Option<DateTime> {
+        if year > 9999
+            || !(1..=12).contains(&month)
+            || !(1..=days_in_month(year, month)).contains(&day)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+        Some(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond: 0,
+        })
+    }
+
+    /// Returns a copy of this `DateTime` with its nanosecond replaced.
+    /// Returns `None` if `nanosecond` is a second or more.
+    pub fn with_nanosecond(self, nanosecond: u32) -> Option<DateTime> {
+        if nanosecond >= 1_000_000_000 {
+            return None;
+        }
+        Some(DateTime { nanosecond, ..self })
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    pub(crate) fn from_unix_timestamp(seconds: i64, nanosecond: u32) -> Option<DateTime> {
+        let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+        let seconds = seconds.rem_euclid(SECONDS_PER_DAY);
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        DateTime::new(
+            year as u16,
+            month as u8,
+            day as u8,
+            (seconds / 3600) as u8,
+            (seconds / 60 % 60) as u8,
+            (seconds % 60) as u8,
+        )?
+        .with_nanosecond(nanosecond)
+    }
+
+    pub(crate) fn unix_timestamp(&self) -> i64 {
+        let days = days_from_civil(
+            i64::from(self.year),
+            i64::from(self.month),
+            i64::from(self.day),
+        );
+        days * SECONDS_PER_DAY
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+    }
+
+    /// Converts a `chrono::DateTime` to a `DateTime`. Returns `None` if its
+    /// year is outside of 0 to 9999, or it is a leap second.
+    #[cfg(feature = "chrono")]
+    pub fn from_chrono<Tz: chrono::TimeZone>(v: &chrono::DateTime<Tz>) -> Option<DateTime> {
+        DateTime::from_unix_timestamp(v.timestamp(), v.timestamp_subsec_nanos())
+    }
+
+    /// Converts this `DateTime` to a `chrono::DateTime<Utc>`.
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(&self) -> chrono::DateTime<chrono::Utc> {
+        let v = chrono::NaiveDate::from_ymd_opt(
+            i32::from(self.year),
+            u32::from(self.month),
+            u32::from(self.day),
+        )
+        .and_then(|d| {
+            d.and_hms_nano_opt(
+                u32::from(self.hour),
+                u32::from(self.minute),
+                u32::from(self.second),
+                self.nanosecond,
+            )
+        })
+        .unwrap();
+        chrono::TimeZone::from_utc_datetime(&chrono::Utc, &v)
+    }
+
+    /// Converts a `time::OffsetDateTime` to a `DateTime`. Returns `None` if
+    /// its year (in UTC) is outside of 0 to 9999.
+    #[cfg(feature = "time")]
+    pub fn from_offset_date_time(v: ::time::OffsetDateTime) -> Option<DateTime> {
+        DateTime::from_unix_timestamp(v.unix_timestamp(), v.nanosecond())
+    }
+
+    /// Converts this `DateTime` to a `time::OffsetDateTime` in UTC.
+    #[cfg(feature = "time")]
+    pub fn to_offset_date_time(&self) -> ::time::OffsetDateTime {
+        let nanos = i128::from(self.unix_timestamp()) * 1_000_000_000 + i128::from(self.nanosecond);
+        ::time::OffsetDateTime::from_unix_timestamp_nanos(nanos).unwrap()
+    }
+
+    /// Converts a `SystemTime` to a `DateTime`. Returns `None` if its year is
+    /// outside of 0 to 9999.
+    #[cfg(feature = "std")]
+    pub fn from_system_time(v: std::time::SystemTime) -> Option<DateTime> {
+        let (seconds, nanosecond) = match v.duration_since(std::time::UNIX_EPOCH) {
+            Ok(d) => (d.as_secs() as i128, d.subsec_nanos()),
+            Err(e) => {
+                let d = e.duration();
+                match d.subsec_nanos() {
+                    0 => (-(d.as_secs() as i128), 0),
+                    nanos => (-(d.as_secs() as i128) - 1, 1_000_000_000 - nanos),
+                }
+            }
+        };
+        let seconds = core::convert::TryInto::try_into(seconds).ok()?;
+        DateTime::from_unix_timestamp(seconds, nanosecond)
+    }
+
+    /// Converts this `DateTime` to a `SystemTime`.
+    #[cfg(feature = "std")]
+    pub fn to_system_time(&self) -> std::time::SystemTime {
+        let seconds = self.unix_timestamp();
+        let nanos = std::time::Duration::from_nanos(u64::from(self.nanosecond));
+        if seconds >= 0 {
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(seconds as u64) + nanos
+        } else {
+            std::time::UNIX_EPOCH - std::time::Duration::from_secs((-seconds) as u64) + nanos
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DateTime;
+
+    #[test]
+    fn test_date_time_new() {
+        assert!(DateTime::new(2000, 2, 29, 23, 59, 59).is_some());
+        assert!(DateTime::new(0, 1, 1, 0, 0, 0).is_some());
+        assert!(DateTime::new(9999, 12, 31, 0, 0, 0).is_some());
+
+        assert!(DateTime::new(10000, 1, 1, 0, 0, 0).is_none());
+        assert!(DateTime::new(1900, 2, 29, 0, 0, 0).is_none());
+        assert!(DateTime::new(2001, 2, 29, 0, 0, 0).is_none());
+        assert!(DateTime::new(2001, 4, 31, 0, 0, 0).is_none());
+        assert!(DateTime::new(2001, 0, 1, 0, 0, 0).is_none());
+        assert!(DateTime::new(2001, 13, 1, 0, 0, 0).is_none());
+        assert!(DateTime::new(2001, 1, 0, 0, 0, 0).is_none());
+        assert!(DateTime::new(2001, 1, 1, 24, 0, 0).is_none());
+        assert!(DateTime::new(2001, 1, 1, 0, 60, 0).is_none());
+        assert!(DateTime::new(2001, 1, 1, 0, 0, 60).is_none());
+
+        let dt = DateTime::new(2001, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            dt.with_nanosecond(999_999_999).unwrap().nanosecond(),
+            999_999_999
+        );
+        assert!(dt.with_nanosecond(1_000_000_000).is_none());
+    }
+
+    #[test]
+    fn test_date_time_unix_timestamp() {
+        for &(ts, (year, month, day, hour, minute, second)) in &[
+            (0, (1970, 1, 1, 0, 0, 0)),
+            (1_258_325_776, (2009, 11, 15, 22, 56, 16)),
+            (951_782_400, (2000, 2, 29, 0, 0, 0)),
+            (-1, (1969, 12, 31, 23, 59, 59)),
+            (-62_167_219_200, (0, 1, 1, 0, 0, 0)),
+            (253_402_300_799, (9999, 12, 31, 23, 59, 59)),
+        ] {
+            let dt = DateTime::new(year, month, day, hour, minute, second).unwrap();
+            assert_eq!(dt.unix_timestamp(), ts);
+            assert_eq!(DateTime::from_unix_timestamp(ts, 0), Some(dt));
+        }
+
+        assert_eq!(DateTime::from_unix_timestamp(-62_167_219_201, 0), None);
+        assert_eq!(DateTime::from_unix_timestamp(253_402_300_800, 0), None);
+        assert_eq!(DateTime::from_unix_timestamp(i64::MAX, 0), None);
+        assert_eq!(DateTime::from_unix_timestamp(i64::MIN, 0), None);
+        assert_eq!(DateTime::from_unix_timestamp(0, 1_000_000_000), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_date_time_chrono() {
+        use chrono::TimeZone;
+
+        let dt = DateTime::new(2009, 11, 15, 22, 56, 16)
+            .unwrap()
+            .with_nanosecond(123)
+            .unwrap();
+        let v = chrono::Utc.timestamp_opt(1_258_325_776, 123).unwrap();
+        assert_eq!(dt.to_chrono(), v);
+        assert_eq!(DateTime::from_chrono(&v), Some(dt));
+        assert_eq!(
+            DateTime::from_chrono(&v.with_timezone(&chrono::FixedOffset::east_opt(3600).unwrap())),
+            Some(dt)
+        );
+
+        assert_eq!(
+            DateTime::from_chrono(&chrono::Utc.with_ymd_and_hms(10000, 1, 1, 0, 0, 0).unwrap()),
+            None
+        );
+        // Leap seconds
+        let v = chrono::NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 1_500_000_000)
+            .unwrap();
+        assert_eq!(
+            DateTime::from_chrono(&chrono::Utc.from_utc_datetime(&v)),
+            None
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_date_time_time() {
+        let dt = DateTime::new(2009, 11, 15, 22, 56, 16)
+            .unwrap()
+            .with_nanosecond(123)
+            .unwrap();
+        let v =
+            ::time::OffsetDateTime::from_unix_timestamp_nanos(1_258_325_776_000_000_123).unwrap();
+        assert_eq!(dt.to_offset_date_time(), v);
+        assert_eq!(DateTime::from_offset_date_time(v), Some(dt));
+        assert_eq!(
+            DateTime::from_offset_date_time(
+                v.to_offset(::time::UtcOffset::from_hms(-5, 0, 0).unwrap())
+            ),
+            Some(dt)
+        );
+
+        let v = ::time::OffsetDateTime::from_unix_timestamp(-62_167_219_201).unwrap();
+        assert_eq!(DateTime::from_offset_date_time(v), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_date_time_system_time() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        for &(v, (year, month, day, hour, minute, second), nanosecond) in &[
+            (
+                UNIX_EPOCH + Duration::new(1_258_325_776, 123),
+                (2009, 11, 15, 22, 56, 16),
+                123,
+            ),
+            (
+                UNIX_EPOCH - Duration::new(1, 0),
+                (1969, 12, 31, 23, 59, 59),
+                0,
+            ),
+            (
+                UNIX_EPOCH - Duration::new(0, 1),
+                (1969, 12, 31, 23, 59, 59),
+                999_999_999,
+            ),
+        ] {
+            let dt = DateTime::new(year, month, day, hour, minute, second)
+                .unwrap()
+                .with_nanosecond(nanosecond)
+                .unwrap();
+            assert_eq!(DateTime::from_system_time(v), Some(dt));
+            assert_eq!(dt.to_system_time(), v);
+        }
+    }
+}
@@ -1,31 +1,36 @@
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
 use alloc::vec;
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 use core::borrow::Borrow;
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 use core::hash::{Hash, Hasher};
 use core::marker::PhantomData;
 use core::mem;
+#[cfg(feature = "alloc")]
+use core::ops::Range;
 
-use chrono::{Datelike, TimeZone, Timelike};
-
-use crate::writer::Writer;
+use crate::writer::{_tlv_len, WriteBuf, WriteError, WriteResult, Writer};
+#[cfg(feature = "alloc")]
+use crate::OwnedBitString;
 use crate::{
-    parse, parse_single, BitString, ObjectIdentifier, OwnedBitString, ParseError, ParseErrorKind,
-    ParseLocation, ParseResult, Parser,
+    parse, parse_single, BitString, DateTime, ObjectIdentifier, ParseError, ParseErrorKind,
+    ParseLocation, ParseResult, Parser, Real, RelativeOid, Tag,
 };
 
-pub(crate) const CONTEXT_SPECIFIC: u8 = 0x80;
-pub(crate) const CONSTRUCTED: u8 = 0x20;
-
 /// Any type that can be parsed as DER ASN.1.
 pub trait Asn1Readable<'a>: Sized {
     fn parse(parser: &mut Parser<'a>) -> ParseResult<Self>;
-    fn can_parse(tag: u8) -> bool;
+    fn can_parse(tag: Tag) -> bool;
 }
 
 /// Types with a fixed-tag that can be parsed as DER ASN.1
 pub trait SimpleAsn1Readable<'a>: Sized {
-    const TAG: u8;
+    const TAG: Tag;
 
     fn parse_data(data: &'a [u8]) -> ParseResult<Self>;
 }
@@ -43,42 +48,195 @@ impl<'a, T: SimpleAsn1Readable<'a>> Asn1Readable<'a> for T {
     }
 
     #[inline]
-    fn can_parse(tag: u8) -> bool {
+    fn can_parse(tag: Tag) -> bool {
         tag == Self::TAG
     }
 }
 
 /// Any type that can be written as DER ASN.1.
 pub trait Asn1Writable<'a>: Sized {
-    fn write(&self, dest: &mut Writer);
+    fn write(&self, dest: &mut Writer) -> WriteResult;
+
+    /// Returns the number of bytes `write` will write, if it can be computed
+    /// without writing them. When lengths are known, they are written before
+    /// the values they're for, rather than the values being moved afterwards
+    /// to make room for them.
+    ///
+    /// If this returns `Some`, it must be exactly the number of bytes that
+    /// are written, otherwise writing the value fails with
+    /// [`WriteError::InvalidValue`].
+    fn encoded_len(&self) -> Option<usize> {
+        None
+    }
 }
 
 // Types with a fixed-tag that can be written as DER ASN.1.
 pub trait SimpleAsn1Writable<'a>: Sized {
-    const TAG: u8;
+    const TAG: Tag;
+
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult;
 
-    fn write_data(&self, dest: &mut Vec<u8>);
+    /// Returns the number of bytes `write_data` will write, if it can be
+    /// computed without writing them. See [`Asn1Writable::encoded_len`].
+    fn data_len(&self) -> Option<usize> {
+        None
+    }
 }
 
 impl<'a, T: SimpleAsn1Writable<'a>> Asn1Writable<'a> for T {
     #[inline]
-    fn write(&self, w: &mut Writer) {
-        w.write_tlv(Self::TAG, move |dest| self.write_data(dest));
+    fn write(&self, w: &mut Writer) -> WriteResult {
+        w.write_tlv_with_len(Self::TAG, self.data_len(), move |dest| {
+            self.write_data(dest)
+        })
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> Option<usize> {
+        _tlv_len(Self::TAG, self.data_len()?)
     }
 }
 
 impl<'a, T: SimpleAsn1Writable<'a>> SimpleAsn1Writable<'a> for &T {
-    const TAG: u8 = T::TAG;
-    fn write_data(&self, dest: &mut Vec<u8>) {
+    const TAG: Tag = T::TAG;
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
         T::write_data(self, dest)
     }
+
+    fn data_len(&self) -> Option<usize> {
+        T::data_len(self)
+    }
+}
+
+// Returns the sum of the lengths of the encodings of `vals`, if they're all
+// known.
+fn encoded_len_sum<'a, 'b, T: Asn1Writable<'a> + 'b, I: IntoIterator<Item = &'b T>>(
+    vals: I,
+) -> Option<usize> {
+    vals.into_iter()
+        .try_fold(0usize, |sum, v| sum.checked_add(v.encoded_len()?))
+}
+
+/// A value whose type is selected by an `OBJECT IDENTIFIER` which precedes it
+/// in the same structure, i.e. ASN.1's `ANY DEFINED BY`. Generally
+/// implemented with `#[derive(asn1::Asn1DefinedByRead)]`.
+pub trait Asn1DefinedByReadable<'a>: Sized {
+    fn parse(oid: &ObjectIdentifier, parser: &mut Parser<'a>) -> ParseResult<Self>;
+}
+
+/// A value whose type is selected by an `OBJECT IDENTIFIER` which precedes it
+/// in the same structure, i.e. ASN.1's `ANY DEFINED BY`. Generally
+/// implemented with `#[derive(asn1::Asn1DefinedByWrite)]`.
+pub trait Asn1DefinedByWritable<'a>: Sized {
+    /// The `OBJECT IDENTIFIER` which selects this value's type.
+    fn oid(&self) -> &ObjectIdentifier;
+    fn write(&self, dest: &mut Writer) -> WriteResult;
+
+    /// Returns the number of bytes `write` will write, if known. See
+    /// [`Asn1Writable::encoded_len`].
+    fn encoded_len(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Stands in for the `OBJECT IDENTIFIER` field referred to by a
+/// `#[defined_by(field)]` attribute. The `OBJECT IDENTIFIER` itself is part
+/// of the `#[defined_by]` field's value, so the two can't disagree.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct DefinedByMarker;
+
+/// A type which can hold the unknown elements at the end of an extensible
+/// `SEQUENCE`, i.e. those in place of its `...` extension marker. Used for
+/// fields annotated with `#[extensions]`.
+pub trait Asn1ExtensionsReadable<'a>: Sized {
+    fn parse(parser: &mut Parser<'a>) -> ParseResult<Self>;
+}
+
+/// A type which can hold the unknown elements at the end of an extensible
+/// `SEQUENCE`, i.e. those in place of its `...` extension marker. Used for
+/// fields annotated with `#[extensions]`.
+pub trait Asn1ExtensionsWritable<'a>: Sized {
+    fn write(&self, dest: &mut Writer) -> WriteResult;
+
+    /// Returns the number of bytes `write` will write, if known. See
+    /// [`Asn1Writable::encoded_len`].
+    fn encoded_len(&self) -> Option<usize> {
+        None
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Asn1ExtensionsReadable<'a> for Vec<Tlv<'a>> {
+    fn parse(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        let mut elements = vec![];
+        while !parser.is_empty() {
+            elements.push(parser.read_element::<Tlv<'a>>()?);
+        }
+        Ok(elements)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Asn1ExtensionsWritable<'a> for Vec<Tlv<'a>> {
+    fn write(&self, w: &mut Writer) -> WriteResult {
+        for el in self {
+            w.write_element(el)?;
+        }
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        encoded_len_sum(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Asn1ExtensionsReadable<'a> for Vec<OwnedTlv> {
+    fn parse(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        let mut elements = vec![];
+        while !parser.is_empty() {
+            elements.push(parser.read_element::<OwnedTlv>()?);
+        }
+        Ok(elements)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Asn1ExtensionsWritable<'a> for Vec<OwnedTlv> {
+    fn write(&self, w: &mut Writer) -> WriteResult {
+        for el in self {
+            w.write_element(el)?;
+        }
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        encoded_len_sum(self)
+    }
+}
+
+/// The encodings of the unknown elements, one after another.
+impl<'a> Asn1ExtensionsReadable<'a> for &'a [u8] {
+    fn parse(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        parser.read_remaining_elements()
+    }
+}
+
+impl<'a> Asn1ExtensionsWritable<'a> for &'a [u8] {
+    fn write(&self, w: &mut Writer) -> WriteResult {
+        w.data.push_slice(self)
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
 }
 
 /// A TLV (type, length, value) represented as the tag and bytes content.
 /// Generally used for parsing ASN.1 `ANY` values.
 #[derive(Debug, PartialEq, PartialOrd, Hash, Clone, Copy)]
 pub struct Tlv<'a> {
-    pub(crate) tag: u8,
+    pub(crate) tag: Tag,
     // `data` is the value of a TLV
     pub(crate) data: &'a [u8],
     // `full_data` contains the encoded type and length, in addition to the
@@ -88,7 +246,7 @@ pub struct Tlv<'a> {
 
 impl<'a> Tlv<'a> {
     /// The tag portion of a TLV.
-    pub fn tag(&self) -> u8 {
+    pub fn tag(&self) -> Tag {
         self.tag
     }
     /// The value portion of the TLV.
@@ -112,14 +270,63 @@ impl<'a> Asn1Readable<'a> for Tlv<'a> {
     }
 
     #[inline]
-    fn can_parse(_tag: u8) -> bool {
+    fn can_parse(_tag: Tag) -> bool {
         true
     }
 }
 impl<'a> Asn1Writable<'a> for Tlv<'a> {
     #[inline]
-    fn write(&self, w: &mut Writer) {
-        w.write_tlv(self.tag, move |dest| dest.extend_from_slice(self.data))
+    fn write(&self, w: &mut Writer) -> WriteResult {
+        w.write_tlv_with_len(self.tag, Some(self.data.len()), move |dest| {
+            dest.push_slice(self.data)
+        })
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> Option<usize> {
+        _tlv_len(self.tag, self.data.len())
+    }
+}
+
+/// An owned [`Tlv`], for use with types which don't borrow from their input.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Hash, Clone)]
+pub struct OwnedTlv {
+    pub(crate) full_data: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedTlv {
+    /// Borrows the contents as a `Tlv`.
+    pub fn as_tlv(&self) -> Tlv<'_> {
+        Parser::new(&self.full_data).read_tlv().unwrap()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Asn1Readable<'a> for OwnedTlv {
+    #[inline]
+    fn parse(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        Ok(OwnedTlv {
+            full_data: parser.read_tlv()?.full_data.to_vec(),
+        })
+    }
+
+    #[inline]
+    fn can_parse(_tag: Tag) -> bool {
+        true
+    }
+}
+#[cfg(feature = "alloc")]
+impl<'a> Asn1Writable<'a> for OwnedTlv {
+    #[inline]
+    fn write(&self, w: &mut Writer) -> WriteResult {
+        w.data.push_slice(&self.full_data)
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> Option<usize> {
+        Some(self.full_data.len())
     }
 }
 
@@ -128,7 +335,7 @@ impl<'a> Asn1Writable<'a> for Tlv<'a> {
 pub type Null = ();
 
 impl SimpleAsn1Readable<'_> for Null {
-    const TAG: u8 = 0x05;
+    const TAG: Tag = Tag::primitive(0x05);
     #[inline]
     fn parse_data(data: &[u8]) -> ParseResult<Null> {
         if data.is_empty() {
@@ -140,13 +347,19 @@ impl SimpleAsn1Readable<'_> for Null {
 }
 
 impl SimpleAsn1Writable<'_> for Null {
-    const TAG: u8 = 0x05;
+    const TAG: Tag = Tag::primitive(0x05);
     #[inline]
-    fn write_data(&self, _dest: &mut Vec<u8>) {}
+    fn write_data(&self, _dest: &mut WriteBuf) -> WriteResult {
+        Ok(())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(0)
+    }
 }
 
 impl SimpleAsn1Readable<'_> for bool {
-    const TAG: u8 = 0x1;
+    const TAG: Tag = Tag::primitive(0x1);
     fn parse_data(data: &[u8]) -> ParseResult<bool> {
         match data {
             b"\x00" => Ok(false),
@@ -157,27 +370,76 @@ impl SimpleAsn1Readable<'_> for bool {
 }
 
 impl SimpleAsn1Writable<'_> for bool {
-    const TAG: u8 = 0x1;
-    fn write_data(&self, dest: &mut Vec<u8>) {
+    const TAG: Tag = Tag::primitive(0x1);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
         if *self {
-            dest.push(0xff);
+            dest.push_byte(0xff)
         } else {
-            dest.push(0x00);
+            dest.push_byte(0x00)
         }
     }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(1)
+    }
 }
 
 impl<'a> SimpleAsn1Readable<'a> for &'a [u8] {
-    const TAG: u8 = 0x04;
+    const TAG: Tag = Tag::primitive(0x04);
     fn parse_data(data: &'a [u8]) -> ParseResult<&'a [u8]> {
         Ok(data)
     }
 }
 
 impl<'a> SimpleAsn1Writable<'a> for &'a [u8] {
-    const TAG: u8 = 0x04;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self);
+    const TAG: Tag = Tag::primitive(0x04);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+/// An owned ASN.1 `OCTET STRING`. `Vec<u8>` can't be used for this, since it
+/// is a `SEQUENCE OF INTEGER`.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OwnedOctetString(Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl OwnedOctetString {
+    pub fn new(data: Vec<u8>) -> OwnedOctetString {
+        OwnedOctetString(data)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedOctetString {
+    const TAG: Tag = Tag::primitive(0x04);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedOctetString(data.to_vec()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedOctetString {
+    const TAG: Tag = Tag::primitive(0x04);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(&self.0)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.0.len())
     }
 }
 
@@ -238,7 +500,7 @@ impl<'a> PrintableString<'a> {
 }
 
 impl<'a> SimpleAsn1Readable<'a> for PrintableString<'a> {
-    const TAG: u8 = 0x13;
+    const TAG: Tag = Tag::primitive(0x13);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
         PrintableString::new_from_bytes(data)
             .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
@@ -246,9 +508,55 @@ impl<'a> SimpleAsn1Readable<'a> for PrintableString<'a> {
 }
 
 impl<'a> SimpleAsn1Writable<'a> for PrintableString<'a> {
-    const TAG: u8 = 0x13;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self.0.as_bytes());
+    const TAG: Tag = Tag::primitive(0x13);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.0.as_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+/// An owned [`PrintableString`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedPrintableString(String);
+
+#[cfg(feature = "alloc")]
+impl OwnedPrintableString {
+    pub fn new(s: String) -> Option<OwnedPrintableString> {
+        PrintableString::new(&s)?;
+        Some(OwnedPrintableString(s))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn as_printable_string(&self) -> PrintableString<'_> {
+        PrintableString(&self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedPrintableString {
+    const TAG: Tag = Tag::primitive(0x13);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedPrintableString(String::from(
+            PrintableString::parse_data(data)?.as_str(),
+        )))
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedPrintableString {
+    const TAG: Tag = Tag::primitive(0x13);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        self.as_printable_string().write_data(dest)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.as_printable_string().data_len()
     }
 }
 
@@ -267,7 +575,7 @@ impl<'a> IA5String<'a> {
         }
     }
 
-    fn new_from_bytes(s: &'a [u8]) -> Option<IA5String> {
+    fn new_from_bytes(s: &'a [u8]) -> Option<IA5String<'a>> {
         if IA5String::verify(s) {
             // TODO: This value is always valid utf-8 because we just verified
             // the contents, but I don't want to call an unsafe function, so we
@@ -289,15 +597,61 @@ impl<'a> IA5String<'a> {
 }
 
 impl<'a> SimpleAsn1Readable<'a> for IA5String<'a> {
-    const TAG: u8 = 0x16;
+    const TAG: Tag = Tag::primitive(0x16);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
         IA5String::new_from_bytes(data).ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
     }
 }
 impl<'a> SimpleAsn1Writable<'a> for IA5String<'a> {
-    const TAG: u8 = 0x16;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self.0.as_bytes());
+    const TAG: Tag = Tag::primitive(0x16);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.0.as_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+/// An owned [`IA5String`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedIA5String(String);
+
+#[cfg(feature = "alloc")]
+impl OwnedIA5String {
+    pub fn new(s: String) -> Option<OwnedIA5String> {
+        IA5String::new(&s)?;
+        Some(OwnedIA5String(s))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn as_ia5string(&self) -> IA5String<'_> {
+        IA5String(&self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedIA5String {
+    const TAG: Tag = Tag::primitive(0x16);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedIA5String(String::from(
+            IA5String::parse_data(data)?.as_str(),
+        )))
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedIA5String {
+    const TAG: Tag = Tag::primitive(0x16);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        self.as_ia5string().write_data(dest)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.as_ia5string().data_len()
     }
 }
 
@@ -311,7 +665,7 @@ impl<'a> Utf8String<'a> {
         Utf8String(s)
     }
 
-    fn new_from_bytes(s: &'a [u8]) -> Option<Utf8String> {
+    fn new_from_bytes(s: &'a [u8]) -> Option<Utf8String<'a>> {
         Some(Utf8String(core::str::from_utf8(s).ok()?))
     }
 
@@ -321,16 +675,40 @@ impl<'a> Utf8String<'a> {
 }
 
 impl<'a> SimpleAsn1Readable<'a> for Utf8String<'a> {
-    const TAG: u8 = 0x0c;
+    const TAG: Tag = Tag::primitive(0x0c);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
         Utf8String::new_from_bytes(data)
             .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
     }
 }
 impl<'a> SimpleAsn1Writable<'a> for Utf8String<'a> {
-    const TAG: u8 = 0x0c;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self.0.as_bytes());
+    const TAG: Tag = Tag::primitive(0x0c);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.0.as_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+/// `String` is the owned counterpart of [`Utf8String`].
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for String {
+    const TAG: Tag = Tag::primitive(0x0c);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(String::from(Utf8String::parse_data(data)?.as_str()))
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for String {
+    const TAG: Tag = Tag::primitive(0x0c);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.as_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.len())
     }
 }
 
@@ -349,7 +727,7 @@ impl<'a> VisibleString<'a> {
         }
     }
 
-    fn new_from_bytes(s: &'a [u8]) -> Option<VisibleString> {
+    fn new_from_bytes(s: &'a [u8]) -> Option<VisibleString<'a>> {
         if VisibleString::verify(s) {
             // TODO: This value is always valid utf-8 because we just verified
             // the contents, but I don't want to call an unsafe function, so we
@@ -376,16 +754,62 @@ impl<'a> VisibleString<'a> {
 }
 
 impl<'a> SimpleAsn1Readable<'a> for VisibleString<'a> {
-    const TAG: u8 = 0x1a;
+    const TAG: Tag = Tag::primitive(0x1a);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
         VisibleString::new_from_bytes(data)
             .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
     }
 }
 impl<'a> SimpleAsn1Writable<'a> for VisibleString<'a> {
-    const TAG: u8 = 0x1a;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self.0.as_bytes());
+    const TAG: Tag = Tag::primitive(0x1a);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.0.as_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+/// An owned [`VisibleString`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedVisibleString(String);
+
+#[cfg(feature = "alloc")]
+impl OwnedVisibleString {
+    pub fn new(s: String) -> Option<OwnedVisibleString> {
+        VisibleString::new(&s)?;
+        Some(OwnedVisibleString(s))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn as_visible_string(&self) -> VisibleString<'_> {
+        VisibleString(&self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedVisibleString {
+    const TAG: Tag = Tag::primitive(0x1a);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedVisibleString(String::from(
+            VisibleString::parse_data(data)?.as_str(),
+        )))
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedVisibleString {
+    const TAG: Tag = Tag::primitive(0x1a);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        self.as_visible_string().write_data(dest)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.as_visible_string().data_len()
     }
 }
 
@@ -427,15 +851,61 @@ impl<'a> BMPString<'a> {
 }
 
 impl<'a> SimpleAsn1Readable<'a> for BMPString<'a> {
-    const TAG: u8 = 0x1e;
+    const TAG: Tag = Tag::primitive(0x1e);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
         BMPString::new(data).ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
     }
 }
 impl<'a> SimpleAsn1Writable<'a> for BMPString<'a> {
-    const TAG: u8 = 0x1e;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self.as_utf16_be_bytes());
+    const TAG: Tag = Tag::primitive(0x1e);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.as_utf16_be_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.as_utf16_be_bytes().len())
+    }
+}
+
+/// An owned [`BMPString`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct OwnedBMPString(Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl OwnedBMPString {
+    pub fn new(b: Vec<u8>) -> Option<OwnedBMPString> {
+        BMPString::new(&b)?;
+        Some(OwnedBMPString(b))
+    }
+
+    pub fn as_utf16_be_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn as_bmpstring(&self) -> BMPString<'_> {
+        BMPString(&self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedBMPString {
+    const TAG: Tag = Tag::primitive(0x1e);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedBMPString(
+            BMPString::parse_data(data)?.as_utf16_be_bytes().to_vec(),
+        ))
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedBMPString {
+    const TAG: Tag = Tag::primitive(0x1e);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(&self.0)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.0.len())
     }
 }
 
@@ -455,14 +925,12 @@ impl<'a> UniversalString<'a> {
     }
 
     fn verify(b: &[u8]) -> bool {
-        if b.len() % 4 != 0 {
+        let chunks = b.chunks_exact(4);
+        if !chunks.remainder().is_empty() {
             return false;
         }
 
-        for r in b
-            .chunks_exact(4)
-            .map(|v| u32::from_be_bytes(v.try_into().unwrap()))
-        {
+        for r in chunks.map(|v| u32::from_be_bytes(v.try_into().unwrap())) {
             if core::char::from_u32(r).is_none() {
                 return false;
             }
@@ -477,15 +945,63 @@ impl<'a> UniversalString<'a> {
 }
 
 impl<'a> SimpleAsn1Readable<'a> for UniversalString<'a> {
-    const TAG: u8 = 0x1c;
+    const TAG: Tag = Tag::primitive(0x1c);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
         UniversalString::new(data).ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
     }
 }
 impl<'a> SimpleAsn1Writable<'a> for UniversalString<'a> {
-    const TAG: u8 = 0x1c;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self.as_utf32_be_bytes());
+    const TAG: Tag = Tag::primitive(0x1c);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.as_utf32_be_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.as_utf32_be_bytes().len())
+    }
+}
+
+/// An owned [`UniversalString`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct OwnedUniversalString(Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl OwnedUniversalString {
+    pub fn new(b: Vec<u8>) -> Option<OwnedUniversalString> {
+        UniversalString::new(&b)?;
+        Some(OwnedUniversalString(b))
+    }
+
+    pub fn as_utf32_be_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn as_universal_string(&self) -> UniversalString<'_> {
+        UniversalString(&self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedUniversalString {
+    const TAG: Tag = Tag::primitive(0x1c);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedUniversalString(
+            UniversalString::parse_data(data)?
+                .as_utf32_be_bytes()
+                .to_vec(),
+        ))
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedUniversalString {
+    const TAG: Tag = Tag::primitive(0x1c);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(&self.0)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.0.len())
     }
 }
 
@@ -508,17 +1024,32 @@ fn validate_integer(data: &[u8], signed: bool) -> ParseResult<()> {
     Ok(())
 }
 
+// Returns the length of the minimal two's complement encoding of `v`, as
+// used for `INTEGER`s.
+#[inline]
+pub(crate) fn int_data_len(v: i128) -> usize {
+    // Every bit that's the same as the sign bit, except the sign bit itself,
+    // is redundant.
+    let redundant_bits = if v < 0 {
+        (!v).leading_zeros()
+    } else {
+        v.leading_zeros()
+    };
+    ((136 - redundant_bits) / 8) as usize
+}
+
 macro_rules! impl_asn1_element_for_int {
     ($t:ty; $signed:expr) => {
         impl SimpleAsn1Readable<'_> for $t {
-            const TAG: u8 = 0x02;
+            const TAG: Tag = Tag::primitive(0x02);
             #[inline]
             fn parse_data(mut data: &[u8]) -> ParseResult<Self> {
                 validate_integer(data, $signed)?;
 
                 // If we've got something like \x00\xff trim off the first \x00, since it's just
-                // there to not mark the value as a negative.
-                if data.len() == mem::size_of::<Self>() + 1 && data[0] == 0 {
+                // there to not mark the value as a negative. For signed types, the value doesn't
+                // fit.
+                if !$signed && data.len() == mem::size_of::<Self>() + 1 && data[0] == 0 {
                     data = &data[1..];
                 }
                 if data.len() > mem::size_of::<Self>() {
@@ -535,20 +1066,19 @@ macro_rules! impl_asn1_element_for_int {
             }
         }
         impl SimpleAsn1Writable<'_> for $t {
-            const TAG: u8 = 0x02;
-            fn write_data(&self, dest: &mut Vec<u8>) {
-                let mut num_bytes = 1;
-                let mut v: $t = *self;
-                #[allow(unused_comparisons)]
-                while v > 127 || ($signed && v < (-128i64) as $t) {
-                    num_bytes += 1;
-                    v = v.checked_shr(8).unwrap_or(0);
-                }
-
+            const TAG: Tag = Tag::primitive(0x02);
+            fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+                let num_bytes = int_data_len(i128::from(*self)) as u32;
                 for i in (1..num_bytes + 1).rev() {
                     let digit = self.checked_shr((i - 1) * 8).unwrap_or(0);
-                    dest.push(digit as u8);
+                    dest.push_byte(digit as u8)?;
                 }
+                Ok(())
+            }
+
+            #[inline]
+            fn data_len(&self) -> Option<usize> {
+                Some(int_data_len(i128::from(*self)))
             }
         }
     };
@@ -569,87 +1099,389 @@ pub struct BigUint<'a> {
     data: &'a [u8],
 }
 
-impl<'a> BigUint<'a> {
-    /// Create a new BigUint from already encoded data. `data` must be encoded
-    /// as required by DER: minimally and if the high bit would be set in the
-    /// first octet, a leading \x00 should be prepended (to disambiguate from
-    /// negative values).
-    pub fn new(data: &'a [u8]) -> Option<Self> {
-        validate_integer(data, false).ok()?;
-        Some(BigUint { data })
+impl<'a> BigUint<'a> {
+    /// Create a new BigUint from already encoded data. `data` must be encoded
+    /// as required by DER: minimally and if the high bit would be set in the
+    /// first octet, a leading \x00 should be prepended (to disambiguate from
+    /// negative values).
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        validate_integer(data, false).ok()?;
+        Some(BigUint { data })
+    }
+
+    /// Returns the contents of the integer as big-endian bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> SimpleAsn1Readable<'a> for BigUint<'a> {
+    const TAG: Tag = Tag::primitive(0x02);
+    fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
+        BigUint::new(data).ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
+    }
+}
+impl<'a> SimpleAsn1Writable<'a> for BigUint<'a> {
+    const TAG: Tag = Tag::primitive(0x02);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.data)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
+}
+
+/// An owned [`BigUint`].
+#[cfg(feature = "alloc")]
+#[derive(PartialEq, Clone, Debug, Hash)]
+pub struct OwnedBigUint {
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedBigUint {
+    /// Create a new OwnedBigUint from already encoded data. See
+    /// [`BigUint::new`] for the requirements on `data`.
+    pub fn new(data: Vec<u8>) -> Option<Self> {
+        BigUint::new(&data)?;
+        Some(OwnedBigUint { data })
+    }
+
+    /// Returns the contents of the integer as big-endian bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn as_biguint(&self) -> BigUint<'_> {
+        BigUint { data: &self.data }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedBigUint {
+    const TAG: Tag = Tag::primitive(0x02);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedBigUint {
+            data: BigUint::parse_data(data)?.as_bytes().to_vec(),
+        })
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedBigUint {
+    const TAG: Tag = Tag::primitive(0x02);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(&self.data)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
+}
+
+/// Arbitrary sized signed integer. Contents may be accessed as `&[u8]` of
+/// big-endian data. Its contents always match the DER encoding of a value
+/// (i.e. they are minimal)
+#[derive(PartialEq, Clone, Copy, Debug, Hash)]
+pub struct BigInt<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> BigInt<'a> {
+    /// Create a new BigInt from already encoded data. `data` must be encoded
+    /// as required by DER: minimally and if the high bit would be set in the
+    /// first octet, a leading \x00 should be prepended (to disambiguate from
+    /// negative values).
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        validate_integer(data, true).ok()?;
+        Some(BigInt { data })
+    }
+
+    /// Returns the contents of the integer as big-endian bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> SimpleAsn1Readable<'a> for BigInt<'a> {
+    const TAG: Tag = Tag::primitive(0x02);
+    fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
+        BigInt::new(data).ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
+    }
+}
+impl<'a> SimpleAsn1Writable<'a> for BigInt<'a> {
+    const TAG: Tag = Tag::primitive(0x02);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.data)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
+}
+
+/// An owned [`BigInt`].
+#[cfg(feature = "alloc")]
+#[derive(PartialEq, Clone, Debug, Hash)]
+pub struct OwnedBigInt {
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedBigInt {
+    /// Create a new OwnedBigInt from already encoded data. See
+    /// [`BigInt::new`] for the requirements on `data`.
+    pub fn new(data: Vec<u8>) -> Option<Self> {
+        BigInt::new(&data)?;
+        Some(OwnedBigInt { data })
+    }
+
+    /// Returns the contents of the integer as big-endian bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn as_bigint(&self) -> BigInt<'_> {
+        BigInt { data: &self.data }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedBigInt {
+    const TAG: Tag = Tag::primitive(0x02);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedBigInt {
+            data: BigInt::parse_data(data)?.as_bytes().to_vec(),
+        })
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedBigInt {
+    const TAG: Tag = Tag::primitive(0x02);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(&self.data)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
+}
+
+impl<'a> SimpleAsn1Readable<'a> for ObjectIdentifier {
+    const TAG: Tag = Tag::primitive(0x06);
+    fn parse_data(data: &'a [u8]) -> ParseResult<ObjectIdentifier> {
+        ObjectIdentifier::from_der(data)
+    }
+}
+impl<'a> SimpleAsn1Writable<'a> for ObjectIdentifier {
+    const TAG: Tag = Tag::primitive(0x06);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.as_der())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.as_der().len())
+    }
+}
+
+impl<'a> SimpleAsn1Readable<'a> for RelativeOid {
+    const TAG: Tag = Tag::primitive(0x0d);
+    fn parse_data(data: &'a [u8]) -> ParseResult<RelativeOid> {
+        RelativeOid::from_der(data)
+    }
+}
+impl<'a> SimpleAsn1Writable<'a> for RelativeOid {
+    const TAG: Tag = Tag::primitive(0x0d);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.as_der())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.as_der().len())
+    }
+}
+
+// Checks that `s` is a series of `/`-separated, non-empty IRI arcs. Arcs
+// which are integers may not have leading zeros, and other arcs may only
+// contain unreserved characters (ASCII letters, digits, `-`, `.`, `_`, and
+// `~`, as well as non-ASCII characters).
+fn is_valid_iri_arcs(s: &str) -> bool {
+    s.split('/').all(|arc| {
+        let valid_chars = arc.chars().all(|c| {
+            c.is_ascii_alphanumeric() || !c.is_ascii() || matches!(c, '-' | '.' | '_' | '~')
+        });
+        let leading_zero =
+            arc.len() > 1 && arc.starts_with('0') && arc.bytes().all(|b| b.is_ascii_digit());
+        !arc.is_empty() && valid_chars && !leading_zero
+    })
+}
+
+/// Type for use with `Parser.read_element` and `Writer.write_element` for
+/// handling ASN.1 `OID-IRI`, e.g. `/ISO/Registration_Authority/19785.CBEFF`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OidIri<'a>(&'a str);
+
+impl<'a> OidIri<'a> {
+    /// Returns `None` unless `s` is a `/` followed by one or more
+    /// `/`-separated arcs.
+    pub fn new(s: &'a str) -> Option<OidIri<'a>> {
+        match s.strip_prefix('/') {
+            Some(arcs) if is_valid_iri_arcs(arcs) => Some(OidIri(s)),
+            _ => None,
+        }
     }
 
-    /// Returns the contents of the integer as big-endian bytes.
-    pub fn as_bytes(&self) -> &'a [u8] {
-        self.data
+    pub fn as_str(&self) -> &'a str {
+        self.0
     }
 }
 
-impl<'a> SimpleAsn1Readable<'a> for BigUint<'a> {
-    const TAG: u8 = 0x02;
+impl<'a> SimpleAsn1Readable<'a> for OidIri<'a> {
+    const TAG: Tag = Tag::primitive(0x23);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
-        BigUint::new(data).ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
+        core::str::from_utf8(data)
+            .ok()
+            .and_then(OidIri::new)
+            .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
     }
 }
-impl<'a> SimpleAsn1Writable<'a> for BigUint<'a> {
-    const TAG: u8 = 0x02;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self.data);
+impl<'a> SimpleAsn1Writable<'a> for OidIri<'a> {
+    const TAG: Tag = Tag::primitive(0x23);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.0.as_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.0.len())
     }
 }
 
-/// Arbitrary sized signed integer. Contents may be accessed as `&[u8]` of
-/// big-endian data. Its contents always match the DER encoding of a value
-/// (i.e. they are minimal)
-#[derive(PartialEq, Clone, Copy, Debug, Hash)]
-pub struct BigInt<'a> {
-    data: &'a [u8],
+/// An owned [`OidIri`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OwnedOidIri(String);
+
+#[cfg(feature = "alloc")]
+impl OwnedOidIri {
+    pub fn new(s: String) -> Option<OwnedOidIri> {
+        OidIri::new(&s)?;
+        Some(OwnedOidIri(s))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn as_oid_iri(&self) -> OidIri<'_> {
+        OidIri(&self.0)
+    }
 }
 
-impl<'a> BigInt<'a> {
-    /// Create a new BigInt from already encoded data. `data` must be encoded
-    /// as required by DER: minimally and if the high bit would be set in the
-    /// first octet, a leading \x00 should be prepended (to disambiguate from
-    /// negative values).
-    pub fn new(data: &'a [u8]) -> Option<Self> {
-        validate_integer(data, true).ok()?;
-        Some(BigInt { data })
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedOidIri {
+    const TAG: Tag = Tag::primitive(0x23);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedOidIri(String::from(
+            OidIri::parse_data(data)?.as_str(),
+        )))
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedOidIri {
+    const TAG: Tag = Tag::primitive(0x23);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        self.as_oid_iri().write_data(dest)
     }
 
-    /// Returns the contents of the integer as big-endian bytes.
-    pub fn as_bytes(&self) -> &'a [u8] {
-        self.data
+    fn data_len(&self) -> Option<usize> {
+        self.as_oid_iri().data_len()
     }
 }
 
-impl<'a> SimpleAsn1Readable<'a> for BigInt<'a> {
-    const TAG: u8 = 0x02;
+/// Type for use with `Parser.read_element` and `Writer.write_element` for
+/// handling ASN.1 `RELATIVE-OID-IRI`, e.g. `Registration_Authority/19785.CBEFF`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RelativeOidIri<'a>(&'a str);
+
+impl<'a> RelativeOidIri<'a> {
+    /// Returns `None` unless `s` is one or more `/`-separated arcs.
+    pub fn new(s: &'a str) -> Option<RelativeOidIri<'a>> {
+        if is_valid_iri_arcs(s) {
+            Some(RelativeOidIri(s))
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+impl<'a> SimpleAsn1Readable<'a> for RelativeOidIri<'a> {
+    const TAG: Tag = Tag::primitive(0x24);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
-        BigInt::new(data).ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
+        core::str::from_utf8(data)
+            .ok()
+            .and_then(RelativeOidIri::new)
+            .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
     }
 }
-impl<'a> SimpleAsn1Writable<'a> for BigInt<'a> {
-    const TAG: u8 = 0x02;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self.data);
+impl<'a> SimpleAsn1Writable<'a> for RelativeOidIri<'a> {
+    const TAG: Tag = Tag::primitive(0x24);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_slice(self.0.as_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.0.len())
     }
 }
 
-impl<'a> SimpleAsn1Readable<'a> for ObjectIdentifier {
-    const TAG: u8 = 0x06;
-    fn parse_data(data: &'a [u8]) -> ParseResult<ObjectIdentifier> {
-        ObjectIdentifier::from_der(data)
+/// An owned [`RelativeOidIri`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OwnedRelativeOidIri(String);
+
+#[cfg(feature = "alloc")]
+impl OwnedRelativeOidIri {
+    pub fn new(s: String) -> Option<OwnedRelativeOidIri> {
+        RelativeOidIri::new(&s)?;
+        Some(OwnedRelativeOidIri(s))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn as_relative_oid_iri(&self) -> RelativeOidIri<'_> {
+        RelativeOidIri(&self.0)
     }
 }
-impl<'a> SimpleAsn1Writable<'a> for ObjectIdentifier {
-    const TAG: u8 = 0x06;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.extend_from_slice(self.as_der());
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedRelativeOidIri {
+    const TAG: Tag = Tag::primitive(0x24);
+    fn parse_data(data: &[u8]) -> ParseResult<Self> {
+        Ok(OwnedRelativeOidIri(String::from(
+            RelativeOidIri::parse_data(data)?.as_str(),
+        )))
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedRelativeOidIri {
+    const TAG: Tag = Tag::primitive(0x24);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        self.as_relative_oid_iri().write_data(dest)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.as_relative_oid_iri().data_len()
     }
 }
 
 impl<'a> SimpleAsn1Readable<'a> for BitString<'a> {
-    const TAG: u8 = 0x03;
+    const TAG: Tag = Tag::primitive(0x03);
     fn parse_data(data: &'a [u8]) -> ParseResult<BitString<'a>> {
         if data.is_empty() {
             return Err(ParseError::new(ParseErrorKind::InvalidValue));
@@ -659,149 +1491,340 @@ impl<'a> SimpleAsn1Readable<'a> for BitString<'a> {
     }
 }
 impl<'a> SimpleAsn1Writable<'a> for BitString<'a> {
-    const TAG: u8 = 0x03;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        dest.push(self.padding_bits());
-        dest.extend_from_slice(self.as_bytes());
+    const TAG: Tag = Tag::primitive(0x03);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        dest.push_byte(self.padding_bits())?;
+        dest.push_slice(self.as_bytes())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(1 + self.as_bytes().len())
     }
 }
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedBitString {
+    const TAG: Tag = Tag::primitive(0x03);
+    fn parse_data(data: &[u8]) -> ParseResult<OwnedBitString> {
+        let bs = BitString::parse_data(data)?;
+        Ok(OwnedBitString::new(bs.as_bytes().to_vec(), bs.padding_bits()).unwrap())
+    }
+}
+#[cfg(feature = "alloc")]
 impl<'a> SimpleAsn1Writable<'a> for OwnedBitString {
-    const TAG: u8 = 0x03;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        self.as_bitstring().write_data(dest);
+    const TAG: Tag = Tag::primitive(0x03);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        self.as_bitstring().write_data(dest)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.as_bitstring().data_len()
+    }
+}
+
+impl SimpleAsn1Readable<'_> for Real {
+    const TAG: Tag = Tag::primitive(0x09);
+    fn parse_data(data: &[u8]) -> ParseResult<Real> {
+        Real::parse_data(data)
+    }
+}
+impl SimpleAsn1Writable<'_> for Real {
+    const TAG: Tag = Tag::primitive(0x09);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        Real::write_data(self, dest)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Real::data_len(self)
+    }
+}
+
+/// `f64`s are ASN.1 `REAL`s. Values which aren't exactly representable are
+/// rounded, see [`Real::to_f64`].
+impl SimpleAsn1Readable<'_> for f64 {
+    const TAG: Tag = Tag::primitive(0x09);
+    fn parse_data(data: &[u8]) -> ParseResult<f64> {
+        Ok(Real::parse_data(data)?.to_f64())
+    }
+}
+impl SimpleAsn1Writable<'_> for f64 {
+    const TAG: Tag = Tag::primitive(0x09);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        Real::from(*self).write_data(dest)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Real::from(*self).data_len()
     }
 }
 
 /// Used for parsing and writing ASN.1 `UTC TIME` values. Wraps a
-/// `chrono::DateTime<Utc>`.
-#[derive(Debug, Clone, PartialEq, Hash)]
-pub struct UtcTime(chrono::DateTime<chrono::Utc>);
+/// [`DateTime`] whose year is between 1950 and 2049, and which has no
+/// fractional seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UtcTime(DateTime);
 
 impl UtcTime {
-    pub fn new(v: chrono::DateTime<chrono::Utc>) -> Option<UtcTime> {
-        if v.year() >= 2050 || v.year() < 1950 {
+    pub fn new(v: DateTime) -> Option<UtcTime> {
+        if v.year() >= 2050 || v.year() < 1950 || v.nanosecond() != 0 {
             return None;
         }
         Some(UtcTime(v))
     }
 
-    pub fn as_chrono(&self) -> &chrono::DateTime<chrono::Utc> {
+    pub fn as_datetime(&self) -> &DateTime {
         &self.0
     }
 }
 
-impl SimpleAsn1Readable<'_> for UtcTime {
-    const TAG: u8 = 0x17;
-    fn parse_data(data: &[u8]) -> ParseResult<Self> {
-        let data = core::str::from_utf8(data)
-            .map_err(|_| ParseError::new(ParseErrorKind::InvalidValue))?;
-
-        // UTCTime comes in 4 different formats: with and without seconds, and
-        // with a fixed offset or UTC. We choose which to parse as based on the
-        // input length.
-        let mut dt = match data.len() {
-            17 => chrono::DateTime::parse_from_str(data, "%y%m%d%H%M%S%z").map(|dt| dt.into()),
-            15 => chrono::DateTime::parse_from_str(data, "%y%m%d%H%M%z").map(|dt| dt.into()),
-            13 => chrono::Utc.datetime_from_str(data, "%y%m%d%H%M%SZ"),
-            11 => chrono::Utc.datetime_from_str(data, "%y%m%d%H%MZ"),
-            _ => return Err(ParseError::new(ParseErrorKind::InvalidValue)),
-        }
-        .map_err(|_| ParseError::new(ParseErrorKind::InvalidValue))?;
-        // Reject leap seconds, which aren't allowed by ASN.1. chrono encodes them as
-        // nanoseconds == 1000000.
-        if dt.nanosecond() >= 1_000_000 {
+fn read_digits(data: &mut &[u8], count: usize) -> ParseResult<u32> {
+    if data.len() < count {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue));
+    }
+    let (digits, rest) = data.split_at(count);
+    let mut value = 0;
+    for &b in digits {
+        if !b.is_ascii_digit() {
             return Err(ParseError::new(ParseErrorKind::InvalidValue));
         }
+        value = value * 10 + u32::from(b - b'0');
+    }
+    *data = rest;
+    Ok(value)
+}
+
+// Parses the time zone at the end of a time: either `Z`, or, when
+// `allow_offset` is set, an offset of `+HHMM` or `-HHMM`. Returns the offset
+// from UTC in seconds.
+fn read_time_zone(data: &[u8], allow_offset: bool) -> ParseResult<i64> {
+    match data.split_first() {
+        Some((&b'Z', [])) => Ok(0),
+        Some((&sign, mut rest)) if sign == b'+' || sign == b'-' => {
+            if !allow_offset {
+                return Err(ParseError::new(ParseErrorKind::TimeZoneOffset));
+            }
+            let hours = read_digits(&mut rest, 2)?;
+            let minutes = read_digits(&mut rest, 2)?;
+            if !rest.is_empty() || hours > 23 || minutes > 59 {
+                return Err(ParseError::new(ParseErrorKind::InvalidValue));
+            }
+            let offset = i64::from(hours * 60 * 60 + minutes * 60);
+            Ok(if sign == b'+' { offset } else { -offset })
+        }
+        _ => Err(ParseError::new(ParseErrorKind::InvalidValue)),
+    }
+}
+
+// Builds the `DateTime` for a time with the given offset from UTC.
+fn to_utc(
+    (year, month, day): (u32, u32, u32),
+    (hour, minute, second): (u32, u32, u32),
+    nanosecond: u32,
+    offset: i64,
+) -> ParseResult<DateTime> {
+    // All of the parts have at most four digits, so these conversions can't
+    // truncate. Leap seconds are rejected here, as ASN.1 doesn't allow them.
+    let dt = DateTime::new(
+        year as u16,
+        month as u8,
+        day as u8,
+        hour as u8,
+        minute as u8,
+        second as u8,
+    )
+    .and_then(|dt| dt.with_nanosecond(nanosecond))
+    .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))?;
+    if offset == 0 {
+        return Ok(dt);
+    }
+    DateTime::from_unix_timestamp(dt.unix_timestamp() - offset, nanosecond)
+        .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
+}
+
+impl SimpleAsn1Readable<'_> for UtcTime {
+    const TAG: Tag = Tag::primitive(0x17);
+    fn parse_data(mut data: &[u8]) -> ParseResult<Self> {
+        // UTCTime comes in 4 different formats: with and without seconds, and
+        // with a fixed offset or UTC.
+        let year = read_digits(&mut data, 2)?;
+        let month = read_digits(&mut data, 2)?;
+        let day = read_digits(&mut data, 2)?;
+        let hour = read_digits(&mut data, 2)?;
+        let minute = read_digits(&mut data, 2)?;
+        let second = match data.first() {
+            Some(b) if b.is_ascii_digit() => read_digits(&mut data, 2)?,
+            _ => 0,
+        };
+        let offset = read_time_zone(data, true)?;
+
         // UTCTime only encodes times prior to 2050. We use the X.509 mapping of two-digit
         // year ordinals to full year:
         // https://tools.ietf.org/html/rfc5280#section-4.1.2.5.1
-        if dt.year() >= 2050 {
-            dt = chrono::Utc
-                .ymd(dt.year() - 100, dt.month(), dt.day())
-                .and_hms(dt.hour(), dt.minute(), dt.second());
-        }
-        Ok(UtcTime(dt))
+        let year = if year >= 50 { 1900 + year } else { 2000 + year };
+        let dt = to_utc((year, month, day), (hour, minute, second), 0, offset)?;
+        UtcTime::new(dt).ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue))
     }
 }
 
-fn push_two_digits(dest: &mut Vec<u8>, val: u8) {
-    dest.push(b'0' + ((val / 10) % 10));
-    dest.push(b'0' + (val % 10));
+fn push_two_digits(dest: &mut WriteBuf, val: u8) -> WriteResult {
+    dest.push_byte(b'0' + ((val / 10) % 10))?;
+    dest.push_byte(b'0' + (val % 10))
 }
 
-fn push_four_digits(dest: &mut Vec<u8>, val: u16) {
-    dest.push(b'0' + ((val / 1000) % 10) as u8);
-    dest.push(b'0' + ((val / 100) % 10) as u8);
-    dest.push(b'0' + ((val / 10) % 10) as u8);
-    dest.push(b'0' + (val % 10) as u8);
+fn push_four_digits(dest: &mut WriteBuf, val: u16) -> WriteResult {
+    dest.push_byte(b'0' + ((val / 1000) % 10) as u8)?;
+    dest.push_byte(b'0' + ((val / 100) % 10) as u8)?;
+    dest.push_byte(b'0' + ((val / 10) % 10) as u8)?;
+    dest.push_byte(b'0' + (val % 10) as u8)
 }
 
 impl SimpleAsn1Writable<'_> for UtcTime {
-    const TAG: u8 = 0x17;
-    fn write_data(&self, dest: &mut Vec<u8>) {
+    const TAG: Tag = Tag::primitive(0x17);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
         let year = if 1950 <= self.0.year() && self.0.year() < 2000 {
             self.0.year() - 1900
         } else if 2000 <= self.0.year() && self.0.year() < 2050 {
             self.0.year() - 2000
         } else {
-            unreachable!()
+            return Err(WriteError::InvalidValue);
         };
-        push_two_digits(dest, year.try_into().unwrap());
-        push_two_digits(dest, self.0.month().try_into().unwrap());
-        push_two_digits(dest, self.0.day().try_into().unwrap());
+        let year = u8::try_from(year).map_err(|_| WriteError::InvalidValue)?;
+        push_two_digits(dest, year)?;
+        push_two_digits(dest, self.0.month())?;
+        push_two_digits(dest, self.0.day())?;
 
-        push_two_digits(dest, self.0.hour().try_into().unwrap());
-        push_two_digits(dest, self.0.minute().try_into().unwrap());
-        push_two_digits(dest, self.0.second().try_into().unwrap());
+        push_two_digits(dest, self.0.hour())?;
+        push_two_digits(dest, self.0.minute())?;
+        push_two_digits(dest, self.0.second())?;
+
+        dest.push_byte(b'Z')
+    }
 
-        dest.push(b'Z');
+    #[inline]
+    fn data_len(&self) -> Option<usize> {
+        // YYMMDDHHMMSSZ
+        Some(13)
     }
 }
 
 /// Used for parsing and writing ASN.1 `GENERALIZED TIME` values. Wraps a
-/// `chrono::DateTime<Utc>`.
-#[derive(Debug, Clone, PartialEq, Hash)]
-pub struct GeneralizedTime(chrono::DateTime<chrono::Utc>);
+/// [`DateTime`].
+///
+/// Values are encoded as DER requires: in UTC (with a trailing `Z`), and with
+/// fractional seconds only when they are non-zero, without trailing zeros.
+/// When parsing, times with an offset from UTC are rejected with
+/// [`ParseErrorKind::TimeZoneOffset`]; they may be read with [`parse_ber`],
+/// which converts them to UTC.
+///
+/// [`parse_ber`]: crate::parse_ber
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GeneralizedTime(DateTime);
 
 impl GeneralizedTime {
-    pub fn new(v: chrono::DateTime<chrono::Utc>) -> GeneralizedTime {
+    pub fn new(v: DateTime) -> GeneralizedTime {
         GeneralizedTime(v)
     }
 
-    pub fn as_chrono(&self) -> &chrono::DateTime<chrono::Utc> {
+    pub fn as_datetime(&self) -> &DateTime {
         &self.0
     }
+
+    /// Parses a BER encoded value, which in addition to DER's form may have
+    /// an offset from UTC, use `,` as the decimal separator, and have
+    /// trailing zeros in its fractional seconds.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn parse_ber_data(data: &[u8]) -> ParseResult<GeneralizedTime> {
+        parse_generalized_time(data, false).map(GeneralizedTime)
+    }
+}
+
+// Parses `YYYYMMDDHHMMSS[.f+]Z`. Unless `der` is set, the time zone may also
+// be an offset (`+HHMM` or `-HHMM`), the decimal separator may be `,`, and
+// the fraction may have trailing zeros.
+fn parse_generalized_time(mut data: &[u8], der: bool) -> ParseResult<DateTime> {
+    let year = read_digits(&mut data, 4)?;
+    let month = read_digits(&mut data, 2)?;
+    let day = read_digits(&mut data, 2)?;
+    let hour = read_digits(&mut data, 2)?;
+    let minute = read_digits(&mut data, 2)?;
+    let second = read_digits(&mut data, 2)?;
+
+    let mut nanos = 0;
+    if let Some((&separator, rest)) = data.split_first() {
+        if separator == b'.' || (!der && separator == b',') {
+            let len = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+            let (mut fraction, rest) = rest.split_at(len);
+            data = rest;
+            if fraction.is_empty() {
+                return Err(ParseError::new(ParseErrorKind::InvalidValue));
+            }
+            while let Some((&b'0', head)) = fraction.split_last() {
+                if der {
+                    return Err(ParseError::new(ParseErrorKind::InvalidValue));
+                }
+                fraction = head;
+            }
+            // Precision beyond nanoseconds can't be represented.
+            if fraction.len() > 9 {
+                return Err(ParseError::new(ParseErrorKind::InvalidValue));
+            }
+            for i in 0..9 {
+                nanos = nanos * 10 + fraction.get(i).map_or(0, |b| u32::from(b - b'0'));
+            }
+        }
+    }
+    let offset = read_time_zone(data, !der)?;
+
+    to_utc((year, month, day), (hour, minute, second), nanos, offset)
 }
 
 impl SimpleAsn1Readable<'_> for GeneralizedTime {
-    const TAG: u8 = 0x18;
+    const TAG: Tag = Tag::primitive(0x18);
     fn parse_data(data: &[u8]) -> ParseResult<GeneralizedTime> {
-        let data = core::str::from_utf8(data)
-            .map_err(|_| ParseError::new(ParseErrorKind::InvalidValue))?;
-        if let Ok(v) = chrono::Utc.datetime_from_str(data, "%Y%m%d%H%M%SZ") {
-            return Ok(GeneralizedTime::new(v));
-        }
-        if let Ok(v) = chrono::DateTime::parse_from_str(data, "%Y%m%d%H%M%S%z") {
-            return Ok(GeneralizedTime::new(v.into()));
-        }
-
-        Err(ParseError::new(ParseErrorKind::InvalidValue))
+        parse_generalized_time(data, true).map(GeneralizedTime)
     }
 }
 
 impl SimpleAsn1Writable<'_> for GeneralizedTime {
-    const TAG: u8 = 0x18;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        push_four_digits(dest, self.0.year().try_into().unwrap());
-        push_two_digits(dest, self.0.month().try_into().unwrap());
-        push_two_digits(dest, self.0.day().try_into().unwrap());
+    const TAG: Tag = Tag::primitive(0x18);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        if self.0.year() > 9999 {
+            return Err(WriteError::InvalidValue);
+        }
+        push_four_digits(dest, self.0.year())?;
+        push_two_digits(dest, self.0.month())?;
+        push_two_digits(dest, self.0.day())?;
+
+        push_two_digits(dest, self.0.hour())?;
+        push_two_digits(dest, self.0.minute())?;
+        push_two_digits(dest, self.0.second())?;
+
+        let (digits, len) = fraction_digits(self.0.nanosecond());
+        if len != 0 {
+            dest.push_byte(b'.')?;
+            dest.push_slice(&digits[..len])?;
+        }
+
+        dest.push_byte(b'Z')
+    }
 
-        push_two_digits(dest, self.0.hour().try_into().unwrap());
-        push_two_digits(dest, self.0.minute().try_into().unwrap());
-        push_two_digits(dest, self.0.second().try_into().unwrap());
+    fn data_len(&self) -> Option<usize> {
+        // YYYYMMDDHHMMSS[.f+]Z
+        match fraction_digits(self.0.nanosecond()).1 {
+            0 => Some(15),
+            len => Some(15 + 1 + len),
+        }
+    }
+}
 
-        dest.push(b'Z');
+// Returns the digits of the fraction of a second `nanos` represents, and how
+// many of them are written, which excludes trailing zeros.
+fn fraction_digits(nanos: u32) -> ([u8; 9], usize) {
+    let mut digits = [0; 9];
+    for (i, d) in digits.iter_mut().enumerate() {
+        *d = b'0' + (nanos / 10u32.pow(8 - i as u32) % 10) as u8;
     }
+    let len = digits.iter().rposition(|&d| d != b'0').map_or(0, |i| i + 1);
+    (digits, len)
 }
 
 /// An ASN.1 `ENUMERATED` value.
@@ -819,7 +1842,7 @@ impl Enumerated {
 }
 
 impl<'a> SimpleAsn1Readable<'a> for Enumerated {
-    const TAG: u8 = 0xa;
+    const TAG: Tag = Tag::primitive(0xa);
 
     fn parse_data(data: &'a [u8]) -> ParseResult<Enumerated> {
         Ok(Enumerated::new(u32::parse_data(data)?))
@@ -827,32 +1850,46 @@ impl<'a> SimpleAsn1Readable<'a> for Enumerated {
 }
 
 impl<'a> SimpleAsn1Writable<'a> for Enumerated {
-    const TAG: u8 = 0xa;
+    const TAG: Tag = Tag::primitive(0xa);
 
-    fn write_data(&self, dest: &mut Vec<u8>) {
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
         u32::write_data(&self.0, dest)
     }
+
+    fn data_len(&self) -> Option<usize> {
+        u32::data_len(&self.0)
+    }
 }
 
 impl<'a, T: Asn1Readable<'a>> Asn1Readable<'a> for Option<T> {
     fn parse(parser: &mut Parser<'a>) -> ParseResult<Self> {
-        match parser.peek_u8() {
+        match parser.peek_tag() {
             Some(tag) if T::can_parse(tag) => Ok(Some(parser.read_element::<T>()?)),
             Some(_) | None => Ok(None),
         }
     }
 
     #[inline]
-    fn can_parse(tag: u8) -> bool {
+    fn can_parse(tag: Tag) -> bool {
         T::can_parse(tag)
     }
 }
 
 impl<'a, T: Asn1Writable<'a>> Asn1Writable<'a> for Option<T> {
     #[inline]
-    fn write(&self, w: &mut Writer) {
+    fn write(&self, w: &mut Writer) -> WriteResult {
         if let Some(v) = self {
-            w.write_element(v);
+            w.write_element(v)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> Option<usize> {
+        match self {
+            Some(v) => v.encoded_len(),
+            None => Some(0),
         }
     }
 }
@@ -888,7 +1925,7 @@ macro_rules! declare_choice {
                 Err(ParseError::new(ParseErrorKind::UnexpectedTag{actual: tlv.tag()}))
             }
 
-            fn can_parse(tag: u8) -> bool {
+            fn can_parse(tag: Tag) -> bool {
                 $(
                     if $number::can_parse(tag) {
                         return true;
@@ -904,13 +1941,21 @@ macro_rules! declare_choice {
                 $number: Asn1Writable<'a>,
             )*
         > Asn1Writable<'a> for $count<$($number,)*> {
-            fn write(&self, w: &mut Writer) {
+            fn write(&self, w: &mut Writer) -> WriteResult {
                 match self {
                     $(
                         $count::$name(v) => w.write_element(v),
                     )*
                 }
             }
+
+            fn encoded_len(&self) -> Option<usize> {
+                match self {
+                    $(
+                        $count::$name(v) => v.encoded_len(),
+                    )*
+                }
+            }
         }
     }
 }
@@ -944,37 +1989,85 @@ impl<'a> Sequence<'a> {
 }
 
 impl<'a> SimpleAsn1Readable<'a> for Sequence<'a> {
-    const TAG: u8 = 0x10 | CONSTRUCTED;
+    const TAG: Tag = Tag::constructed(0x10);
     #[inline]
     fn parse_data(data: &'a [u8]) -> ParseResult<Sequence<'a>> {
         Ok(Sequence::new(data))
     }
 }
 impl<'a> SimpleAsn1Writable<'a> for Sequence<'a> {
-    const TAG: u8 = 0x10 | CONSTRUCTED;
+    const TAG: Tag = Tag::constructed(0x10);
     #[inline]
-    fn write_data(&self, data: &mut Vec<u8>) {
-        data.extend_from_slice(self.data);
+    fn write_data(&self, data: &mut WriteBuf) -> WriteResult {
+        data.push_slice(self.data)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
+}
+
+/// An owned [`Sequence`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Hash, Clone)]
+pub struct OwnedSequence {
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedSequence {
+    pub fn as_sequence(&self) -> Sequence<'_> {
+        Sequence::new(&self.data)
+    }
+
+    /// Parses the contents of the `OwnedSequence`. Behaves the same as the
+    /// module-level `parse` function.
+    pub fn parse<'a, T, E: From<ParseError>, F: Fn(&mut Parser<'a>) -> Result<T, E>>(
+        &'a self,
+        f: F,
+    ) -> Result<T, E> {
+        self.as_sequence().parse(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Readable<'_> for OwnedSequence {
+    const TAG: Tag = Tag::constructed(0x10);
+    fn parse_data(data: &[u8]) -> ParseResult<OwnedSequence> {
+        Ok(OwnedSequence {
+            data: data.to_vec(),
+        })
+    }
+}
+#[cfg(feature = "alloc")]
+impl SimpleAsn1Writable<'_> for OwnedSequence {
+    const TAG: Tag = Tag::constructed(0x10);
+    fn write_data(&self, data: &mut WriteBuf) -> WriteResult {
+        self.as_sequence().write_data(data)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.as_sequence().data_len()
     }
 }
 
 /// Writes an ASN.1 `SEQUENCE` using a callback that writes the inner
 /// elements.
 pub struct SequenceWriter<'a> {
-    f: &'a dyn Fn(&mut Writer),
+    f: &'a dyn Fn(&mut Writer) -> WriteResult,
 }
 
 impl<'a> SequenceWriter<'a> {
     #[inline]
-    pub fn new(f: &'a dyn Fn(&mut Writer)) -> Self {
+    pub fn new(f: &'a dyn Fn(&mut Writer) -> WriteResult) -> Self {
         SequenceWriter { f }
     }
 }
 
 impl<'a> SimpleAsn1Writable<'a> for SequenceWriter<'a> {
-    const TAG: u8 = 0x10 | CONSTRUCTED;
+    const TAG: Tag = Tag::constructed(0x10);
     #[inline]
-    fn write_data(&self, dest: &mut Vec<u8>) {
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
         (self.f)(&mut Writer::new(dest))
     }
 }
@@ -1053,7 +2146,7 @@ impl<'a, T: Asn1Readable<'a> + Hash> Hash for SequenceOf<'a, T> {
 }
 
 impl<'a, T: Asn1Readable<'a> + 'a> SimpleAsn1Readable<'a> for SequenceOf<'a, T> {
-    const TAG: u8 = 0x10 | CONSTRUCTED;
+    const TAG: Tag = Tag::constructed(0x10);
     #[inline]
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
         SequenceOf::new(data)
@@ -1077,12 +2170,76 @@ impl<'a, T: Asn1Readable<'a>> Iterator for SequenceOf<'a, T> {
 }
 
 impl<'a, T: Asn1Readable<'a> + Asn1Writable<'a>> SimpleAsn1Writable<'a> for SequenceOf<'a, T> {
-    const TAG: u8 = 0x10 | CONSTRUCTED;
-    fn write_data(&self, dest: &mut Vec<u8>) {
+    const TAG: Tag = Tag::constructed(0x10);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
         let mut w = Writer::new(dest);
         for el in self.clone() {
-            w.write_element(&el);
+            w.write_element(&el)?;
+        }
+        Ok(())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.clone()
+            .try_fold(0usize, |sum, el| sum.checked_add(el.encoded_len()?))
+    }
+}
+
+/// `Vec<T>` is the owned counterpart of [`SequenceOf`].
+#[cfg(feature = "alloc")]
+impl<'a, T: Asn1Readable<'a>> SimpleAsn1Readable<'a> for Vec<T> {
+    const TAG: Tag = Tag::constructed(0x10);
+    fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
+        parse(data, |p| {
+            let mut v = vec![];
+            while !p.is_empty() {
+                v.push(
+                    p.read_element::<T>()
+                        .map_err(|e| e.add_location(ParseLocation::Index(v.len())))?,
+                );
+            }
+            Ok(v)
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Asn1Writable<'a>> SimpleAsn1Writable<'a> for Vec<T> {
+    const TAG: Tag = Tag::constructed(0x10);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        let mut w = Writer::new(dest);
+        for el in self {
+            w.write_element(el)?;
+        }
+        Ok(())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        encoded_len_sum(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Asn1Readable<'a>> SimpleAsn1Readable<'a> for Box<[T]> {
+    const TAG: Tag = Tag::constructed(0x10);
+    fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
+        Ok(Vec::parse_data(data)?.into_boxed_slice())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Asn1Writable<'a>> SimpleAsn1Writable<'a> for Box<[T]> {
+    const TAG: Tag = Tag::constructed(0x10);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        let mut w = Writer::new(dest);
+        for el in self.iter() {
+            w.write_element(el)?;
         }
+        Ok(())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        encoded_len_sum(self.iter())
     }
 }
 
@@ -1105,17 +2262,26 @@ impl<'a, T: Asn1Writable<'a>, V: Borrow<[T]>> SequenceOfWriter<'a, T, V> {
 impl<'a, T: Asn1Writable<'a>, V: Borrow<[T]>> SimpleAsn1Writable<'a>
     for SequenceOfWriter<'a, T, V>
 {
-    const TAG: u8 = 0x10 | CONSTRUCTED;
-    fn write_data(&self, dest: &mut Vec<u8>) {
+    const TAG: Tag = Tag::constructed(0x10);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
         let mut w = Writer::new(dest);
         for el in self.vals.borrow() {
-            w.write_element(el);
+            w.write_element(el)?;
         }
+        Ok(())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        encoded_len_sum(self.vals.borrow())
     }
 }
 
 /// Represents an ASN.1 `SET OF`. This is an `Iterator` over values that
 /// are decoded.
+///
+/// The elements must be in the canonical DER order. Sets which are not can
+/// be parsed with [`parse_ber`](crate::parse_ber) or
+/// [`BerInput`](crate::BerInput), which sort them.
 pub struct SetOf<'a, T: Asn1Readable<'a>> {
     parser: Parser<'a>,
     _phantom: PhantomData<T>,
@@ -1167,7 +2333,7 @@ impl<'a, T: Asn1Readable<'a> + Hash> Hash for SetOf<'a, T> {
 }
 
 impl<'a, T: Asn1Readable<'a> + 'a> SimpleAsn1Readable<'a> for SetOf<'a, T> {
-    const TAG: u8 = 0x11 | CONSTRUCTED;
+    const TAG: Tag = Tag::constructed(0x11);
 
     #[inline]
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
@@ -1178,11 +2344,13 @@ impl<'a, T: Asn1Readable<'a> + 'a> SimpleAsn1Readable<'a> for SetOf<'a, T> {
                 let el = p
                     .read_tlv()
                     .map_err(|e| e.add_location(ParseLocation::Index(i)))?;
+                // DER requires the elements be sorted by their encodings.
+                // Duplicate elements are permitted.
                 if let Some(last_el) = last_element {
-                    /*if el.full_data < last_el.full_data {
+                    if el.full_data < last_el.full_data {
                         return Err(ParseError::new(ParseErrorKind::InvalidSetOrdering)
                             .add_location(ParseLocation::Index(i)));
-                    }*/
+                    }
                 }
                 last_element = Some(el);
                 el.parse::<T>()
@@ -1211,14 +2379,61 @@ impl<'a, T: Asn1Readable<'a>> Iterator for SetOf<'a, T> {
 }
 
 impl<'a, T: Asn1Readable<'a> + Asn1Writable<'a>> SimpleAsn1Writable<'a> for SetOf<'a, T> {
-    const TAG: u8 = 0x11 | CONSTRUCTED;
-    fn write_data(&self, dest: &mut Vec<u8>) {
+    const TAG: Tag = Tag::constructed(0x11);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
         let mut w = Writer::new(dest);
         // We are known to be ordered correctly because that's an invariant for
         // `self`, so we don't need to sort here.
         for el in self.clone() {
-            w.write_element(&el);
+            w.write_element(&el)?;
         }
+        Ok(())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.clone()
+            .try_fold(0usize, |sum, el| sum.checked_add(el.encoded_len()?))
+    }
+}
+
+/// An owned [`SetOf`]. The elements are sorted into the order DER requires
+/// when written, so they may be stored in any order.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct OwnedSetOf<T>(Vec<T>);
+
+#[cfg(feature = "alloc")]
+impl<T> OwnedSetOf<T> {
+    pub fn new(vals: Vec<T>) -> OwnedSetOf<T> {
+        OwnedSetOf(vals)
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Asn1Readable<'a> + 'a> SimpleAsn1Readable<'a> for OwnedSetOf<T> {
+    const TAG: Tag = Tag::constructed(0x11);
+    fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
+        Ok(OwnedSetOf(SetOf::<T>::parse_data(data)?.collect()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Asn1Writable<'a>> SimpleAsn1Writable<'a> for OwnedSetOf<T> {
+    const TAG: Tag = Tag::constructed(0x11);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        write_set_of(dest, &self.0)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        encoded_len_sum(&self.0)
     }
 }
 
@@ -1240,35 +2455,67 @@ impl<'a, T: Asn1Writable<'a>, V: Borrow<[T]>> SetOfWriter<'a, T, V> {
 }
 
 impl<'a, T: Asn1Writable<'a>, V: Borrow<[T]>> SimpleAsn1Writable<'a> for SetOfWriter<'a, T, V> {
-    const TAG: u8 = 0x11 | CONSTRUCTED;
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        let vals = self.vals.borrow();
-        if vals.is_empty() {
-            return;
-        } else if vals.len() == 1 {
-            let mut w = Writer::new(dest);
-            w.write_element(&vals[0]);
-            return;
-        }
+    const TAG: Tag = Tag::constructed(0x11);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        write_set_of(dest, self.vals.borrow())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        encoded_len_sum(self.vals.borrow())
+    }
+}
+
+fn write_set_of<'a, T: Asn1Writable<'a>>(dest: &mut WriteBuf, vals: &[T]) -> WriteResult {
+    if vals.is_empty() {
+        return Ok(());
+    } else if vals.len() == 1 {
+        let mut w = Writer::new(dest);
+        return w.write_element(&vals[0]);
+    }
 
+    #[cfg(feature = "alloc")]
+    {
         // Optimization: use the dest storage as scratch, then truncate.
-        let mut data = vec![];
-        let mut w = Writer::new(&mut data);
-        // Optimization opportunity: use a SmallVec here.
-        let mut spans = vec![];
-
-        let mut pos = 0;
-        for el in vals {
-            w.write_element(el);
-            let l = w.data.len();
-            spans.push(pos..l);
-            pos = l;
-        }
-        spans.sort_by_key(|v| &data[v.clone()]);
-        for span in spans {
-            dest.extend_from_slice(&data[span]);
+        if let Some(mut data) = dest.scratch() {
+            let mut w = Writer::new(&mut data);
+            // Optimization opportunity: use a SmallVec here.
+            let mut spans = vec![];
+
+            let mut pos = 0;
+            for el in vals {
+                w.write_element(el)?;
+                let l = w.data.len();
+                spans.push(pos..l);
+                pos = l;
+            }
+            return write_set_of_elements(dest, data.as_slice(), spans);
         }
     }
+
+    // Writing into a caller-provided buffer never allocates, so the elements
+    // are sorted where they're written instead.
+    let start = dest.len();
+    let mut w = Writer::new(dest);
+    for el in vals {
+        w.write_element(el)?;
+    }
+    dest.sort_elements_from(start, |a, b| a < b);
+    Ok(())
+}
+
+// Appends the encoded elements in `data`, delimited by `spans`, to `dest` in
+// the order DER requires for a `SET OF`: ascending order of their encodings.
+#[cfg(feature = "alloc")]
+pub(crate) fn write_set_of_elements(
+    dest: &mut WriteBuf,
+    data: &[u8],
+    mut spans: Vec<Range<usize>>,
+) -> WriteResult {
+    spans.sort_by_key(|v| &data[v.clone()]);
+    for span in spans {
+        dest.push_slice(&data[span])?;
+    }
+    Ok(())
 }
 
 /// `Implicit` is a type which wraps another ASN.1 type, indicating that the tag is an ASN.1
@@ -1279,13 +2526,13 @@ impl<'a, T: Asn1Writable<'a>, V: Borrow<[T]>> SimpleAsn1Writable<'a> for SetOfWr
 /// used.
 #[cfg(feature = "const-generics")]
 #[derive(PartialEq, Debug)]
-pub struct Implicit<'a, T, const TAG: u8> {
+pub struct Implicit<'a, T, const TAG: u32> {
     pub inner: T,
     _lifetime: PhantomData<&'a ()>,
 }
 
 #[cfg(feature = "const-generics")]
-impl<'a, T, const TAG: u8> Implicit<'a, T, { TAG }> {
+impl<'a, T, const TAG: u32> Implicit<'a, T, { TAG }> {
     pub fn new(v: T) -> Self {
         Implicit {
             inner: v,
@@ -1299,30 +2546,34 @@ impl<'a, T, const TAG: u8> Implicit<'a, T, { TAG }> {
 }
 
 #[cfg(feature = "const-generics")]
-impl<'a, T, const TAG: u8> From<T> for Implicit<'a, T, { TAG }> {
+impl<'a, T, const TAG: u32> From<T> for Implicit<'a, T, { TAG }> {
     fn from(v: T) -> Self {
         Implicit::new(v)
     }
 }
 
 #[cfg(feature = "const-generics")]
-impl<'a, T: SimpleAsn1Readable<'a>, const TAG: u8> SimpleAsn1Readable<'a>
+impl<'a, T: SimpleAsn1Readable<'a>, const TAG: u32> SimpleAsn1Readable<'a>
     for Implicit<'a, T, { TAG }>
 {
-    const TAG: u8 = crate::implicit_tag(TAG, T::TAG);
+    const TAG: Tag = crate::implicit_tag(TAG, T::TAG);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
         Ok(Implicit::new(T::parse_data(data)?))
     }
 }
 
 #[cfg(feature = "const-generics")]
-impl<'a, T: SimpleAsn1Writable<'a>, const TAG: u8> SimpleAsn1Writable<'a>
+impl<'a, T: SimpleAsn1Writable<'a>, const TAG: u32> SimpleAsn1Writable<'a>
     for Implicit<'a, T, { TAG }>
 {
-    const TAG: u8 = crate::implicit_tag(TAG, T::TAG);
+    const TAG: Tag = crate::implicit_tag(TAG, T::TAG);
+
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        self.inner.write_data(dest)
+    }
 
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        self.inner.write_data(dest);
+    fn data_len(&self) -> Option<usize> {
+        self.inner.data_len()
     }
 }
 
@@ -1334,13 +2585,13 @@ impl<'a, T: SimpleAsn1Writable<'a>, const TAG: u8> SimpleAsn1Writable<'a>
 /// used.
 #[cfg(feature = "const-generics")]
 #[derive(PartialEq, Debug)]
-pub struct Explicit<'a, T, const TAG: u8> {
+pub struct Explicit<'a, T, const TAG: u32> {
     pub inner: T,
     _lifetime: PhantomData<&'a ()>,
 }
 
 #[cfg(feature = "const-generics")]
-impl<'a, T, const TAG: u8> Explicit<'a, T, { TAG }> {
+impl<'a, T, const TAG: u32> Explicit<'a, T, { TAG }> {
     pub fn new(v: T) -> Self {
         Explicit {
             inner: v,
@@ -1354,35 +2605,38 @@ impl<'a, T, const TAG: u8> Explicit<'a, T, { TAG }> {
 }
 
 #[cfg(feature = "const-generics")]
-impl<'a, T, const TAG: u8> From<T> for Explicit<'a, T, { TAG }> {
+impl<'a, T, const TAG: u32> From<T> for Explicit<'a, T, { TAG }> {
     fn from(v: T) -> Self {
         Explicit::new(v)
     }
 }
 
 #[cfg(feature = "const-generics")]
-impl<'a, T: Asn1Readable<'a>, const TAG: u8> SimpleAsn1Readable<'a> for Explicit<'a, T, { TAG }> {
-    const TAG: u8 = crate::explicit_tag(TAG);
+impl<'a, T: Asn1Readable<'a>, const TAG: u32> SimpleAsn1Readable<'a> for Explicit<'a, T, { TAG }> {
+    const TAG: Tag = crate::explicit_tag(TAG);
     fn parse_data(data: &'a [u8]) -> ParseResult<Self> {
         Ok(Explicit::new(parse(data, |p| p.read_element::<T>())?))
     }
 }
 
 #[cfg(feature = "const-generics")]
-impl<'a, T: Asn1Writable<'a>, const TAG: u8> SimpleAsn1Writable<'a> for Explicit<'a, T, { TAG }> {
-    const TAG: u8 = crate::explicit_tag(TAG);
-    fn write_data(&self, dest: &mut Vec<u8>) {
-        Writer::new(dest).write_element(&self.inner);
+impl<'a, T: Asn1Writable<'a>, const TAG: u32> SimpleAsn1Writable<'a> for Explicit<'a, T, { TAG }> {
+    const TAG: Tag = crate::explicit_tag(TAG);
+    fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+        Writer::new(dest).write_element(&self.inner)
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.inner.encoded_len()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        parse_single, IA5String, ParseError, ParseErrorKind, PrintableString, SequenceOf, SetOf,
-        Tlv, UtcTime,
+        parse_single, DateTime, IA5String, ParseError, ParseErrorKind, PrintableString, SequenceOf,
+        SetOf, Tag, Tlv, UtcTime,
     };
-    use chrono::TimeZone;
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
@@ -1407,7 +2661,7 @@ mod tests {
     #[test]
     fn test_tlv_parse() {
         let tlv = Tlv {
-            tag: 0x2,
+            tag: Tag::primitive(0x2),
             data: b"\x03",
             full_data: b"\x02\x01\x03",
         };
@@ -1415,7 +2669,7 @@ mod tests {
         assert_eq!(
             tlv.parse::<&[u8]>(),
             Err(ParseError::new(ParseErrorKind::UnexpectedTag {
-                actual: 0x2
+                actual: Tag::primitive(0x2),
             }))
         );
     }
@@ -1486,7 +2740,14 @@ mod tests {
 
     #[test]
     fn test_utctime_new() {
-        assert!(UtcTime::new(chrono::Utc.ymd(1950, 1, 1).and_hms(12, 0, 0)).is_some());
-        assert!(UtcTime::new(chrono::Utc.ymd(2050, 1, 1).and_hms(12, 0, 0)).is_none());
+        assert!(UtcTime::new(DateTime::new(1950, 1, 1, 12, 0, 0).unwrap()).is_some());
+        assert!(UtcTime::new(DateTime::new(2050, 1, 1, 12, 0, 0).unwrap()).is_none());
+        assert!(UtcTime::new(
+            DateTime::new(1950, 1, 1, 12, 0, 0)
+                .unwrap()
+                .with_nanosecond(1)
+                .unwrap()
+        )
+        .is_none());
     }
 }
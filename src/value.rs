@@ -0,0 +1,630 @@
+use crate::ber::MAX_DEPTH;
+use crate::parser::{ParseError, ParseErrorKind, ParseResult, Parser};
+use crate::types::{Asn1Readable, Asn1Writable, SimpleAsn1Readable, SimpleAsn1Writable};
+use crate::writer::_tlv_len;
+use crate::{
+    BMPString, BigInt, BitString, GeneralizedTime, IA5String, ObjectIdentifier, OwnedBMPString,
+    OwnedBigInt, OwnedBitString, OwnedIA5String, OwnedOctetString, OwnedPrintableString, OwnedTlv,
+    OwnedUniversalString, OwnedVisibleString, PrintableString, Real, RelativeOid, Tag, TagClass,
+    Tlv, UniversalString, UtcTime, Utf8String, VisibleString, WriteBuf, WriteResult, Writer,
+};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Index;
+use core::slice;
+
+const ENUMERATED_TAG: Tag = Tag::primitive(0x0a);
+const SEQUENCE_TAG: Tag = Tag::constructed(0x10);
+const SET_TAG: Tag = Tag::constructed(0x11);
+
+/// An arbitrary ASN.1 value, for use when the schema of the data isn't
+/// known, e.g. when inspecting it. `Value` can be read from any DER with
+/// [`parse_single`](crate::parse_single), and writing it produces exactly the
+/// DER it was read from.
+///
+/// Universal types which rust-asn1 supports are decoded into their
+/// corresponding variant. Other constructed values (e.g. those with
+/// context-specific tags) are decoded into [`Value::Constructed`], with their
+/// contents decoded as children, and all other primitive values are kept as
+/// a [`Value::Other`].
+///
+/// The children of a `Value` can be accessed with [`Value::get`],
+/// [`Value::find`], [`Value::iter`], or by indexing. A `Value` borrows from
+/// the data it was read from; [`Value::into_owned`] converts it into an
+/// [`OwnedValue`] which doesn't.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value<'a> {
+    Boolean(bool),
+    Integer(BigInt<'a>),
+    BitString(BitString<'a>),
+    OctetString(&'a [u8]),
+    Null,
+    ObjectIdentifier(ObjectIdentifier),
+    Real(Real),
+    /// An `ENUMERATED`, whose contents are encoded like an `INTEGER`.
+    Enumerated(BigInt<'a>),
+    Utf8String(Utf8String<'a>),
+    RelativeOid(RelativeOid),
+    PrintableString(PrintableString<'a>),
+    IA5String(IA5String<'a>),
+    UtcTime(UtcTime),
+    GeneralizedTime(GeneralizedTime),
+    VisibleString(VisibleString<'a>),
+    UniversalString(UniversalString<'a>),
+    BMPString(BMPString<'a>),
+    Sequence(Vec<Value<'a>>),
+    /// A `SET` or `SET OF`. Its elements are kept (and written) in the order
+    /// they appeared.
+    Set(Vec<Value<'a>>),
+    /// Any other constructed value, e.g. an `EXPLICIT` or `IMPLICIT` tagged
+    /// `SEQUENCE`.
+    Constructed {
+        tag: Tag,
+        children: Vec<Value<'a>>,
+    },
+    /// Any other primitive value, e.g. an `IMPLICIT` tagged `INTEGER` or a
+    /// `NumericString`.
+    Other(Tlv<'a>),
+}
+
+impl<'a> Value<'a> {
+    /// The tag this value is encoded with.
+    pub fn tag(&self) -> Tag {
+        match self {
+            Value::Boolean(_) => <bool as SimpleAsn1Readable>::TAG,
+            Value::Integer(_) => <BigInt as SimpleAsn1Readable>::TAG,
+            Value::BitString(_) => <BitString as SimpleAsn1Readable>::TAG,
+            Value::OctetString(_) => <&[u8] as SimpleAsn1Readable>::TAG,
+            Value::Null => <() as SimpleAsn1Readable>::TAG,
+            Value::ObjectIdentifier(_) => <ObjectIdentifier as SimpleAsn1Readable>::TAG,
+            Value::Real(_) => <Real as SimpleAsn1Readable>::TAG,
+            Value::Enumerated(_) => ENUMERATED_TAG,
+            Value::Utf8String(_) => <Utf8String as SimpleAsn1Readable>::TAG,
+            Value::RelativeOid(_) => <RelativeOid as SimpleAsn1Readable>::TAG,
+            Value::PrintableString(_) => <PrintableString as SimpleAsn1Readable>::TAG,
+            Value::IA5String(_) => <IA5String as SimpleAsn1Readable>::TAG,
+            Value::UtcTime(_) => <UtcTime as SimpleAsn1Readable>::TAG,
+            Value::GeneralizedTime(_) => <GeneralizedTime as SimpleAsn1Readable>::TAG,
+            Value::VisibleString(_) => <VisibleString as SimpleAsn1Readable>::TAG,
+            Value::UniversalString(_) => <UniversalString as SimpleAsn1Readable>::TAG,
+            Value::BMPString(_) => <BMPString as SimpleAsn1Readable>::TAG,
+            Value::Sequence(_) => SEQUENCE_TAG,
+            Value::Set(_) => SET_TAG,
+            Value::Constructed { tag, .. } => *tag,
+            Value::Other(tlv) => tlv.tag(),
+        }
+    }
+
+    /// The children of a `Sequence`, `Set`, or `Constructed` value. Other
+    /// values have no children.
+    pub fn children(&self) -> &[Value<'a>] {
+        match self {
+            Value::Sequence(children)
+            | Value::Set(children)
+            | Value::Constructed { children, .. } => children,
+            _ => &[],
+        }
+    }
+
+    /// Returns the child at `index`, if there is one.
+    pub fn get(&self, index: usize) -> Option<&Value<'a>> {
+        self.children().get(index)
+    }
+
+    /// Returns the first child with the tag `tag`, if there is one.
+    pub fn find(&self, tag: Tag) -> Option<&Value<'a>> {
+        self.children().iter().find(|v| v.tag() == tag)
+    }
+
+    /// Returns an iterator over this value's children.
+    pub fn iter(&self) -> slice::Iter<'_, Value<'a>> {
+        self.children().iter()
+    }
+
+    /// Converts this value into an [`OwnedValue`], which doesn't borrow from
+    /// the data it was read from.
+    pub fn into_owned(self) -> OwnedValue {
+        let children =
+            |children: Vec<Value<'_>>| children.into_iter().map(Value::into_owned).collect();
+        match self {
+            Value::Boolean(v) => OwnedValue::Boolean(v),
+            Value::Integer(v) => {
+                OwnedValue::Integer(OwnedBigInt::new(v.as_bytes().to_vec()).unwrap())
+            }
+            Value::BitString(v) => OwnedValue::BitString(
+                OwnedBitString::new(v.as_bytes().to_vec(), v.padding_bits()).unwrap(),
+            ),
+            Value::OctetString(v) => OwnedValue::OctetString(OwnedOctetString::new(v.to_vec())),
+            Value::Null => OwnedValue::Null,
+            Value::ObjectIdentifier(v) => OwnedValue::ObjectIdentifier(v),
+            Value::Real(v) => OwnedValue::Real(v),
+            Value::Enumerated(v) => {
+                OwnedValue::Enumerated(OwnedBigInt::new(v.as_bytes().to_vec()).unwrap())
+            }
+            Value::Utf8String(v) => OwnedValue::Utf8String(String::from(v.as_str())),
+            Value::RelativeOid(v) => OwnedValue::RelativeOid(v),
+            Value::PrintableString(v) => OwnedValue::PrintableString(
+                OwnedPrintableString::new(String::from(v.as_str())).unwrap(),
+            ),
+            Value::IA5String(v) => {
+                OwnedValue::IA5String(OwnedIA5String::new(String::from(v.as_str())).unwrap())
+            }
+            Value::UtcTime(v) => OwnedValue::UtcTime(v),
+            Value::GeneralizedTime(v) => OwnedValue::GeneralizedTime(v),
+            Value::VisibleString(v) => OwnedValue::VisibleString(
+                OwnedVisibleString::new(String::from(v.as_str())).unwrap(),
+            ),
+            Value::UniversalString(v) => OwnedValue::UniversalString(
+                OwnedUniversalString::new(v.as_utf32_be_bytes().to_vec()).unwrap(),
+            ),
+            Value::BMPString(v) => {
+                OwnedValue::BMPString(OwnedBMPString::new(v.as_utf16_be_bytes().to_vec()).unwrap())
+            }
+            Value::Sequence(v) => OwnedValue::Sequence(children(v)),
+            Value::Set(v) => OwnedValue::Set(children(v)),
+            Value::Constructed { tag, children: v } => OwnedValue::Constructed {
+                tag,
+                children: children(v),
+            },
+            Value::Other(tlv) => OwnedValue::Other(OwnedTlv {
+                full_data: tlv.full_data().to_vec(),
+            }),
+        }
+    }
+
+    fn from_tlv(tlv: Tlv<'a>, depth: u32) -> ParseResult<Value<'a>> {
+        let tag = tlv.tag();
+        let data = tlv.data();
+        if tag.is_constructed() {
+            if depth > MAX_DEPTH {
+                return Err(ParseError::new(ParseErrorKind::ExceedsMaxDepth));
+            }
+            let mut p = Parser::new(data);
+            let mut children = Vec::new();
+            while !p.is_empty() {
+                children.push(Value::from_tlv(p.read_tlv()?, depth + 1)?);
+            }
+            return Ok(match tag {
+                SEQUENCE_TAG => Value::Sequence(children),
+                SET_TAG => Value::Set(children),
+                _ => Value::Constructed { tag, children },
+            });
+        }
+        Value::from_primitive(tlv)
+    }
+
+    // Kept separate from `from_tlv`, so that the temporaries of every arm
+    // here don't make each level of its recursion use more stack.
+    #[inline(never)]
+    fn from_primitive(tlv: Tlv<'a>) -> ParseResult<Value<'a>> {
+        let tag = tlv.tag();
+        let data = tlv.data();
+        if tag.class() != TagClass::Universal {
+            return Ok(Value::Other(tlv));
+        }
+        Ok(match tag.value() {
+            0x01 => Value::Boolean(bool::parse_data(data)?),
+            0x02 => Value::Integer(BigInt::parse_data(data)?),
+            0x03 => Value::BitString(BitString::parse_data(data)?),
+            0x04 => Value::OctetString(data),
+            0x05 => {
+                <()>::parse_data(data)?;
+                Value::Null
+            }
+            0x06 => Value::ObjectIdentifier(ObjectIdentifier::parse_data(data)?),
+            0x09 => Value::Real(Real::parse_data(data)?),
+            0x0a => Value::Enumerated(BigInt::parse_data(data)?),
+            0x0c => Value::Utf8String(Utf8String::parse_data(data)?),
+            0x0d => Value::RelativeOid(RelativeOid::parse_data(data)?),
+            0x13 => Value::PrintableString(PrintableString::parse_data(data)?),
+            0x16 => Value::IA5String(IA5String::parse_data(data)?),
+            0x17 => {
+                // `UtcTime` accepts some encodings which aren't DER (without
+                // seconds, or with an offset); those are kept as they are so
+                // that they are written back unchanged.
+                let time = UtcTime::parse_data(data)?;
+                let mut encoded = WriteBuf::new();
+                let written = time.write_data(&mut encoded);
+                if written.is_err() || encoded.as_slice() != data {
+                    return Ok(Value::Other(tlv));
+                }
+                Value::UtcTime(time)
+            }
+            0x18 => Value::GeneralizedTime(GeneralizedTime::parse_data(data)?),
+            0x1a => Value::VisibleString(VisibleString::parse_data(data)?),
+            0x1c => Value::UniversalString(UniversalString::parse_data(data)?),
+            0x1e => Value::BMPString(BMPString::parse_data(data)?),
+            _ => Value::Other(tlv),
+        })
+    }
+}
+
+impl<'a> Index<usize> for Value<'a> {
+    type Output = Value<'a>;
+
+    /// Returns the child at `index`. Panics if there isn't one.
+    fn index(&self, index: usize) -> &Value<'a> {
+        &self.children()[index]
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b Value<'a> {
+    type Item = &'b Value<'a>;
+    type IntoIter = slice::Iter<'b, Value<'a>>;
+
+    fn into_iter(self) -> slice::Iter<'b, Value<'a>> {
+        self.iter()
+    }
+}
+
+impl<'a> Asn1Readable<'a> for Value<'a> {
+    fn parse(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        Value::from_tlv(parser.read_tlv()?, 0)
+    }
+
+    fn can_parse(_tag: Tag) -> bool {
+        true
+    }
+}
+
+fn children_len<'a, T: Asn1Writable<'a>>(children: &[T]) -> Option<usize> {
+    children
+        .iter()
+        .try_fold(0usize, |sum, child| sum.checked_add(child.encoded_len()?))
+}
+
+fn write_children<'a, T: Asn1Writable<'a>>(dest: &mut WriteBuf, children: &[T]) -> WriteResult {
+    let mut w = Writer::new(dest);
+    for child in children {
+        w.write_element(child)?;
+    }
+    Ok(())
+}
+
+impl<'a> Asn1Writable<'a> for Value<'a> {
+    fn write(&self, w: &mut Writer) -> WriteResult {
+        match self {
+            Value::Boolean(v) => w.write_element(v),
+            Value::Integer(v) => w.write_element(v),
+            Value::BitString(v) => w.write_element(v),
+            Value::OctetString(v) => w.write_element(v),
+            Value::Null => w.write_element(&()),
+            Value::ObjectIdentifier(v) => w.write_element(v),
+            Value::Real(v) => w.write_element(v),
+            Value::Enumerated(v) => {
+                w.write_tlv_with_len(ENUMERATED_TAG, v.data_len(), |dest| v.write_data(dest))
+            }
+            Value::Utf8String(v) => w.write_element(v),
+            Value::RelativeOid(v) => w.write_element(v),
+            Value::PrintableString(v) => w.write_element(v),
+            Value::IA5String(v) => w.write_element(v),
+            Value::UtcTime(v) => w.write_element(v),
+            Value::GeneralizedTime(v) => w.write_element(v),
+            Value::VisibleString(v) => w.write_element(v),
+            Value::UniversalString(v) => w.write_element(v),
+            Value::BMPString(v) => w.write_element(v),
+            Value::Sequence(children) => {
+                w.write_tlv_with_len(SEQUENCE_TAG, children_len(children), |dest| {
+                    write_children(dest, children)
+                })
+            }
+            Value::Set(children) => w.write_tlv_with_len(SET_TAG, children_len(children), |dest| {
+                write_children(dest, children)
+            }),
+            Value::Constructed { tag, children } => {
+                w.write_tlv_with_len(*tag, children_len(children), |dest| {
+                    write_children(dest, children)
+                })
+            }
+            Value::Other(tlv) => w.write_element(tlv),
+        }
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        match self {
+            Value::Boolean(v) => v.encoded_len(),
+            Value::Integer(v) => v.encoded_len(),
+            Value::BitString(v) => v.encoded_len(),
+            Value::OctetString(v) => v.encoded_len(),
+            Value::Null => ().encoded_len(),
+            Value::ObjectIdentifier(v) => v.encoded_len(),
+            Value::Real(v) => v.encoded_len(),
+            Value::Enumerated(v) => _tlv_len(ENUMERATED_TAG, v.data_len()?),
+            Value::Utf8String(v) => v.encoded_len(),
+            Value::RelativeOid(v) => v.encoded_len(),
+            Value::PrintableString(v) => v.encoded_len(),
+            Value::IA5String(v) => v.encoded_len(),
+            Value::UtcTime(v) => v.encoded_len(),
+            Value::GeneralizedTime(v) => v.encoded_len(),
+            Value::VisibleString(v) => v.encoded_len(),
+            Value::UniversalString(v) => v.encoded_len(),
+            Value::BMPString(v) => v.encoded_len(),
+            Value::Sequence(children) => _tlv_len(SEQUENCE_TAG, children_len(children)?),
+            Value::Set(children) => _tlv_len(SET_TAG, children_len(children)?),
+            Value::Constructed { tag, children } => _tlv_len(*tag, children_len(children)?),
+            Value::Other(tlv) => tlv.encoded_len(),
+        }
+    }
+}
+
+/// An owned [`Value`], for when it needs to outlive the data it was read
+/// from. Each variant holds the owned counterpart of the corresponding
+/// `Value` variant's contents.
+#[derive(Debug, PartialEq, Clone)]
+pub enum OwnedValue {
+    Boolean(bool),
+    Integer(OwnedBigInt),
+    BitString(OwnedBitString),
+    OctetString(OwnedOctetString),
+    Null,
+    ObjectIdentifier(ObjectIdentifier),
+    Real(Real),
+    Enumerated(OwnedBigInt),
+    Utf8String(String),
+    RelativeOid(RelativeOid),
+    PrintableString(OwnedPrintableString),
+    IA5String(OwnedIA5String),
+    UtcTime(UtcTime),
+    GeneralizedTime(GeneralizedTime),
+    VisibleString(OwnedVisibleString),
+    UniversalString(OwnedUniversalString),
+    BMPString(OwnedBMPString),
+    Sequence(Vec<OwnedValue>),
+    Set(Vec<OwnedValue>),
+    Constructed { tag: Tag, children: Vec<OwnedValue> },
+    Other(OwnedTlv),
+}
+
+impl OwnedValue {
+    /// Borrows the contents as a `Value`.
+    pub fn as_value(&self) -> Value<'_> {
+        fn children(children: &[OwnedValue]) -> Vec<Value<'_>> {
+            children.iter().map(OwnedValue::as_value).collect()
+        }
+        match self {
+            OwnedValue::Boolean(v) => Value::Boolean(*v),
+            OwnedValue::Integer(v) => Value::Integer(v.as_bigint()),
+            OwnedValue::BitString(v) => Value::BitString(v.as_bitstring()),
+            OwnedValue::OctetString(v) => Value::OctetString(v.as_bytes()),
+            OwnedValue::Null => Value::Null,
+            OwnedValue::ObjectIdentifier(v) => Value::ObjectIdentifier(v.clone()),
+            OwnedValue::Real(v) => Value::Real(*v),
+            OwnedValue::Enumerated(v) => Value::Enumerated(v.as_bigint()),
+            OwnedValue::Utf8String(v) => Value::Utf8String(Utf8String::new(v)),
+            OwnedValue::RelativeOid(v) => Value::RelativeOid(v.clone()),
+            OwnedValue::PrintableString(v) => Value::PrintableString(v.as_printable_string()),
+            OwnedValue::IA5String(v) => Value::IA5String(v.as_ia5string()),
+            OwnedValue::UtcTime(v) => Value::UtcTime(v.clone()),
+            OwnedValue::GeneralizedTime(v) => Value::GeneralizedTime(v.clone()),
+            OwnedValue::VisibleString(v) => Value::VisibleString(v.as_visible_string()),
+            OwnedValue::UniversalString(v) => Value::UniversalString(v.as_universal_string()),
+            OwnedValue::BMPString(v) => Value::BMPString(v.as_bmpstring()),
+            OwnedValue::Sequence(v) => Value::Sequence(children(v)),
+            OwnedValue::Set(v) => Value::Set(children(v)),
+            OwnedValue::Constructed { tag, children: v } => Value::Constructed {
+                tag: *tag,
+                children: children(v),
+            },
+            OwnedValue::Other(v) => Value::Other(v.as_tlv()),
+        }
+    }
+
+    /// The tag this value is encoded with.
+    pub fn tag(&self) -> Tag {
+        match self {
+            OwnedValue::Sequence(_) => SEQUENCE_TAG,
+            OwnedValue::Set(_) => SET_TAG,
+            OwnedValue::Constructed { tag, .. } => *tag,
+            _ => self.as_value().tag(),
+        }
+    }
+
+    /// The children of a `Sequence`, `Set`, or `Constructed` value. Other
+    /// values have no children.
+    pub fn children(&self) -> &[OwnedValue] {
+        match self {
+            OwnedValue::Sequence(children)
+            | OwnedValue::Set(children)
+            | OwnedValue::Constructed { children, .. } => children,
+            _ => &[],
+        }
+    }
+}
+
+impl<'a> Asn1Readable<'a> for OwnedValue {
+    fn parse(parser: &mut Parser<'a>) -> ParseResult<Self> {
+        Ok(Value::parse(parser)?.into_owned())
+    }
+
+    fn can_parse(_tag: Tag) -> bool {
+        true
+    }
+}
+
+impl<'a> Asn1Writable<'a> for OwnedValue {
+    fn write(&self, w: &mut Writer) -> WriteResult {
+        match self {
+            OwnedValue::Sequence(children) => {
+                w.write_tlv_with_len(SEQUENCE_TAG, children_len(children), |dest| {
+                    write_children(dest, children)
+                })
+            }
+            OwnedValue::Set(children) => {
+                w.write_tlv_with_len(SET_TAG, children_len(children), |dest| {
+                    write_children(dest, children)
+                })
+            }
+            OwnedValue::Constructed { tag, children } => {
+                w.write_tlv_with_len(*tag, children_len(children), |dest| {
+                    write_children(dest, children)
+                })
+            }
+            _ => self.as_value().write(w),
+        }
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        match self {
+            OwnedValue::Sequence(children) => _tlv_len(SEQUENCE_TAG, children_len(children)?),
+            OwnedValue::Set(children) => _tlv_len(SET_TAG, children_len(children)?),
+            OwnedValue::Constructed { tag, children } => _tlv_len(*tag, children_len(children)?),
+            _ => self.as_value().encoded_len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OwnedValue, Value};
+    use crate::{
+        parse_single, write_single, Asn1Writable, BigInt, DateTime, ObjectIdentifier, ParseError,
+        ParseErrorKind, PrintableString, Tag, TagClass, UtcTime,
+    };
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_parse_value() {
+        // SEQUENCE {
+        //   [0] EXPLICIT INTEGER 2,
+        //   INTEGER 0x1234,
+        //   SEQUENCE { OBJECT IDENTIFIER 1.2.840.113549.1.1.11, NULL },
+        //   SET { PrintableString "ab" },
+        //   [1] IMPLICIT BOOLEAN true,
+        //   NumericString "12",
+        //   UTCTime 0001010000Z (no seconds),
+        //   ENUMERATED 1
+        // }
+        let data = b"\x30\x35\xa0\x03\x02\x01\x02\x02\x02\x12\x34\x30\x0d\x06\x09\x2a\x86\x48\x86\xf7\x0d\x01\x01\x0b\x05\x00\x31\x04\x13\x02ab\x81\x01\xff\x12\x0212\x17\x0b0001010000Z\x0a\x01\x01";
+        let value = parse_single::<Value>(data).unwrap();
+
+        assert_eq!(value.tag(), Tag::constructed(0x10));
+        assert_eq!(value.children().len(), 8);
+        assert_eq!(
+            value[0],
+            Value::Constructed {
+                tag: Tag::new(0, TagClass::ContextSpecific, true),
+                children: vec![Value::Integer(BigInt::new(b"\x02").unwrap())],
+            }
+        );
+        assert_eq!(value[1], Value::Integer(BigInt::new(b"\x12\x34").unwrap()));
+        assert_eq!(
+            value[2][0],
+            Value::ObjectIdentifier(
+                ObjectIdentifier::from_string("1.2.840.113549.1.1.11").unwrap()
+            )
+        );
+        assert_eq!(value[2][1], Value::Null);
+        assert_eq!(
+            value[3].children(),
+            &[Value::PrintableString(PrintableString::new("ab").unwrap())]
+        );
+        assert_eq!(
+            value[4].tag(),
+            Tag::new(1, TagClass::ContextSpecific, false)
+        );
+        assert!(matches!(value[4], Value::Other(_)));
+        assert!(matches!(value[5], Value::Other(_)));
+        assert!(matches!(value[6], Value::Other(_)));
+        assert_eq!(value[7], Value::Enumerated(BigInt::new(b"\x01").unwrap()));
+
+        assert_eq!(write_single(&value).unwrap(), data);
+    }
+
+    #[test]
+    fn test_navigate() {
+        let value =
+            parse_single::<Value>(b"\x30\x0c\x02\x01\x01\x30\x03\x02\x01\x02\xa3\x00\x05\x00")
+                .unwrap();
+
+        assert_eq!(
+            value.get(0),
+            Some(&Value::Integer(BigInt::new(b"\x01").unwrap()))
+        );
+        assert_eq!(value.get(4), None);
+        assert_eq!(value[0].get(0), None);
+        assert_eq!(value[0].children(), &[]);
+        assert_eq!(
+            value
+                .find(Tag::constructed(0x10))
+                .map(|v| v.children().len()),
+            Some(1)
+        );
+        assert_eq!(
+            value.find(Tag::new(3, TagClass::ContextSpecific, true)),
+            Some(&Value::Constructed {
+                tag: Tag::new(3, TagClass::ContextSpecific, true),
+                children: vec![],
+            })
+        );
+        assert_eq!(value.find(Tag::primitive(0x04)), None);
+        assert_eq!(
+            value.iter().map(|v| v.tag()).collect::<Vec<_>>(),
+            vec![
+                Tag::primitive(0x02),
+                Tag::constructed(0x10),
+                Tag::new(3, TagClass::ContextSpecific, true),
+                Tag::primitive(0x05),
+            ]
+        );
+        assert_eq!((&value).into_iter().count(), 4);
+    }
+
+    #[test]
+    fn test_write_value() {
+        let value = Value::Sequence(vec![
+            Value::Boolean(true),
+            Value::UtcTime(UtcTime::new(DateTime::new(2000, 1, 1, 0, 0, 0).unwrap()).unwrap()),
+            Value::Set(vec![]),
+        ]);
+        let data = write_single(&value).unwrap();
+        assert_eq!(data, b"\x30\x14\x01\x01\xff\x17\x0d000101000000Z\x31\x00");
+        assert_eq!(parse_single::<Value>(&data), Ok(value));
+    }
+
+    #[test]
+    fn test_owned_value() {
+        // A SEQUENCE containing one of each kind of value.
+        let data = b"\x30\x6a\x01\x01\xff\x02\x02\x12\x34\x03\x02\x04\xf0\x04\x02\x01\x02\x05\x00\x06\x03\x2a\x03\x04\x09\x03\x80\x00\x01\x0a\x01\x01\x0c\x03h\xc3\xa9\x0d\x02\x08\x01\x13\x02ab\x16\x02a\x40\x17\x0d000101000000Z\x18\x0f20000101000000Z\x1a\x02ab\x1c\x04\x00\x00\x00a\x1e\x02\x00a\x31\x03\x02\x01\x01\xa0\x03\x02\x01\x02\x81\x01\xff";
+        let owned = {
+            let data = data.to_vec();
+            parse_single::<Value>(&data).unwrap().into_owned()
+        };
+
+        assert_eq!(owned.tag(), Tag::constructed(0x10));
+        assert_eq!(owned.children().len(), 20);
+        assert_eq!(
+            owned.children()[8],
+            OwnedValue::Utf8String("h\u{e9}".into())
+        );
+        assert_eq!(owned.as_value(), parse_single::<Value>(data).unwrap());
+        assert_eq!(parse_single::<OwnedValue>(data).as_ref(), Ok(&owned));
+        assert_eq!(owned.encoded_len(), Some(data.len()));
+        assert_eq!(write_single(&owned).unwrap(), data);
+    }
+
+    #[test]
+    fn test_parse_value_errors() {
+        assert_eq!(
+            parse_single::<Value>(b"\x01\x01\x01"),
+            Err(ParseError::new(ParseErrorKind::InvalidValue))
+        );
+        assert_eq!(
+            parse_single::<Value>(b"\x30\x03\x02\x00"),
+            Err(ParseError::new(ParseErrorKind::ShortData))
+        );
+        assert_eq!(
+            parse_single::<Value>(b"\x30\x02\x02\x00"),
+            Err(ParseError::new(ParseErrorKind::InvalidValue))
+        );
+
+        let mut value = Value::Null;
+        for _ in 0..200 {
+            value = Value::Sequence(vec![value]);
+        }
+        assert_eq!(
+            parse_single::<Value>(&write_single(&value).unwrap()),
+            Err(ParseError::new(ParseErrorKind::ExceedsMaxDepth))
+        );
+    }
+}
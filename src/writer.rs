@@ -1,7 +1,267 @@
+use crate::parser::Parser;
 use crate::types::{Asn1Writable, SimpleAsn1Writable};
-use alloc::vec;
+use crate::Tag;
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
+use core::fmt;
 
+/// WriteError are returned when there is an error writing ASN.1 data.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WriteError {
+    /// A value could not be encoded, e.g. a `UtcTime` whose year is outside
+    /// of the 1950-2049 range it can represent.
+    InvalidValue,
+    /// The encoded data would have been longer than the limit given to
+    /// [`WriteBuf::with_limit`].
+    LimitExceeded,
+    /// The encoded data would not have fit in the buffer given to
+    /// [`WriteBuf::from_slice`].
+    BufferTooSmall,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ASN.1 writing error: ")?;
+        match self {
+            WriteError::InvalidValue => write!(f, "invalid value"),
+            WriteError::LimitExceeded => write!(f, "output size limit exceeded"),
+            WriteError::BufferTooSmall => write!(f, "output buffer too small"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WriteError {}
+
+pub type WriteResult<T = ()> = Result<T, WriteError>;
+
+/// The buffer that encoded data is written to. It's either a `Vec<u8>` which
+/// grows as needed (with the `alloc` feature), or a caller-provided
+/// `&mut [u8]`, in which case writes which don't fit fail with
+/// [`WriteError::BufferTooSmall`]. If it was created with a limit, writes
+/// which would exceed it fail with [`WriteError::LimitExceeded`].
+#[derive(Debug)]
+pub struct WriteBuf<'a> {
+    storage: Storage<'a>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug)]
+enum Storage<'a> {
+    #[cfg(feature = "alloc")]
+    Vec(Vec<u8>),
+    // Only the first `len` bytes of `data` have been written.
+    Slice {
+        data: &'a mut [u8],
+        len: usize,
+    },
+}
+
+impl<'a> WriteBuf<'a> {
+    /// Creates an empty `WriteBuf` without a limit on its size.
+    #[cfg(feature = "alloc")]
+    pub fn new() -> WriteBuf<'a> {
+        WriteBuf {
+            storage: Storage::Vec(Vec::new()),
+            limit: None,
+        }
+    }
+
+    /// Creates an empty `WriteBuf` which may hold at most `limit` bytes.
+    #[cfg(feature = "alloc")]
+    pub fn with_limit(limit: usize) -> WriteBuf<'a> {
+        WriteBuf {
+            storage: Storage::Vec(Vec::new()),
+            limit: Some(limit),
+        }
+    }
+
+    /// Creates an empty `WriteBuf` which writes into `data`. Writes which
+    /// don't fit in it fail with [`WriteError::BufferTooSmall`].
+    pub fn from_slice(data: &'a mut [u8]) -> WriteBuf<'a> {
+        WriteBuf {
+            storage: Storage::Slice { data, len: 0 },
+            limit: None,
+        }
+    }
+
+    // Returns an empty `WriteBuf` for data which will later be copied into
+    // this one, with the space which remains in this one as its limit, or
+    // `None` if this one writes into a slice, since that must never allocate.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn scratch(&self) -> Option<WriteBuf<'static>> {
+        match self.storage {
+            Storage::Vec(ref data) => Some(WriteBuf {
+                storage: Storage::Vec(Vec::new()),
+                limit: self.limit.map(|limit| limit - data.len()),
+            }),
+            Storage::Slice { .. } => None,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        match self.storage {
+            #[cfg(feature = "alloc")]
+            Storage::Vec(ref data) => data,
+            Storage::Slice { ref data, len } => &data[..len],
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.storage {
+            Storage::Vec(data) => data,
+            Storage::Slice { data, len } => data[..len].to_vec(),
+        }
+    }
+
+    // Checks that `n` more bytes may be written, which also requires them to
+    // fit when writing into a slice.
+    #[inline]
+    fn check_limit(&self, n: usize) -> WriteResult {
+        if let Some(limit) = self.limit {
+            if n > limit - self.len() {
+                return Err(WriteError::LimitExceeded);
+            }
+        }
+        match self.storage {
+            Storage::Slice { ref data, len } if n > data.len() - len => {
+                Err(WriteError::BufferTooSmall)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Makes room for `n` more bytes up front, when it's known how many bytes
+    // will be written.
+    fn reserve(&mut self, n: usize) -> WriteResult {
+        self.check_limit(n)?;
+        #[cfg(feature = "alloc")]
+        {
+            if let Storage::Vec(ref mut data) = self.storage {
+                data.reserve(n);
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn push_byte(&mut self, b: u8) -> WriteResult {
+        if let Some(limit) = self.limit {
+            if self.len() >= limit {
+                return Err(WriteError::LimitExceeded);
+            }
+        }
+        match self.storage {
+            #[cfg(feature = "alloc")]
+            Storage::Vec(ref mut data) => data.push(b),
+            Storage::Slice {
+                ref mut data,
+                ref mut len,
+            } => {
+                *data.get_mut(*len).ok_or(WriteError::BufferTooSmall)? = b;
+                *len += 1;
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn push_slice(&mut self, data: &[u8]) -> WriteResult {
+        if let Some(limit) = self.limit {
+            if data.len() > limit - self.len() {
+                return Err(WriteError::LimitExceeded);
+            }
+        }
+        match self.storage {
+            #[cfg(feature = "alloc")]
+            Storage::Vec(ref mut v) => v.extend_from_slice(data),
+            Storage::Slice {
+                data: ref mut dest,
+                ref mut len,
+            } => {
+                dest.get_mut(*len..*len + data.len())
+                    .ok_or(WriteError::BufferTooSmall)?
+                    .copy_from_slice(data);
+                *len += data.len();
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [u8] {
+        match self.storage {
+            #[cfg(feature = "alloc")]
+            Storage::Vec(ref mut data) => data,
+            Storage::Slice { ref mut data, len } => &mut data[..len],
+        }
+    }
+
+    // Inserts `data` at `pos`, shifting everything after it.
+    fn insert_at(&mut self, pos: usize, data: &[u8]) -> WriteResult {
+        let len = self.len();
+        // Grow by `data.len()` bytes, whose values are overwritten below.
+        self.push_slice(data)?;
+        let dest = self.as_mut_slice();
+        dest.copy_within(pos..len, pos + data.len());
+        dest[pos..pos + data.len()].copy_from_slice(data);
+        Ok(())
+    }
+
+    // Sorts the elements written since `start` into ascending order by
+    // `less`, keeping equal elements in the order they were written. This is
+    // an insertion sort which moves the elements in place, so that no
+    // allocation is needed.
+    pub(crate) fn sort_elements_from<F: Fn(&[u8], &[u8]) -> bool>(
+        &mut self,
+        start: usize,
+        less: F,
+    ) {
+        let data = &mut self.as_mut_slice()[start..];
+        let mut sorted_len = 0;
+        while sorted_len < data.len() {
+            let element_len = _element_len(&data[sorted_len..]);
+            let element = sorted_len..sorted_len + element_len;
+            let mut pos = 0;
+            while pos < sorted_len {
+                let other_len = _element_len(&data[pos..]);
+                if less(&data[element.clone()], &data[pos..pos + other_len]) {
+                    break;
+                }
+                pos += other_len;
+            }
+            data[pos..element.end].rotate_right(element_len);
+            sorted_len = element.end;
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Default for WriteBuf<'_> {
+    fn default() -> Self {
+        WriteBuf::new()
+    }
+}
+
+// Returns the length of the encoding of the element which `data` starts
+// with, which must have been written by this crate.
+fn _element_len(data: &[u8]) -> usize {
+    let tlv = Parser::new(data).read_tlv().unwrap();
+    tlv.full_data().len()
+}
+
+#[inline]
 fn _length_length(length: usize) -> u8 {
     let mut i = length;
     let mut num_bytes = 1;
@@ -11,39 +271,37 @@ fn _length_length(length: usize) -> u8 {
     }
     num_bytes
 }
-fn _insert_at_position(vec: &mut Vec<u8>, pos: usize, data: &[u8]) {
-    for _ in 0..data.len() {
-        vec.push(0);
-    }
-    let src_range = pos..vec.len() - data.len();
-    vec.copy_within(src_range, pos + data.len());
-    vec[pos..pos + data.len()].copy_from_slice(data);
-}
 
 /// Encapsulates an ongoing write. For almost all use-cases the correct
 /// entrypoint is [`write()`] or [`write_single()`].
-pub struct Writer<'a> {
-    pub(crate) data: &'a mut Vec<u8>,
+pub struct Writer<'a, 'b> {
+    pub(crate) data: &'a mut WriteBuf<'b>,
 }
 
-impl Writer<'_> {
+impl Writer<'_, '_> {
     #[inline]
     #[doc(hidden)]
-    pub fn new(data: &mut Vec<u8>) -> Writer {
+    pub fn new<'a, 'b>(data: &'a mut WriteBuf<'b>) -> Writer<'a, 'b> {
         Writer { data }
     }
 
     /// Writes a single element to the output.
     #[inline]
-    pub fn write_element<'a, T: Asn1Writable<'a>>(&mut self, val: &T) {
-        val.write(self);
+    pub fn write_element<'a, T: Asn1Writable<'a>>(&mut self, val: &T) -> WriteResult {
+        val.write(self)
     }
 
     /// This is an alias for `write_element::<Explicit<T, tag>>`` for use when
     /// MSRV is <1.51.
-    pub fn write_explicit_element<'a, T: Asn1Writable<'a>>(&mut self, val: &T, tag: u8) {
+    pub fn write_explicit_element<'a, T: Asn1Writable<'a>>(
+        &mut self,
+        val: &T,
+        tag: u32,
+    ) -> WriteResult {
         let tag = crate::explicit_tag(tag);
-        self.write_tlv(tag, |dest| Writer::new(dest).write_element(val));
+        self.write_tlv_with_len(tag, val.encoded_len(), |dest| {
+            Writer::new(dest).write_element(val)
+        })
     }
 
     /// This is an alias for `write_element::<Option<Explicit<T, tag>>>` for
@@ -51,19 +309,24 @@ impl Writer<'_> {
     pub fn write_optional_explicit_element<'a, T: Asn1Writable<'a>>(
         &mut self,
         val: &Option<T>,
-        tag: u8,
-    ) {
+        tag: u32,
+    ) -> WriteResult {
         if let Some(v) = val {
-            let tag = crate::explicit_tag(tag);
-            self.write_tlv(tag, |dest| Writer::new(dest).write_element(v));
+            self.write_explicit_element(v, tag)
+        } else {
+            Ok(())
         }
     }
 
     /// This is an alias for `write_element::<Implicit<T, tag>>` for use when
     /// MSRV is <1.51.
-    pub fn write_implicit_element<'a, T: SimpleAsn1Writable<'a>>(&mut self, val: &T, tag: u8) {
+    pub fn write_implicit_element<'a, T: SimpleAsn1Writable<'a>>(
+        &mut self,
+        val: &T,
+        tag: u32,
+    ) -> WriteResult {
         let tag = crate::implicit_tag(tag, T::TAG);
-        self.write_tlv(tag, |dest| val.write_data(dest));
+        self.write_tlv_with_len(tag, val.data_len(), |dest| val.write_data(dest))
     }
 
     /// This is an alias for `write_element::<Option<Implicit<T, tag>>>` for
@@ -71,96 +334,297 @@ impl Writer<'_> {
     pub fn write_optional_implicit_element<'a, T: SimpleAsn1Writable<'a>>(
         &mut self,
         val: &Option<T>,
-        tag: u8,
-    ) {
+        tag: u32,
+    ) -> WriteResult {
         if let Some(v) = val {
-            let tag = crate::implicit_tag(tag, T::TAG);
-            self.write_tlv(tag, |dest| v.write_data(dest));
+            self.write_implicit_element(v, tag)
+        } else {
+            Ok(())
         }
     }
 
     /// Writes a TLV with the specified tag where the value is any bytes
-    /// written to the `Vec` in the callback. The length portion of the
+    /// written to the `WriteBuf` in the callback. The length portion of the
     /// TLV is automatically computed.
     #[inline]
-    pub fn write_tlv<F: FnOnce(&mut Vec<u8>)>(&mut self, tag: u8, body: F) {
-        self.data.push(tag);
-        // Push a 0-byte placeholder for the length. Needing only a single byte
-        // for the element is probably the most common case.
-        self.data.push(0);
+    pub fn write_tlv<F: FnOnce(&mut WriteBuf) -> WriteResult>(
+        &mut self,
+        tag: Tag,
+        body: F,
+    ) -> WriteResult {
+        let start_len = _start_tlv(self.data, tag)?;
+        body(self.data)?;
+        _finish_tlv(self.data, start_len)
+    }
+
+    // Like `write_tlv`, but when the length of the value is known up front,
+    // it's written before the value rather than after it, so the value
+    // doesn't need to be moved to make room for a long form length.
+    pub(crate) fn write_tlv_with_len<F: FnOnce(&mut WriteBuf) -> WriteResult>(
+        &mut self,
+        tag: Tag,
+        data_len: Option<usize>,
+        body: F,
+    ) -> WriteResult {
+        let data_len = match data_len {
+            Some(data_len) => data_len,
+            None => return self.write_tlv(tag, body),
+        };
+        tag.write_bytes(self.data)?;
+        _write_length(self.data, data_len)?;
         let start_len = self.data.len();
-        body(self.data);
-        let added_len = self.data.len() - start_len;
-        if added_len >= 128 {
-            let n = _length_length(added_len);
-            self.data[start_len - 1] = 0x80 | n;
-            let mut length_buf = [0u8; 8];
-            for (pos, i) in (1..n + 1).rev().enumerate() {
-                length_buf[pos] = (added_len >> ((i - 1) * 8)) as u8;
-            }
-            _insert_at_position(self.data, start_len, &length_buf[..n as usize]);
-        } else {
-            self.data[start_len - 1] = added_len as u8;
+        body(self.data)?;
+        // The length has already been written, so a value which doesn't
+        // match it can't be encoded correctly.
+        if self.data.len() - start_len != data_len {
+            return Err(WriteError::InvalidValue);
+        }
+        Ok(())
+    }
+}
+
+// Writes the encoding of a TLV's length.
+fn _write_length(data: &mut WriteBuf, length: usize) -> WriteResult {
+    if length >= 128 {
+        let n = _length_length(length);
+        data.push_byte(0x80 | n)?;
+        for i in (1..n + 1).rev() {
+            data.push_byte((length >> ((i - 1) * 8)) as u8)?;
+        }
+        Ok(())
+    } else {
+        data.push_byte(length as u8)
+    }
+}
+
+// Returns the length of the encoding of a TLV with `tag` and a value of
+// `data_len` bytes.
+#[inline]
+pub(crate) fn _tlv_len(tag: Tag, data_len: usize) -> Option<usize> {
+    let length_len = if data_len >= 128 {
+        1 + usize::from(_length_length(data_len))
+    } else {
+        1
+    };
+    (tag.encoded_len() + length_len).checked_add(data_len)
+}
+
+// Writes `tag` and a placeholder for the length of a TLV whose value will be
+// written next, returning the position the value starts at. The TLV must be
+// completed with `_finish_tlv`.
+pub(crate) fn _start_tlv(data: &mut WriteBuf, tag: Tag) -> WriteResult<usize> {
+    tag.write_bytes(data)?;
+    // Push a 0-byte placeholder for the length. Needing only a single byte
+    // for the element is probably the most common case.
+    data.push_byte(0)?;
+    Ok(data.len())
+}
+
+pub(crate) fn _finish_tlv(data: &mut WriteBuf, start_len: usize) -> WriteResult {
+    let added_len = data.len() - start_len;
+    if added_len >= 128 {
+        let n = _length_length(added_len);
+        data.as_mut_slice()[start_len - 1] = 0x80 | n;
+        let mut length_buf = [0u8; 8];
+        for (pos, i) in (1..n + 1).rev().enumerate() {
+            length_buf[pos] = (added_len >> ((i - 1) * 8)) as u8;
         }
+        data.insert_at(start_len, &length_buf[..n as usize])
+    } else {
+        data.as_mut_slice()[start_len - 1] = added_len as u8;
+        Ok(())
     }
 }
 
+// Runs `f` with a `Writer` for `buf`, returning the data it wrote.
+#[cfg(feature = "alloc")]
+fn write_to_vec<E: From<WriteError>, F: Fn(&mut Writer) -> Result<(), E>>(
+    mut buf: WriteBuf,
+    f: F,
+) -> Result<Vec<u8>, E> {
+    f(&mut Writer::new(&mut buf))?;
+    Ok(buf.into_vec())
+}
+
 /// Constructs a writer and invokes a callback which writes ASN.1 elements into
 /// the writer, then returns the generated DER bytes.
+#[cfg(feature = "alloc")]
 #[inline]
-pub fn write<F: Fn(&mut Writer)>(f: F) -> Vec<u8> {
-    let mut v = vec![];
-    let mut w = Writer::new(&mut v);
-    f(&mut w);
-    v
+pub fn write<F: Fn(&mut Writer) -> WriteResult>(f: F) -> WriteResult<Vec<u8>> {
+    write_to_vec(WriteBuf::new(), f)
+}
+
+/// Like [`write()`], but the callback may return its own error type, so
+/// that errors other than [`WriteError`]s can be propagated with `?`.
+///
+/// ```
+/// #[derive(Debug)]
+/// enum Error {
+///     Write(asn1::WriteError),
+///     TooManyItems,
+/// }
+///
+/// impl From<asn1::WriteError> for Error {
+///     fn from(e: asn1::WriteError) -> Error {
+///         Error::Write(e)
+///     }
+/// }
+///
+/// let items = [1u8, 2, 3];
+/// let result = asn1::try_write(|w| {
+///     if items.len() > 2 {
+///         return Err(Error::TooManyItems);
+///     }
+///     w.write_element(&asn1::SequenceOfWriter::new(&items[..]))?;
+///     Ok(())
+/// });
+/// assert!(matches!(result, Err(Error::TooManyItems)));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn try_write<E: From<WriteError>, F: Fn(&mut Writer) -> Result<(), E>>(
+    f: F,
+) -> Result<Vec<u8>, E> {
+    write_to_vec(WriteBuf::new(), f)
+}
+
+/// Like [`write()`], but fails with [`WriteError::LimitExceeded`] if more
+/// than `limit` bytes would be written.
+#[cfg(feature = "alloc")]
+pub fn write_with_limit<F: Fn(&mut Writer) -> WriteResult>(
+    limit: usize,
+    f: F,
+) -> WriteResult<Vec<u8>> {
+    write_to_vec(WriteBuf::with_limit(limit), f)
 }
 
 /// Writes a single top-level ASN.1 element, returning the generated DER bytes.
 /// Most often this will be used where `T` is a type with
 /// `#[derive(asn1::Asn1Write)]`.
-pub fn write_single<'a, T: Asn1Writable<'a>>(v: &T) -> Vec<u8> {
-    write(|w| {
-        w.write_element(v);
-    })
+#[cfg(feature = "alloc")]
+pub fn write_single<'a, T: Asn1Writable<'a>>(v: &T) -> WriteResult<Vec<u8>> {
+    let mut buf = WriteBuf::new();
+    write_single_to(&mut buf, v)?;
+    Ok(buf.into_vec())
+}
+
+// Writes `v` to `buf`, first reserving room for all of it if its length is
+// known.
+fn write_single_to<'a, T: Asn1Writable<'a>>(buf: &mut WriteBuf, v: &T) -> WriteResult {
+    if let Some(len) = v.encoded_len() {
+        buf.reserve(len)?;
+    }
+    Writer::new(buf).write_element(v)
+}
+
+/// Like [`write_single()`], but fails with [`WriteError::LimitExceeded`] if
+/// more than `limit` bytes would be written.
+#[cfg(feature = "alloc")]
+pub fn write_single_with_limit<'a, T: Asn1Writable<'a>>(
+    v: &T,
+    limit: usize,
+) -> WriteResult<Vec<u8>> {
+    let mut buf = WriteBuf::with_limit(limit);
+    write_single_to(&mut buf, v)?;
+    Ok(buf.into_vec())
+}
+
+/// Like [`write()`], but writes into `buf` rather than allocating, returning
+/// the number of bytes written. Fails with [`WriteError::BufferTooSmall`] if
+/// they don't fit.
+///
+/// ```
+/// let mut buf = [0; 8];
+/// let len = asn1::write_to_slice(&mut buf, |w| {
+///     w.write_element(&asn1::SequenceWriter::new(&|w| {
+///         w.write_element(&1u8)?;
+///         w.write_element(&3u8)
+///     }))
+/// });
+/// assert_eq!(len, Ok(8));
+/// assert_eq!(&buf, b"\x30\x06\x02\x01\x01\x02\x01\x03");
+/// ```
+pub fn write_to_slice<F: Fn(&mut Writer) -> WriteResult>(
+    buf: &mut [u8],
+    f: F,
+) -> WriteResult<usize> {
+    let mut buf = WriteBuf::from_slice(buf);
+    f(&mut Writer::new(&mut buf))?;
+    Ok(buf.len())
+}
+
+/// Like [`write_single()`], but writes into `buf` rather than allocating,
+/// returning the number of bytes written. Fails with
+/// [`WriteError::BufferTooSmall`] if they don't fit, without writing anything
+/// if `v`'s length is known up front.
+pub fn write_single_to_slice<'a, T: Asn1Writable<'a>>(v: &T, buf: &mut [u8]) -> WriteResult<usize> {
+    let mut buf = WriteBuf::from_slice(buf);
+    write_single_to(&mut buf, v)?;
+    Ok(buf.len())
 }
 
 #[cfg(test)]
 mod tests {
     use alloc::vec;
 
-    use chrono::{TimeZone, Utc};
-
-    use super::{_insert_at_position, write, write_single, Writer};
-    use crate::types::Asn1Writable;
+    use super::{
+        write, write_single, write_single_to_slice, write_single_with_limit, write_to_slice,
+        write_with_limit, WriteBuf, WriteError, WriteResult, Writer,
+    };
+    use crate::types::{Asn1Writable, SimpleAsn1Writable};
     use crate::{
-        parse_single, BMPString, BigInt, BigUint, BitString, Choice1, Choice2, Choice3, Enumerated,
-        GeneralizedTime, IA5String, ObjectIdentifier, OwnedBitString, PrintableString, Sequence,
-        SequenceOf, SequenceOfWriter, SequenceWriter, SetOf, SetOfWriter, Tlv, UniversalString,
-        UtcTime, Utf8String, VisibleString,
+        parse_single, BMPString, BigInt, BigUint, BitString, Choice1, Choice2, Choice3, DateTime,
+        Enumerated, GeneralizedTime, IA5String, ObjectIdentifier, OidIri, OwnedBMPString,
+        OwnedBigInt, OwnedBigUint, OwnedBitString, OwnedIA5String, OwnedOctetString, OwnedOidIri,
+        OwnedPrintableString, OwnedRelativeOidIri, OwnedSequence, OwnedSetOf, OwnedTlv,
+        OwnedUniversalString, OwnedVisibleString, PrintableString, Real, RelativeOid,
+        RelativeOidIri, Sequence, SequenceOf, SequenceOfWriter, SequenceWriter, SetOf, SetOfWriter,
+        Tag, TagClass, Tlv, UniversalString, UtcTime, Utf8String, VisibleString,
     };
     #[cfg(feature = "const-generics")]
     use crate::{Explicit, Implicit};
+    use alloc::boxed::Box;
+    use alloc::string::String;
+    use alloc::vec::Vec;
 
     fn assert_writes<'a, T>(data: &[(T, &[u8])])
     where
         T: Asn1Writable<'a>,
     {
         for (val, expected) in data {
-            let result = write_single(val);
+            let result = write_single(val).unwrap();
             assert_eq!(&result, expected);
+            // Only a `SequenceWriter`'s length isn't known before it's
+            // written.
+            if let Some(len) = val.encoded_len() {
+                assert_eq!(len, result.len());
+            }
+
+            // Writing into a slice gives the same result, and fails if it's
+            // too small.
+            let mut buf = vec![0; result.len()];
+            assert_eq!(write_single_to_slice(val, &mut buf), Ok(result.len()));
+            assert_eq!(buf, result);
+            if !result.is_empty() {
+                assert_eq!(
+                    write_single_to_slice(val, &mut buf[1..]),
+                    Err(WriteError::BufferTooSmall)
+                );
+            }
         }
     }
 
     #[test]
     fn test_insert_at_position() {
-        let mut v = vec![1, 2, 3, 4];
-        _insert_at_position(&mut v, 2, &[5, 6]);
-        assert_eq!(&v, &[1, 2, 5, 6, 3, 4]);
+        let mut buf = WriteBuf::new();
+        buf.push_slice(&[1, 2, 3, 4]).unwrap();
+        buf.insert_at(2, &[5, 6]).unwrap();
+        assert_eq!(buf.as_slice(), &[1, 2, 5, 6, 3, 4]);
     }
 
     #[test]
     fn test_write_element() {
-        assert_eq!(write(|w| w.write_element(&())), b"\x05\x00");
+        assert_eq!(write(|w| w.write_element(&())).unwrap(), b"\x05\x00");
     }
 
     #[test]
@@ -182,6 +646,17 @@ mod tests {
         ])
     }
 
+    #[test]
+    fn test_write_owned_octet_string() {
+        assert_writes::<OwnedOctetString>(&[
+            (OwnedOctetString::new(vec![]), b"\x04\x00"),
+            (
+                OwnedOctetString::new(vec![1, 2, 3]),
+                b"\x04\x03\x01\x02\x03",
+            ),
+        ]);
+    }
+
     #[test]
     fn test_write_printable_string() {
         assert_writes::<PrintableString>(&[
@@ -254,6 +729,31 @@ mod tests {
         )])
     }
 
+    #[test]
+    fn test_write_owned_strings() {
+        assert_writes(&[(
+            OwnedPrintableString::new(String::from("Test User 1")).unwrap(),
+            b"\x13\x0bTest User 1",
+        )]);
+        assert_writes(&[(
+            OwnedIA5String::new(String::from("Test User 1")).unwrap(),
+            b"\x16\x0bTest User 1",
+        )]);
+        assert_writes(&[(String::from("Test User 1"), b"\x0c\x0bTest User 1")]);
+        assert_writes(&[(
+            OwnedVisibleString::new(String::from("Test User 1")).unwrap(),
+            b"\x1a\x0bTest User 1",
+        )]);
+        assert_writes(&[(
+            OwnedBMPString::new(vec![0x00, 0x61]).unwrap(),
+            b"\x1e\x02\x00\x61",
+        )]);
+        assert_writes(&[(
+            OwnedUniversalString::new(vec![0x00, 0x00, 0x00, 0x61]).unwrap(),
+            b"\x1c\x04\x00\x00\x00\x61",
+        )]);
+    }
+
     #[test]
     fn test_write_i64() {
         assert_writes::<i64>(&[
@@ -323,6 +823,102 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_write_owned_integers() {
+        assert_writes(&[(
+            OwnedBigUint::new(vec![0x00, 0xff]).unwrap(),
+            b"\x02\x02\x00\xff",
+        )]);
+        assert_writes(&[(OwnedBigInt::new(vec![0x80]).unwrap(), b"\x02\x01\x80")]);
+    }
+
+    #[test]
+    fn test_write_real() {
+        assert_writes::<Real>(&[
+            (
+                Real::Binary {
+                    mantissa: 0,
+                    exponent: 5,
+                },
+                b"\x09\x00",
+            ),
+            (
+                Real::Binary {
+                    mantissa: 4,
+                    exponent: -2,
+                },
+                b"\x09\x03\x80\x00\x01",
+            ),
+            (
+                Real::Binary {
+                    mantissa: -3,
+                    exponent: -3,
+                },
+                b"\x09\x03\xc0\xfd\x03",
+            ),
+            (
+                Real::Binary {
+                    mantissa: 0x1_0001,
+                    exponent: 300,
+                },
+                b"\x09\x06\x81\x01\x2c\x01\x00\x01",
+            ),
+            (
+                Real::Binary {
+                    mantissa: i64::MIN,
+                    exponent: i32::MAX - 63,
+                },
+                b"\x09\x07\xc3\x04\x7f\xff\xff\xff\x01",
+            ),
+            (
+                Real::Decimal {
+                    mantissa: -150,
+                    exponent: -2,
+                },
+                b"\x09\x08\x03-15.E-1",
+            ),
+            (
+                Real::Decimal {
+                    mantissa: 10,
+                    exponent: -1,
+                },
+                b"\x09\x06\x031.E+0",
+            ),
+            (Real::PlusInfinity, b"\x09\x01\x40"),
+            (Real::MinusInfinity, b"\x09\x01\x41"),
+            (Real::NotANumber, b"\x09\x01\x42"),
+            (Real::MinusZero, b"\x09\x01\x43"),
+        ]);
+
+        assert_writes::<f64>(&[
+            (0.0, b"\x09\x00"),
+            (-0.0, b"\x09\x01\x43"),
+            (1.0, b"\x09\x03\x80\x00\x01"),
+            (-0.375, b"\x09\x03\xc0\xfd\x03"),
+            (0.1, b"\x09\x09\x80\xc9\x0c\xcc\xcc\xcc\xcc\xcc\xcd"),
+            (f64::NAN, b"\x09\x01\x42"),
+        ]);
+
+        // Normalizing these would overflow their exponents.
+        for v in &[
+            Real::Binary {
+                mantissa: 2,
+                exponent: i32::MAX,
+            },
+            Real::Binary {
+                mantissa: i64::MIN,
+                exponent: i32::MAX - 62,
+            },
+            Real::Decimal {
+                mantissa: 10,
+                exponent: i32::MAX,
+            },
+        ] {
+            assert_eq!(v.encoded_len(), None);
+            assert_eq!(write_single(v), Err(WriteError::InvalidValue));
+        }
+    }
+
     #[test]
     fn test_write_object_identifier() {
         assert_writes::<ObjectIdentifier>(&[
@@ -345,6 +941,37 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_write_relative_oid() {
+        assert_writes::<RelativeOid>(&[
+            (
+                RelativeOid::from_string("8.571.1").unwrap(),
+                b"\x0d\x04\x08\x84\x3b\x01",
+            ),
+            (RelativeOid::from_string("0").unwrap(), b"\x0d\x01\x00"),
+        ]);
+    }
+
+    #[test]
+    fn test_write_oid_iri() {
+        assert_writes::<OidIri>(&[(
+            OidIri::new("/ISO/Registration_Authority/19785.CBEFF").unwrap(),
+            b"\x1f\x23\x27/ISO/Registration_Authority/19785.CBEFF",
+        )]);
+        assert_writes::<RelativeOidIri>(&[(
+            RelativeOidIri::new("19785.CBEFF").unwrap(),
+            b"\x1f\x24\x0b19785.CBEFF",
+        )]);
+        assert_writes::<OwnedOidIri>(&[(
+            OwnedOidIri::new(String::from("/2/100")).unwrap(),
+            b"\x1f\x23\x06/2/100",
+        )]);
+        assert_writes::<OwnedRelativeOidIri>(&[(
+            OwnedRelativeOidIri::new(String::from("19785.CBEFF")).unwrap(),
+            b"\x1f\x24\x0b19785.CBEFF",
+        )]);
+    }
+
     #[test]
     fn test_write_bit_string() {
         assert_writes::<BitString>(&[
@@ -373,15 +1000,15 @@ mod tests {
     fn test_write_utctime() {
         assert_writes::<UtcTime>(&[
             (
-                UtcTime::new(Utc.ymd(1991, 5, 6).and_hms(23, 45, 40)).unwrap(),
+                UtcTime::new(DateTime::new(1991, 5, 6, 23, 45, 40).unwrap()).unwrap(),
                 b"\x17\x0d910506234540Z",
             ),
             (
-                UtcTime::new(Utc.timestamp(0, 0)).unwrap(),
+                UtcTime::new(DateTime::new(1970, 1, 1, 0, 0, 0).unwrap()).unwrap(),
                 b"\x17\x0d700101000000Z",
             ),
             (
-                UtcTime::new(Utc.timestamp(1258325776, 0)).unwrap(),
+                UtcTime::new(DateTime::new(2009, 11, 15, 22, 56, 16).unwrap()).unwrap(),
                 b"\x17\x0d091115225616Z",
             ),
         ]);
@@ -391,17 +1018,44 @@ mod tests {
     fn test_write_generalizedtime() {
         assert_writes(&[
             (
-                GeneralizedTime::new(Utc.ymd(1991, 5, 6).and_hms(23, 45, 40)),
+                GeneralizedTime::new(DateTime::new(1991, 5, 6, 23, 45, 40).unwrap()),
                 b"\x18\x0f19910506234540Z",
             ),
             (
-                GeneralizedTime::new(Utc.timestamp(0, 0)),
+                GeneralizedTime::new(DateTime::new(1970, 1, 1, 0, 0, 0).unwrap()),
                 b"\x18\x0f19700101000000Z",
             ),
             (
-                GeneralizedTime::new(Utc.timestamp(1258325776, 0)),
+                GeneralizedTime::new(DateTime::new(2009, 11, 15, 22, 56, 16).unwrap()),
                 b"\x18\x0f20091115225616Z",
             ),
+            (
+                GeneralizedTime::new(
+                    DateTime::new(2009, 11, 15, 22, 56, 16)
+                        .unwrap()
+                        .with_nanosecond(123_456_000)
+                        .unwrap(),
+                ),
+                b"\x18\x1620091115225616.123456Z",
+            ),
+            (
+                GeneralizedTime::new(
+                    DateTime::new(2009, 11, 15, 22, 56, 16)
+                        .unwrap()
+                        .with_nanosecond(500_000_000)
+                        .unwrap(),
+                ),
+                b"\x18\x1120091115225616.5Z",
+            ),
+            (
+                GeneralizedTime::new(
+                    DateTime::new(2009, 11, 15, 22, 56, 16)
+                        .unwrap()
+                        .with_nanosecond(1)
+                        .unwrap(),
+                ),
+                b"\x18\x1920091115225616.000000001Z",
+            ),
         ]);
     }
 
@@ -418,7 +1072,8 @@ mod tests {
         assert_eq!(
             write(|w| {
                 w.write_element(&SequenceWriter::new(&|w: &mut Writer| w.write_element(&())))
-            }),
+            })
+            .unwrap(),
             b"\x30\x02\x05\x00"
         );
         assert_eq!(
@@ -426,7 +1081,8 @@ mod tests {
                 w.write_element(&SequenceWriter::new(&|w: &mut Writer| {
                     w.write_element(&true)
                 }))
-            }),
+            })
+            .unwrap(),
             b"\x30\x03\x01\x01\xff"
         );
 
@@ -434,6 +1090,10 @@ mod tests {
             parse_single::<Sequence>(b"\x30\x06\x01\x01\xff\x02\x01\x06").unwrap(),
             b"\x30\x06\x01\x01\xff\x02\x01\x06",
         )]);
+        assert_writes(&[(
+            parse_single::<OwnedSequence>(b"\x30\x06\x01\x01\xff\x02\x01\x06").unwrap(),
+            b"\x30\x06\x01\x01\xff\x02\x01\x06",
+        )]);
     }
 
     #[test]
@@ -455,7 +1115,7 @@ mod tests {
         assert_writes::<SequenceOfWriter<SequenceWriter, &[SequenceWriter]>>(&[
             (SequenceOfWriter::new(&[]), b"\x30\x00"),
             (
-                SequenceOfWriter::new(&[SequenceWriter::new(&|_w| ())]),
+                SequenceOfWriter::new(&[SequenceWriter::new(&|_w| Ok(()))]),
                 b"\x30\x02\x30\x00",
             ),
             (
@@ -472,6 +1132,32 @@ mod tests {
         )]);
     }
 
+    #[test]
+    fn test_write_vec() {
+        assert_writes::<Vec<u8>>(&[
+            (vec![], b"\x30\x00"),
+            (
+                vec![1, 2, 3],
+                b"\x30\x09\x02\x01\x01\x02\x01\x02\x02\x01\x03",
+            ),
+        ]);
+        assert_writes::<Vec<Vec<bool>>>(&[(
+            vec![vec![true], vec![]],
+            b"\x30\x07\x30\x03\x01\x01\xff\x30\x00",
+        )]);
+    }
+
+    #[test]
+    fn test_write_boxed_slice() {
+        assert_writes::<Box<[u8]>>(&[
+            (vec![].into_boxed_slice(), b"\x30\x00"),
+            (
+                vec![1, 2].into_boxed_slice(),
+                b"\x30\x06\x02\x01\x01\x02\x01\x02",
+            ),
+        ]);
+    }
+
     #[test]
     fn test_write_set_of() {
         assert_writes::<SetOfWriter<u8, &[u8]>>(&[
@@ -505,6 +1191,18 @@ mod tests {
         )]);
     }
 
+    #[test]
+    fn test_write_owned_set_of() {
+        assert_writes::<OwnedSetOf<u64>>(&[
+            (OwnedSetOf::new(vec![]), b"\x31\x00"),
+            (OwnedSetOf::new(vec![3]), b"\x31\x03\x02\x01\x03"),
+            (
+                OwnedSetOf::new(vec![3, 1, 2]),
+                b"\x31\x09\x02\x01\x01\x02\x01\x02\x02\x01\x03",
+            ),
+        ]);
+    }
+
     #[test]
     fn test_write_implicit() {
         #[cfg(feature = "const-generics")]
@@ -512,24 +1210,27 @@ mod tests {
             (Implicit::new(true), b"\x82\x01\xff"),
             (Implicit::new(false), b"\x82\x01\x00"),
         ]);
+        #[cfg(feature = "const-generics")]
+        assert_writes::<Implicit<bool, 200>>(&[(Implicit::new(true), b"\x9f\x81\x48\x01\xff")]);
 
         assert_eq!(
-            write(|w| { w.write_optional_implicit_element(&Some(true), 2) }),
+            write(|w| { w.write_optional_implicit_element(&Some(true), 2) }).unwrap(),
             b"\x82\x01\xff"
         );
         assert_eq!(
-            write(|w| { w.write_optional_explicit_element::<u8>(&None, 2) }),
+            write(|w| { w.write_optional_explicit_element::<u8>(&None, 2) }).unwrap(),
             b""
         );
 
         assert_eq!(
             write(|w| {
-                w.write_optional_implicit_element(&Some(SequenceWriter::new(&|_w| {})), 2)
-            }),
+                w.write_optional_implicit_element(&Some(SequenceWriter::new(&|_w| Ok(()))), 2)
+            })
+            .unwrap(),
             b"\xa2\x00"
         );
         assert_eq!(
-            write(|w| { w.write_optional_explicit_element::<SequenceWriter>(&None, 2) }),
+            write(|w| { w.write_optional_explicit_element::<SequenceWriter>(&None, 2) }).unwrap(),
             b""
         );
     }
@@ -543,11 +1244,11 @@ mod tests {
         ]);
 
         assert_eq!(
-            write(|w| { w.write_optional_explicit_element(&Some(true), 2) }),
+            write(|w| { w.write_optional_explicit_element(&Some(true), 2) }).unwrap(),
             b"\xa2\x03\x01\x01\xff"
         );
         assert_eq!(
-            write(|w| { w.write_optional_explicit_element::<u8>(&None, 2) }),
+            write(|w| { w.write_optional_explicit_element::<u8>(&None, 2) }).unwrap(),
             b""
         );
     }
@@ -579,9 +1280,119 @@ mod tests {
 
     #[test]
     fn test_write_tlv() {
-        assert_writes(&[(
-            parse_single::<Tlv>(b"\x01\x01\x00").unwrap(),
-            b"\x01\x01\x00",
-        )]);
+        assert_writes(&[
+            (
+                parse_single::<Tlv>(b"\x01\x01\x00").unwrap(),
+                b"\x01\x01\x00",
+            ),
+            (
+                parse_single::<Tlv>(b"\x9f\x81\x00\x01\x00").unwrap(),
+                b"\x9f\x81\x00\x01\x00",
+            ),
+        ]);
+        assert_eq!(
+            write(|w| {
+                w.write_tlv(Tag::new(0x4321, TagClass::Application, true), |dest| {
+                    dest.push_byte(0)
+                })
+            })
+            .unwrap(),
+            b"\x7f\x81\x86\x21\x01\x00"
+        );
+    }
+
+    #[test]
+    fn test_write_owned_tlv() {
+        let tlv = parse_single::<OwnedTlv>(b"\xbf\x81\x00\x02\x05\x00").unwrap();
+        assert_writes(&[(tlv, b"\xbf\x81\x00\x02\x05\x00")]);
+    }
+
+    #[test]
+    fn test_write_incorrect_data_len() {
+        struct Incorrect;
+
+        impl SimpleAsn1Writable<'_> for Incorrect {
+            const TAG: Tag = Tag::primitive(0x04);
+            fn write_data(&self, dest: &mut WriteBuf) -> WriteResult {
+                dest.push_slice(b"abc")
+            }
+
+            fn data_len(&self) -> Option<usize> {
+                Some(2)
+            }
+        }
+
+        assert_eq!(write_single(&Incorrect), Err(WriteError::InvalidValue));
+    }
+
+    #[test]
+    fn test_write_with_limit() {
+        assert_eq!(write_single_with_limit(&true, 3).unwrap(), b"\x01\x01\xff");
+        assert_eq!(
+            write_single_with_limit(&true, 2),
+            Err(WriteError::LimitExceeded)
+        );
+        assert_eq!(
+            write_with_limit(4, |w| {
+                w.write_element(&())?;
+                w.write_element(&())
+            }),
+            Ok(vec![0x05, 0x00, 0x05, 0x00])
+        );
+
+        // The limit applies to the long form of a length, which is inserted
+        // after the value is written, and to `SET OF` elements, which are
+        // sorted before being written.
+        let data = vec![0u8; 128];
+        assert_eq!(
+            write_single_with_limit(&data.as_slice(), 131)
+                .unwrap()
+                .len(),
+            131
+        );
+        assert_eq!(
+            write_single_with_limit(&data.as_slice(), 130),
+            Err(WriteError::LimitExceeded)
+        );
+        let set = SetOfWriter::new(&[2u8, 1][..]);
+        assert_eq!(write_single_with_limit(&set, 8).unwrap().len(), 8);
+        assert_eq!(
+            write_single_with_limit(&set, 7),
+            Err(WriteError::LimitExceeded)
+        );
+    }
+
+    #[test]
+    fn test_write_to_slice() {
+        let mut buf = [0; 4];
+        assert_eq!(
+            write_to_slice(&mut buf, |w| {
+                w.write_element(&())?;
+                w.write_element(&())
+            }),
+            Ok(4)
+        );
+        assert_eq!(&buf, b"\x05\x00\x05\x00");
+
+        // Nothing is written when the length is known not to fit.
+        let mut buf = [0; 2];
+        assert_eq!(
+            write_single_to_slice(&true, &mut buf),
+            Err(WriteError::BufferTooSmall)
+        );
+        assert_eq!(buf, [0, 0]);
+
+        // The long form of a length is inserted after the value is written,
+        // and must fit too.
+        let data = [0u8; 128];
+        let write_unknown_length =
+            |w: &mut Writer| w.write_tlv(Tag::primitive(0x04), |dest| dest.push_slice(&data));
+        let mut buf = [0; 131];
+        assert_eq!(write_to_slice(&mut buf, write_unknown_length), Ok(131));
+        assert_eq!(&buf[..3], b"\x04\x81\x80");
+        assert_eq!(
+            write_to_slice(&mut buf[..130], write_unknown_length),
+            Err(WriteError::BufferTooSmall)
+        );
     }
 }
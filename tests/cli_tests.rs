@@ -0,0 +1,135 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Runs the `asn1` binary with `input` on stdin, returning whether it
+// succeeded, and its stdout and stderr.
+fn run(args: &[&str], input: &[u8]) -> (bool, Vec<u8>, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_asn1"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.success(),
+        output.stdout,
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+const PEM: &[u8] = b"-----BEGIN TEST-----
+MAkCAQUxBAQCBQA=
+-----END TEST-----
+";
+const DER: &[u8] = b"\x30\x09\x02\x01\x05\x31\x04\x04\x02\x05\x00";
+
+#[test]
+fn test_dump() {
+    let expected = "SEQUENCE {
+  INTEGER 5
+  SET {
+    OCTET STRING, encapsulates {
+      NULL
+    }
+  }
+}
+";
+    for input in &[
+        DER,
+        PEM,
+        b"30 09 02 01 05 31 04 04 02 05 00\n",
+        b"MAkCAQUxBAQCBQA=\n",
+    ] {
+        let (success, stdout, _) = run(&["dump", "--no-offsets"], input);
+        assert!(success);
+        assert_eq!(String::from_utf8(stdout).unwrap(), expected);
+    }
+
+    let (success, stdout, _) = run(
+        &["dump", "--no-offsets", "--ber"],
+        b"\x30\x80\x05\x00\x00\x00",
+    );
+    assert!(success);
+    assert_eq!(
+        String::from_utf8(stdout).unwrap(),
+        "SEQUENCE {\n  NULL\n}\n"
+    );
+}
+
+#[test]
+fn test_validate() {
+    let (success, stdout, _) = run(&["validate"], DER);
+    assert!(success);
+    assert_eq!(stdout, b"OK\n");
+
+    // A `SET`'s elements are ordered by their tags, which isn't always the
+    // order of their encodings.
+    let (success, stdout, _) = run(
+        &["validate", "--inform", "der"],
+        b"\x31\x08\xa0\x03\x02\x01\x01\x81\x01\xff",
+    );
+    assert!(success);
+    assert_eq!(stdout, b"OK\n");
+
+    for (input, error) in &[
+        (
+            &b"\x30\x06\x02\x02\x00\x01\x05\x00"[..],
+            "asn1: invalid DER at offset 2: InvalidValue (ASN.1 parsing error: invalid value)\n",
+        ),
+        (
+            b"\x30\x04\x05\x00\x05\x01",
+            "asn1: invalid DER at offset 4: ShortData (ASN.1 parsing error: short data)\n",
+        ),
+        (
+            b"\x31\x06\x02\x01\x02\x02\x01\x01",
+            "asn1: invalid DER at offset 0: InvalidSetOrdering (ASN.1 parsing error: SET value was ordered incorrectly)\n",
+        ),
+        (
+            b"\x24\x04\x04\x02\x00\x00",
+            "asn1: invalid DER at offset 0: InvalidValue (ASN.1 parsing error: invalid value)\n",
+        ),
+    ] {
+        let (success, _, stderr) = run(&["validate", "--inform", "der"], input);
+        assert!(!success);
+        assert_eq!(&stderr, error);
+    }
+}
+
+#[test]
+fn test_extract() {
+    let (success, stdout, _) = run(&["extract", "1", "--outform", "hex"], PEM);
+    assert!(success);
+    assert_eq!(stdout, b"310404020500\n");
+
+    let (success, stdout, _) = run(&["extract", "1.0.0"], DER);
+    assert!(success);
+    assert_eq!(stdout, b"\x05\x00");
+
+    let (success, _, stderr) = run(&["extract", "1.2"], DER);
+    assert!(!success);
+    assert_eq!(stderr, "asn1: no element at path 1.2\n");
+}
+
+#[test]
+fn test_convert() {
+    let (success, stdout, _) = run(&["convert"], PEM);
+    assert!(success);
+    assert_eq!(stdout, DER);
+
+    let (success, stdout, _) = run(&["convert", "--outform", "pem", "--label", "TEST"], DER);
+    assert!(success);
+    assert_eq!(stdout, PEM);
+
+    let (success, stdout, _) = run(&["convert", "--outform", "base64"], PEM);
+    assert!(success);
+    assert_eq!(stdout, b"MAkCAQUxBAQCBQA=\n");
+
+    let (success, _, stderr) = run(&["convert", "--outform", "pem"], DER);
+    assert!(!success);
+    assert_eq!(stderr, "asn1: --label is required for PEM output\n");
+}
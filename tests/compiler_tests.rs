@@ -0,0 +1,85 @@
+#![cfg(feature = "derive")]
+
+// The output of `asn1_compiler` for `asn1_compiler/tests/modules/pkix.asn1`,
+// which `asn1_compiler`'s tests keep up to date.
+#[allow(dead_code)]
+mod generated {
+    include!("../asn1_compiler/tests/modules/pkix.rs");
+}
+
+use generated::{example, pkix1_explicit88, pkix1_implicit88};
+use std::fmt;
+
+fn assert_roundtrips<T>(value: T, der_bytes: &[u8])
+where
+    T: for<'a> asn1::Asn1Readable<'a> + for<'a> asn1::Asn1Writable<'a> + PartialEq + fmt::Debug,
+{
+    assert_eq!(asn1::parse_single::<T>(der_bytes), Ok(value));
+    let value = asn1::parse_single::<T>(der_bytes).unwrap();
+    assert_eq!(asn1::write_single(&value).unwrap(), der_bytes);
+}
+
+#[test]
+fn test_constants() {
+    assert_eq!(
+        pkix1_explicit88::ID_AT_COMMON_NAME,
+        asn1::ObjectIdentifier::from_string("2.5.4.3").unwrap()
+    );
+    assert_eq!(
+        pkix1_implicit88::ID_KP,
+        asn1::ObjectIdentifier::from_string("1.3.6.1.5.5.7.3").unwrap()
+    );
+    assert_eq!(pkix1_explicit88::UB_COMMON_NAME, 64);
+    assert_eq!(pkix1_explicit88::VERSION_V3, 2);
+    assert_eq!(pkix1_implicit88::KEY_USAGE_KEY_ENCIPHERMENT, 2);
+}
+
+#[test]
+fn test_extension() {
+    let basic_constraints = pkix1_implicit88::BasicConstraints {
+        c_a: true,
+        path_len_constraint: None,
+    };
+    assert_roundtrips(basic_constraints, b"\x30\x03\x01\x01\xff");
+
+    assert_roundtrips(
+        pkix1_explicit88::Extension {
+            extn_id: pkix1_implicit88::ID_CE_BASIC_CONSTRAINTS,
+            critical: false,
+            extn_value: asn1::OwnedOctetString::new(b"\x30\x03\x01\x01\xff".to_vec()),
+        },
+        b"\x30\x0c\x06\x03\x55\x1d\x13\x04\x05\x30\x03\x01\x01\xff",
+    );
+}
+
+#[test]
+fn test_implicit_tags() {
+    assert_roundtrips(
+        pkix1_implicit88::GeneralSubtree {
+            base: pkix1_implicit88::GeneralName::DNSName(
+                asn1::OwnedIA5String::new("example.com".to_string()).unwrap(),
+            ),
+            minimum: 0,
+            maximum: Some(3),
+        },
+        b"\x30\x10\x82\x0bexample.com\x81\x01\x03",
+    );
+}
+
+#[test]
+fn test_automatic_tags() {
+    assert_roundtrips(
+        example::Message {
+            id: asn1::OwnedOctetString::new(vec![1, 2]),
+            flags: None,
+            body: example::MessageBody::Text("hi".to_string()),
+            entries: None,
+            options: Some(example::Options {
+                verbose: true,
+                priority: -1,
+            }),
+            count: None,
+        },
+        b"\x30\x0f\x80\x02\x01\x02\xa2\x04\x80\x02hi\xa4\x03\x81\x01\xff",
+    );
+}
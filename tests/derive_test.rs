@@ -12,8 +12,13 @@ fn assert_roundtrips<
         let parsed = asn1::parse_single::<T>(der_bytes);
         assert_eq!(value, &parsed);
         if let Ok(v) = value {
-            let result = asn1::write_single(v);
+            let result = asn1::write_single(v).unwrap();
             assert_eq!(&result, der_bytes);
+            assert_eq!(asn1::Asn1Writable::encoded_len(v), Some(result.len()));
+
+            let mut buf = vec![0; result.len()];
+            assert_eq!(asn1::write_single_to_slice(v, &mut buf), Ok(result.len()));
+            assert_eq!(buf, result);
         }
     }
 }
@@ -32,6 +37,32 @@ fn test_struct_no_fields() {
     ])
 }
 
+#[test]
+fn test_struct_zero_sized_fields() {
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct Empty {}
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct Inner {
+        e: Empty,
+    }
+
+    // `a` and `b` are both zero-sized, so they share an address.
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct Outer {
+        a: Empty,
+        b: Inner,
+    }
+
+    assert_roundtrips(&[(
+        Ok(Outer {
+            a: Empty {},
+            b: Inner { e: Empty {} },
+        }),
+        b"\x30\x06\x30\x00\x30\x02\x30\x00",
+    )]);
+}
+
 #[test]
 fn test_struct_simple_fields() {
     #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
@@ -300,7 +331,7 @@ fn test_enum() {
         (Ok(BasicChoice::B(())), b"\x05\x00"),
         (
             Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
-                actual: 4,
+                actual: asn1::Tag::primitive(4),
             })),
             b"\x04\x00",
         ),
@@ -326,7 +357,7 @@ fn test_enum_lifetimes() {
         (Ok(LifetimesChoice::B(b"lol")), b"\x04\x03lol"),
         (
             Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
-                actual: 5,
+                actual: asn1::Tag::primitive(5),
             })),
             b"\x05\x00",
         ),
@@ -353,7 +384,7 @@ fn test_enum_explicit() {
         (Ok(ExplicitChoice::B(b"lol")), b"\x04\x03lol"),
         (
             Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
-                actual: 5,
+                actual: asn1::Tag::primitive(5),
             })),
             b"\x05\x00",
         ),
@@ -364,6 +395,21 @@ fn test_enum_explicit() {
         (Ok(Some(ExplicitChoice::B(b"lol"))), b"\x04\x03lol"),
         (Ok(None), b""),
     ]);
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, PartialEq, Debug)]
+    enum NestedChoice<'a> {
+        #[explicit(1)]
+        Inner(ExplicitChoice<'a>),
+        C(bool),
+    }
+
+    assert_roundtrips(&[
+        (
+            Ok(NestedChoice::Inner(ExplicitChoice::B(b"lol"))),
+            b"\xa1\x05\x04\x03lol",
+        ),
+        (Ok(NestedChoice::C(true)), b"\x01\x01\xff"),
+    ]);
 }
 
 #[test]
@@ -386,7 +432,7 @@ fn test_enum_implicit() {
         (Ok(ImplicitChoice::C(b"lol")), b"\x04\x03lol"),
         (
             Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
-                actual: 5,
+                actual: asn1::Tag::primitive(5),
             })),
             b"\x05\x00",
         ),
@@ -448,10 +494,10 @@ fn test_required_implicit() {
             b"\x30\x00",
         ),
         (
-            Err(
-                asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag { actual: 11 })
-                    .add_location(asn1::ParseLocation::Field("RequiredImplicit::value")),
-            ),
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
+                actual: asn1::Tag::primitive(11),
+            })
+            .add_location(asn1::ParseLocation::Field("RequiredImplicit::value"))),
             b"\x30\x03\x0b\x01\x00",
         ),
     ]);
@@ -475,12 +521,543 @@ fn test_required_explicit() {
                 .add_location(asn1::ParseLocation::Field("RequiredExplicit::value"))),
             b"\x30\x00",
         ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
+                actual: asn1::Tag::primitive(11),
+            })
+            .add_location(asn1::ParseLocation::Field("RequiredExplicit::value"))),
+            b"\x30\x03\x0b\x01\x00",
+        ),
+    ]);
+}
+
+#[test]
+fn test_high_tag_numbers() {
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, PartialEq, Debug)]
+    struct HighTags {
+        #[implicit(31)]
+        a: Option<u8>,
+        #[explicit(300, required)]
+        b: bool,
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, PartialEq, Debug)]
+    enum HighTagChoice {
+        #[implicit(1000)]
+        A(u64),
+        #[explicit(40)]
+        B(bool),
+    }
+
+    assert_roundtrips(&[
+        (
+            Ok(HighTags {
+                a: Some(3),
+                b: true,
+            }),
+            b"\x30\x0b\x9f\x1f\x01\x03\xbf\x82\x2c\x03\x01\x01\xff",
+        ),
+        (
+            Ok(HighTags { a: None, b: false }),
+            b"\x30\x07\xbf\x82\x2c\x03\x01\x01\x00",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
+                actual: asn1::Tag::new(31, asn1::TagClass::ContextSpecific, true),
+            })
+            .add_location(asn1::ParseLocation::Field("HighTags::b"))),
+            b"\x30\x05\xbf\x1f\x02\x30\x00",
+        ),
+    ]);
+
+    assert_roundtrips(&[
+        (Ok(HighTagChoice::A(17)), b"\x9f\x87\x68\x01\x11"),
+        (Ok(HighTagChoice::B(true)), b"\xbf\x28\x03\x01\x01\xff"),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
+                actual: asn1::Tag::new(1000, asn1::TagClass::ContextSpecific, true),
+            })),
+            b"\xbf\x87\x68\x00",
+        ),
+    ]);
+}
+
+#[test]
+fn test_set() {
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, PartialEq, Debug)]
+    #[asn1(set)]
+    struct SetFields<'a> {
+        b: bool,
+        #[implicit(1)]
+        c: Option<u64>,
+        a: u64,
+        #[explicit(0, required)]
+        d: &'a [u8],
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, PartialEq, Debug)]
+    #[asn1(set)]
+    struct DefaultSet(#[default(3u64)] u64, Option<bool>);
+
+    assert_roundtrips(&[
+        (
+            Ok(SetFields {
+                a: 5,
+                b: true,
+                c: Some(7),
+                d: b"x",
+            }),
+            b"\x31\x0e\x01\x01\xff\x02\x01\x05\xa0\x03\x04\x01x\x81\x01\x07",
+        ),
+        (
+            Ok(SetFields {
+                a: 5,
+                b: true,
+                c: None,
+                d: b"x",
+            }),
+            b"\x31\x0b\x01\x01\xff\x02\x01\x05\xa0\x03\x04\x01x",
+        ),
         (
             Err(
-                asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag { actual: 11 })
-                    .add_location(asn1::ParseLocation::Field("RequiredExplicit::value")),
+                asn1::ParseError::new(asn1::ParseErrorKind::DuplicateSetElement)
+                    .add_location(asn1::ParseLocation::Field("SetFields::b")),
             ),
-            b"\x30\x03\x0b\x01\x00",
+            b"\x31\x09\x01\x01\xff\x02\x01\x05\x01\x01\x00",
+        ),
+        (
+            Err(
+                asn1::ParseError::new(asn1::ParseErrorKind::MissingSetElement)
+                    .add_location(asn1::ParseLocation::Field("SetFields::d")),
+            ),
+            b"\x31\x06\x01\x01\xff\x02\x01\x05",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
+                actual: asn1::Tag::primitive(5),
+            })),
+            b"\x31\x0d\x01\x01\xff\x02\x01\x05\xa0\x03\x04\x01x\x05\x00",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
+                actual: asn1::Tag::primitive(2),
+            })
+            .add_location(asn1::ParseLocation::Field("SetFields::d"))),
+            b"\x31\x0b\x01\x01\xff\x02\x01\x05\xa0\x03\x02\x01\x05",
+        ),
+    ]);
+
+    // Elements may appear in any order when parsing.
+    assert_eq!(
+        asn1::parse_single::<SetFields>(
+            b"\x31\x0e\x81\x01\x07\xa0\x03\x04\x01x\x02\x01\x05\x01\x01\xff"
+        ),
+        Ok(SetFields {
+            a: 5,
+            b: true,
+            c: Some(7),
+            d: b"x",
+        })
+    );
+
+    assert_roundtrips(&[
+        (Ok(DefaultSet(3, None)), b"\x31\x00"),
+        (
+            Ok(DefaultSet(5, Some(true))),
+            b"\x31\x06\x01\x01\xff\x02\x01\x05",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::EncodedDefault)
+                .add_location(asn1::ParseLocation::Field("DefaultSet::0"))),
+            b"\x31\x03\x02\x01\x03",
+        ),
+    ]);
+}
+
+#[test]
+fn test_owned() {
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, PartialEq, Debug)]
+    struct Owned {
+        name: String,
+        serial: asn1::OwnedBigUint,
+        #[implicit(0)]
+        key_id: Option<asn1::OwnedOctetString>,
+        values: Vec<u64>,
+        any: asn1::OwnedTlv,
+        params: asn1::OwnedSequence,
+    }
+
+    fn assert_static_send<T: Send + 'static>(_: &T) {}
+
+    let data =
+        b"\x30\x18\x0c\x03abc\x02\x02\x00\xff\x80\x01\x07\x30\x03\x02\x01\x05\x05\x00\x30\x03\x02\x01\x06".to_vec();
+    let value = asn1::parse_single::<Owned>(&data).unwrap();
+    drop(data);
+    assert_static_send(&value);
+
+    assert_eq!(value.name, "abc");
+    assert_eq!(value.serial.as_bytes(), b"\x00\xff");
+    assert_eq!(
+        value.key_id.as_ref().map(|v| v.as_bytes()),
+        Some(&b"\x07"[..])
+    );
+    assert_eq!(value.values, vec![5]);
+    assert_eq!(value.any.as_tlv().tag(), asn1::Tag::primitive(0x05));
+    assert_eq!(value.params.parse(|p| p.read_element::<u64>()), Ok(6));
+    assert_eq!(
+        asn1::write_single(&value).unwrap(),
+        b"\x30\x18\x0c\x03abc\x02\x02\x00\xff\x80\x01\x07\x30\x03\x02\x01\x05\x05\x00\x30\x03\x02\x01\x06"
+    );
+}
+
+#[test]
+fn test_sequence_of_and_set_of_fields() {
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, PartialEq, Debug)]
+    struct Collections {
+        seq: Vec<u8>,
+        boxed: Box<[bool]>,
+        set: asn1::OwnedSetOf<u8>,
+    }
+
+    assert_roundtrips(&[(
+        Ok(Collections {
+            seq: vec![2, 1],
+            boxed: vec![true].into_boxed_slice(),
+            set: asn1::OwnedSetOf::new(vec![1, 2]),
+        }),
+        b"\x30\x15\x30\x06\x02\x01\x02\x02\x01\x01\x30\x03\x01\x01\xff\x31\x06\x02\x01\x01\x02\x01\x02",
+    )]);
+
+    // SET OF elements are sorted when written.
+    let value = Collections {
+        seq: vec![],
+        boxed: vec![].into_boxed_slice(),
+        set: asn1::OwnedSetOf::new(vec![2, 1]),
+    };
+    assert_eq!(
+        asn1::write_single(&value).unwrap(),
+        b"\x30\x0c\x30\x00\x30\x00\x31\x06\x02\x01\x01\x02\x01\x02"
+    );
+}
+
+#[test]
+fn test_defined_by() {
+    const SHA256_WITH_RSA: asn1::ObjectIdentifier = asn1::oid!(1, 2, 840, 113549, 1, 1, 11);
+
+    #[derive(asn1::Asn1DefinedByRead, asn1::Asn1DefinedByWrite, Debug, PartialEq)]
+    enum AlgorithmParameters<'a> {
+        #[defined_by(SHA256_WITH_RSA)]
+        Sha256WithRsa(asn1::Null),
+        #[defined_by(asn1::oid!(1, 2, 840, 10045, 4, 3, 2))]
+        EcdsaWithSha256,
+        #[defined_by(unknown)]
+        Other(asn1::ObjectIdentifier, Option<asn1::Tlv<'a>>),
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct AlgorithmIdentifier<'a> {
+        algorithm: asn1::DefinedByMarker,
+        #[defined_by(algorithm)]
+        params: AlgorithmParameters<'a>,
+    }
+
+    assert_roundtrips(&[
+        (
+            Ok(AlgorithmIdentifier {
+                algorithm: asn1::DefinedByMarker,
+                params: AlgorithmParameters::Sha256WithRsa(()),
+            }),
+            b"\x30\x0d\x06\x09\x2a\x86\x48\x86\xf7\x0d\x01\x01\x0b\x05\x00",
+        ),
+        (
+            Ok(AlgorithmIdentifier {
+                algorithm: asn1::DefinedByMarker,
+                params: AlgorithmParameters::EcdsaWithSha256,
+            }),
+            b"\x30\x0a\x06\x08\x2a\x86\x48\xce\x3d\x04\x03\x02",
+        ),
+        (
+            Ok(AlgorithmIdentifier {
+                algorithm: asn1::DefinedByMarker,
+                params: AlgorithmParameters::Other(
+                    asn1::oid!(1, 2, 3),
+                    Some(asn1::parse_single(b"\x02\x01\x05").unwrap()),
+                ),
+            }),
+            b"\x30\x07\x06\x02\x2a\x03\x02\x01\x05",
+        ),
+        (
+            Ok(AlgorithmIdentifier {
+                algorithm: asn1::DefinedByMarker,
+                params: AlgorithmParameters::Other(asn1::oid!(1, 2, 3), None),
+            }),
+            b"\x30\x04\x06\x02\x2a\x03",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::ShortData)
+                .add_location(asn1::ParseLocation::Field(
+                    "AlgorithmParameters::Sha256WithRsa",
+                ))
+                .add_location(asn1::ParseLocation::Field("AlgorithmIdentifier::params"))),
+            b"\x30\x0b\x06\x09\x2a\x86\x48\x86\xf7\x0d\x01\x01\x0b",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::ExtraData)),
+            b"\x30\x0c\x06\x08\x2a\x86\x48\xce\x3d\x04\x03\x02\x05\x00",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::UnexpectedTag {
+                actual: asn1::Tag::primitive(0x02),
+            })
+            .add_location(asn1::ParseLocation::Field("AlgorithmIdentifier::algorithm"))),
+            b"\x30\x03\x02\x01\x05",
+        ),
+    ]);
+
+    #[derive(asn1::Asn1DefinedByRead, asn1::Asn1DefinedByWrite, Debug, PartialEq)]
+    enum KnownParameters {
+        #[defined_by(SHA256_WITH_RSA)]
+        Sha256WithRsa(asn1::Null),
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct KnownAlgorithmIdentifier {
+        algorithm: asn1::DefinedByMarker,
+        #[defined_by(algorithm)]
+        params: KnownParameters,
+    }
+
+    assert_roundtrips(&[
+        (
+            Ok(KnownAlgorithmIdentifier {
+                algorithm: asn1::DefinedByMarker,
+                params: KnownParameters::Sha256WithRsa(()),
+            }),
+            b"\x30\x0d\x06\x09\x2a\x86\x48\x86\xf7\x0d\x01\x01\x0b\x05\x00",
+        ),
+        (
+            Err(
+                asn1::ParseError::new(asn1::ParseErrorKind::UnknownDefinedBy).add_location(
+                    asn1::ParseLocation::Field("KnownAlgorithmIdentifier::params"),
+                ),
+            ),
+            b"\x30\x04\x06\x02\x2a\x03",
+        ),
+    ]);
+}
+
+#[test]
+fn test_defined_by_long_oid() {
+    // Too long to be stored inline.
+    #[derive(asn1::Asn1DefinedByRead, asn1::Asn1DefinedByWrite, Debug, PartialEq)]
+    enum Params {
+        #[defined_by(asn1::oid!(
+            2,
+            25,
+            329800735698586629295641978511506172918,
+            329800735698586629295641978511506172918,
+            329800735698586629295641978511506172918,
+            329800735698586629295641978511506172918
+        ))]
+        Uuid(u64),
+    }
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct Identifier {
+        id: asn1::DefinedByMarker,
+        #[defined_by(id)]
+        params: Params,
+    }
+
+    let value = Identifier {
+        id: asn1::DefinedByMarker,
+        params: Params::Uuid(5),
+    };
+    let data = asn1::write_single(&value).unwrap();
+    assert_eq!(&data[..4], b"\x30\x52\x06\x4d");
+    assert_eq!(&data[data.len() - 3..], b"\x02\x01\x05");
+    assert_eq!(asn1::parse_single::<Identifier>(&data), Ok(value));
+}
+
+#[test]
+fn test_extensible() {
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    #[asn1(extensible)]
+    struct Skipped {
+        a: u64,
+        b: Option<bool>,
+    }
+
+    assert_roundtrips(&[
+        (
+            Ok(Skipped {
+                a: 1,
+                b: Some(true),
+            }),
+            b"\x30\x06\x02\x01\x01\x01\x01\xff",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::ShortData)),
+            b"\x30\x05\x02\x01\x01\x04\x05",
+        ),
+    ]);
+    // Unknown elements are discarded.
+    assert_eq!(
+        asn1::parse_single::<Skipped>(b"\x30\x09\x02\x01\x01\x01\x01\xff\x04\x01a"),
+        Ok(Skipped {
+            a: 1,
+            b: Some(true)
+        })
+    );
+    assert_eq!(
+        asn1::parse_single::<Skipped>(b"\x30\x06\x02\x01\x01\x04\x01a"),
+        Ok(Skipped { a: 1, b: None })
+    );
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct Captured<'a> {
+        a: u64,
+        #[extensions]
+        rest: Vec<asn1::Tlv<'a>>,
+    }
+
+    assert_roundtrips(&[
+        (Ok(Captured { a: 1, rest: vec![] }), b"\x30\x03\x02\x01\x01"),
+        (
+            Ok(Captured {
+                a: 1,
+                rest: vec![
+                    asn1::parse_single(b"\x04\x01a").unwrap(),
+                    asn1::parse_single(b"\x05\x00").unwrap(),
+                ],
+            }),
+            b"\x30\x08\x02\x01\x01\x04\x01a\x05\x00",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::ShortData)
+                .add_location(asn1::ParseLocation::Field("Captured::rest"))),
+            b"\x30\x05\x02\x01\x01\x04\x05",
+        ),
+    ]);
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct CapturedOwned {
+        a: u64,
+        #[extensions]
+        rest: Vec<asn1::OwnedTlv>,
+    }
+
+    assert_roundtrips(&[(
+        Ok(CapturedOwned {
+            a: 1,
+            rest: vec![asn1::parse_single(b"\x04\x01a").unwrap()],
+        }),
+        b"\x30\x06\x02\x01\x01\x04\x01a",
+    )]);
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct CapturedRaw<'a>(u64, #[extensions] &'a [u8]);
+
+    assert_roundtrips(&[
+        (Ok(CapturedRaw(1, b"")), b"\x30\x03\x02\x01\x01"),
+        (
+            Ok(CapturedRaw(1, b"\x04\x01a\x05\x00")),
+            b"\x30\x08\x02\x01\x01\x04\x01a\x05\x00",
+        ),
+    ]);
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    #[asn1(set, extensible)]
+    struct SkippedSet {
+        a: u64,
+        #[implicit(1)]
+        b: Option<bool>,
+    }
+
+    assert_roundtrips(&[(
+        Ok(SkippedSet {
+            a: 1,
+            b: Some(true),
+        }),
+        b"\x31\x06\x02\x01\x01\x81\x01\xff",
+    )]);
+    assert_eq!(
+        asn1::parse_single::<SkippedSet>(b"\x31\x09\x02\x01\x01\x80\x01\x00\x81\x01\xff"),
+        Ok(SkippedSet {
+            a: 1,
+            b: Some(true)
+        })
+    );
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    #[asn1(extensible)]
+    enum ExtensibleChoice<'a> {
+        Integer(u64),
+        #[implicit(0)]
+        Boolean(bool),
+        #[extensions]
+        Unknown(asn1::Tlv<'a>),
+    }
+
+    assert_roundtrips(&[
+        (Ok(ExtensibleChoice::Integer(5)), b"\x02\x01\x05"),
+        (Ok(ExtensibleChoice::Boolean(true)), b"\x80\x01\xff"),
+        (
+            Ok(ExtensibleChoice::Unknown(
+                asn1::parse_single(b"\x0c\x01a").unwrap(),
+            )),
+            b"\x0c\x01a",
+        ),
+        (
+            Ok(ExtensibleChoice::Unknown(
+                asn1::parse_single(b"\xa3\x00").unwrap(),
+            )),
+            b"\xa3\x00",
+        ),
+        (
+            Err(asn1::ParseError::new(asn1::ParseErrorKind::InvalidValue)
+                .add_location(asn1::ParseLocation::Field("ExtensibleChoice::Integer"))),
+            b"\x02\x02\x00\x05",
+        ),
+    ]);
+
+    // An extensible `CHOICE` accepts any tag, so an untagged `OPTIONAL` one
+    // takes whatever element follows it.
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct OptionalChoice<'a> {
+        choice: Option<ExtensibleChoice<'a>>,
+        b: bool,
+    }
+
+    assert_eq!(
+        asn1::parse_single::<OptionalChoice>(b"\x30\x03\x01\x01\xff"),
+        Err(asn1::ParseError::new(asn1::ParseErrorKind::ShortData)
+            .add_location(asn1::ParseLocation::Field("OptionalChoice::b")))
+    );
+
+    #[derive(asn1::Asn1Read, asn1::Asn1Write, Debug, PartialEq)]
+    struct TaggedOptionalChoice<'a> {
+        #[explicit(0)]
+        choice: Option<ExtensibleChoice<'a>>,
+        b: bool,
+    }
+
+    assert_roundtrips(&[
+        (
+            Ok(TaggedOptionalChoice {
+                choice: None,
+                b: true,
+            }),
+            b"\x30\x03\x01\x01\xff",
+        ),
+        (
+            Ok(TaggedOptionalChoice {
+                choice: Some(ExtensibleChoice::Unknown(
+                    asn1::parse_single(b"\x01\x01\x00").unwrap(),
+                )),
+                b: true,
+            }),
+            b"\x30\x08\xa0\x03\x01\x01\x00\x01\x01\xff",
         ),
     ]);
 }
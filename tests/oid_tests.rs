@@ -21,3 +21,46 @@ fn test_match_statement() {
     };
     assert_eq!(result, "abc");
 }
+
+#[test]
+fn test_large_arcs() {
+    const UUID_OID: asn1::ObjectIdentifier =
+        asn1::oid!(2, 25, 329800735698586629295641978511506172918);
+
+    let oid = asn1::ObjectIdentifier::from_string("2.25.329800735698586629295641978511506172918")
+        .unwrap();
+    assert_eq!(oid, UUID_OID);
+    assert!(matches!(oid, UUID_OID));
+    assert_eq!(
+        asn1::parse_single::<asn1::ObjectIdentifier>(&asn1::write_single(&oid).unwrap()),
+        Ok(UUID_OID)
+    );
+}
+
+#[test]
+fn test_long_oid() {
+    const LONG_OID: asn1::ObjectIdentifier = asn1::oid!(
+        1, 2, 840, 113549, 1, 1, 1, 2, 840, 113549, 1, 1, 1, 2, 840, 113549, 1, 1, 1, 2, 840,
+        113549, 1, 1, 1, 2, 840, 113549, 1, 1, 1, 2, 840, 113549, 1, 1
+    );
+
+    let oid = LONG_OID;
+    let s = "1.2.840.113549.1.1.1.2.840.113549.1.1.1.2.840.113549.1.1.1.2.840.113549.1.1.1.2.840.113549.1.1.1.2.840.113549.1.1";
+    assert_eq!(oid, asn1::ObjectIdentifier::from_string(s).unwrap());
+    assert_eq!(oid.to_string(), s);
+    assert_eq!(
+        asn1::parse_single::<asn1::ObjectIdentifier>(&asn1::write_single(&oid).unwrap()),
+        Ok(LONG_OID)
+    );
+}
+
+#[test]
+fn test_const_from_dotted_str() {
+    const OID1: asn1::ObjectIdentifier = asn1::ObjectIdentifier::from_dotted_str("1.2.3.4");
+
+    let result = match asn1::oid!(1, 2, 3, 4) {
+        OID1 => "abc",
+        _ => "def",
+    };
+    assert_eq!(result, "abc");
+}
@@ -2,7 +2,7 @@ fn assert_roundtrips<T>(i: T)
 where
     for<'a> T: asn1::Asn1Writable<'a> + asn1::Asn1Readable<'a> + std::fmt::Debug + PartialEq,
 {
-    let result = asn1::write_single::<T>(&i);
+    let result = asn1::write_single::<T>(&i).unwrap();
     let parsed = asn1::parse_single::<T>(&result).unwrap();
     assert_eq!(parsed, i);
 }